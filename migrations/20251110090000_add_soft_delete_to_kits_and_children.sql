@@ -0,0 +1,17 @@
+-- PostgreSQL migration: add soft-delete (trash bin) support to kits and their children
+-- - `deleted_at IS NULL` means the row is live
+-- - Children trashed together with their parent share the parent's `deleted_at`,
+--   which lets a restore bring back exactly the rows that were trashed with it
+
+ALTER TABLE kits ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE runners ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE sub_assemblies ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE kit_parts ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE kit_part_requirements ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Partial indexes: only trashed rows are looked up by deleted_at (trash listing and purge)
+CREATE INDEX IF NOT EXISTS idx_kits_deleted_at ON kits(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_runners_deleted_at ON runners(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_sub_assemblies_deleted_at ON sub_assemblies(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_kit_parts_deleted_at ON kit_parts(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_kit_part_requirements_deleted_at ON kit_part_requirements(deleted_at) WHERE deleted_at IS NOT NULL;
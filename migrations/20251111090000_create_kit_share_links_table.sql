@@ -0,0 +1,21 @@
+-- PostgreSQL migration: create kit_share_links table for public read-only kit links
+-- - token is random (two UUIDv4 = 244 random bits) and is the only thing exposed publicly
+-- - revoked_at / expires_at are NULL while the link is active / has no expiry
+
+CREATE TABLE IF NOT EXISTS kit_share_links (
+    id BIGSERIAL PRIMARY KEY,
+    token TEXT NOT NULL DEFAULT replace(gen_random_uuid()::text || gen_random_uuid()::text, '-', ''),
+    kit_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT kit_share_links_token_key UNIQUE (token),
+    CONSTRAINT kit_share_links_kit_id_fkey
+        FOREIGN KEY (kit_id) REFERENCES kits(id) ON DELETE CASCADE,
+    CONSTRAINT kit_share_links_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_kit_share_links_kit_id ON kit_share_links(kit_id);
@@ -0,0 +1,43 @@
+-- PostgreSQL migration: collaborative kits
+-- - kit_members: who can access a kit and with which role (owner / editor / viewer)
+-- - updated_by: the user who made the latest change to each row (user_id stays the creator)
+
+CREATE TABLE IF NOT EXISTS kit_members (
+    kit_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    role TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT kit_members_pkey PRIMARY KEY (kit_id, user_id),
+    CONSTRAINT kit_members_role_check CHECK (role IN ('owner', 'editor', 'viewer')),
+    CONSTRAINT kit_members_kit_id_fkey
+        FOREIGN KEY (kit_id) REFERENCES kits(id) ON DELETE CASCADE,
+    CONSTRAINT kit_members_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_kit_members_user_id ON kit_members(user_id);
+
+-- Backfill: every existing kit is owned by its creator
+INSERT INTO kit_members (kit_id, user_id, role)
+SELECT id, user_id, 'owner' FROM kits
+ON CONFLICT (kit_id, user_id) DO NOTHING;
+
+-- Audit column on kits and every child table
+ALTER TABLE kits ADD COLUMN IF NOT EXISTS updated_by BIGINT
+    CONSTRAINT kits_updated_by_fkey REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE runners ADD COLUMN IF NOT EXISTS updated_by BIGINT
+    CONSTRAINT runners_updated_by_fkey REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE sub_assemblies ADD COLUMN IF NOT EXISTS updated_by BIGINT
+    CONSTRAINT sub_assemblies_updated_by_fkey REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE kit_parts ADD COLUMN IF NOT EXISTS updated_by BIGINT
+    CONSTRAINT kit_parts_updated_by_fkey REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE kit_part_requirements ADD COLUMN IF NOT EXISTS updated_by BIGINT
+    CONSTRAINT kit_part_requirements_updated_by_fkey REFERENCES users(id) ON DELETE SET NULL;
+
+UPDATE kits SET updated_by = user_id WHERE updated_by IS NULL;
+UPDATE runners SET updated_by = user_id WHERE updated_by IS NULL;
+UPDATE sub_assemblies SET updated_by = user_id WHERE updated_by IS NULL;
+UPDATE kit_parts SET updated_by = user_id WHERE updated_by IS NULL;
+UPDATE kit_part_requirements SET updated_by = user_id WHERE updated_by IS NULL;
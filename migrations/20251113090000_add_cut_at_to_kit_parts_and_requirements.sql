@@ -0,0 +1,17 @@
+-- Track when a kit part / requirement was cut so the dashboard can chart progress over time
+
+ALTER TABLE kit_parts ADD COLUMN IF NOT EXISTS cut_at TIMESTAMPTZ;
+ALTER TABLE kit_part_requirements ADD COLUMN IF NOT EXISTS cut_at TIMESTAMPTZ;
+
+-- Backfill: best guess for rows that were already cut
+UPDATE kit_parts SET cut_at = updated_at WHERE is_cut AND cut_at IS NULL;
+
+UPDATE kit_part_requirements kpr
+SET cut_at = kp.updated_at
+FROM kit_parts kp
+WHERE kp.id = kpr.kit_part_id AND kpr.is_cut AND kpr.cut_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_kit_parts_cut_at
+    ON kit_parts(cut_at) WHERE cut_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_kit_part_requirements_cut_at
+    ON kit_part_requirements(cut_at) WHERE cut_at IS NOT NULL;
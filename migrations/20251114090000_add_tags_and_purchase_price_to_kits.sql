@@ -0,0 +1,9 @@
+-- Extra kit metadata used by the CSV import (and editable through the normal kit endpoints)
+
+ALTER TABLE kits ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE kits ADD COLUMN IF NOT EXISTS purchase_price DOUBLE PRECISION;
+
+ALTER TABLE kits ADD CONSTRAINT kits_purchase_price_non_negative
+    CHECK (purchase_price IS NULL OR purchase_price >= 0);
+
+CREATE INDEX IF NOT EXISTS idx_kits_tags ON kits USING GIN (tags);
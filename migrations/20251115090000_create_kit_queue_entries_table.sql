@@ -0,0 +1,25 @@
+-- Per-user build queue: ordered by (pinned DESC, rank) where rank is a fractional index string,
+-- so moving one entry only rewrites that entry's rank
+
+CREATE TABLE IF NOT EXISTS kit_queue_entries (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    kit_id BIGINT NOT NULL,
+    -- "C" collation: compare byte-by-byte so ordering matches the rank generator
+    rank TEXT COLLATE "C" NOT NULL,
+    pinned BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT kit_queue_entries_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT kit_queue_entries_kit_id_fkey
+        FOREIGN KEY (kit_id) REFERENCES kits(id) ON DELETE CASCADE,
+
+    -- A kit appears at most once in each user's queue
+    CONSTRAINT kit_queue_entries_user_kit_key UNIQUE (user_id, kit_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_kit_queue_entries_user_order
+    ON kit_queue_entries(user_id, pinned DESC, rank);
+CREATE INDEX IF NOT EXISTS idx_kit_queue_entries_kit_id ON kit_queue_entries(kit_id);
@@ -0,0 +1,17 @@
+-- Runner names must be unique within a kit (A, B1, B2, ...).
+-- Partial index: trashed runners do not block re-creating the same name.
+
+-- Existing live duplicates: keep the oldest name, suffix the rest with their id
+UPDATE runners r
+SET name = r.name || '-' || r.id, updated_at = NOW()
+WHERE r.deleted_at IS NULL
+  AND EXISTS (
+      SELECT 1 FROM runners o
+      WHERE o.kit_id = r.kit_id
+        AND o.name = r.name
+        AND o.deleted_at IS NULL
+        AND o.id < r.id
+  );
+
+CREATE UNIQUE INDEX IF NOT EXISTS runners_kit_id_name_key
+    ON runners(kit_id, name) WHERE deleted_at IS NULL;
@@ -0,0 +1,9 @@
+-- Gate inventory per runner: either a count (gates 1..gate_count) or an explicit list of labels.
+-- Both NULL = not defined yet, requirements on that runner are not validated.
+ALTER TABLE runners ADD COLUMN IF NOT EXISTS gate_count INT
+    CONSTRAINT runners_gate_count_check CHECK (gate_count BETWEEN 1 AND 999);
+ALTER TABLE runners ADD COLUMN IF NOT EXISTS gates JSONB
+    CONSTRAINT runners_gates_check CHECK (gates IS NULL OR jsonb_typeof(gates) = 'array');
+
+ALTER TABLE runners ADD CONSTRAINT runners_gate_inventory_mode_check
+    CHECK (gate_count IS NULL OR gates IS NULL);
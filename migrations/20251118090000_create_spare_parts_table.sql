@@ -0,0 +1,24 @@
+-- Notes and tags on leftover (spare) gates. The spare list itself is computed from
+-- the runner gate inventory minus gates referenced by requirements; this table only
+-- stores what the user wrote about a spare.
+CREATE TABLE IF NOT EXISTS spare_parts (
+    id BIGSERIAL PRIMARY KEY,
+    runner_id BIGINT NOT NULL,
+    gate TEXT NOT NULL, -- canonical gate without runner prefix, e.g. '12', '3a'
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    note TEXT,
+    user_id BIGINT NOT NULL,
+    updated_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT spare_parts_runner_id_fkey
+        FOREIGN KEY (runner_id) REFERENCES runners(id) ON DELETE CASCADE,
+    CONSTRAINT spare_parts_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT spare_parts_updated_by_fkey
+        FOREIGN KEY (updated_by) REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT spare_parts_runner_id_gate_key UNIQUE (runner_id, gate)
+);
+
+CREATE INDEX IF NOT EXISTS idx_spare_parts_tags ON spare_parts USING GIN (tags);
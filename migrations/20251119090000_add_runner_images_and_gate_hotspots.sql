@@ -0,0 +1,24 @@
+-- Runner diagram image (file name inside UPLOAD_DIR) and clickable gate hotspots on it.
+ALTER TABLE runners ADD COLUMN IF NOT EXISTS image_path TEXT;
+
+-- Coordinates are relative to the image size (0.0 - 1.0) so they survive re-uploads at a different resolution.
+CREATE TABLE IF NOT EXISTS runner_gate_hotspots (
+    id BIGSERIAL PRIMARY KEY,
+    runner_id BIGINT NOT NULL,
+    gate TEXT NOT NULL, -- canonical gate without runner prefix, e.g. '12', '3a'
+    x DOUBLE PRECISION NOT NULL CHECK (x BETWEEN 0 AND 1),
+    y DOUBLE PRECISION NOT NULL CHECK (y BETWEEN 0 AND 1),
+    polygon JSONB, -- optional outline: [[x, y], ...]
+    user_id BIGINT NOT NULL,
+    updated_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT runner_gate_hotspots_runner_id_fkey
+        FOREIGN KEY (runner_id) REFERENCES runners(id) ON DELETE CASCADE,
+    CONSTRAINT runner_gate_hotspots_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT runner_gate_hotspots_updated_by_fkey
+        FOREIGN KEY (updated_by) REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT runner_gate_hotspots_runner_id_gate_key UNIQUE (runner_id, gate)
+);
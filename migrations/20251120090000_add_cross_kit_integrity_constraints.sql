@@ -0,0 +1,67 @@
+-- Cross-kit integrity: rows that point at each other must live in the same kit
+-- - kit_parts.sub_assembly_id must be a sub-assembly of the kit part's kit
+-- - kit_part_requirements gets its own kit_id so both runner_id and kit_part_id
+--   can be checked with composite foreign keys (id, kit_id)
+-- Moving a runner / sub-assembly to another kit while something still points at it
+-- now fails with a foreign key violation instead of silently linking two kits.
+
+ALTER TABLE sub_assemblies ADD CONSTRAINT sub_assemblies_id_kit_id_key UNIQUE (id, kit_id);
+ALTER TABLE runners ADD CONSTRAINT runners_id_kit_id_key UNIQUE (id, kit_id);
+ALTER TABLE kit_parts ADD CONSTRAINT kit_parts_id_kit_id_key UNIQUE (id, kit_id);
+
+-- Existing cross-kit links cannot be repaired automatically (we cannot tell which
+-- side is right), and deleting them would lose user data, so stop the migration
+-- and let someone fix the listed rows by hand first
+DO $$
+DECLARE
+    bad_ids TEXT;
+BEGIN
+    SELECT string_agg(kp.id::TEXT, ', ' ORDER BY kp.id) INTO bad_ids
+    FROM kit_parts kp
+    JOIN sub_assemblies sa ON sa.id = kp.sub_assembly_id
+    WHERE sa.kit_id <> kp.kit_id;
+
+    IF bad_ids IS NOT NULL THEN
+        RAISE EXCEPTION 'kit_parts linked to a sub-assembly of another kit: %', bad_ids
+            USING HINT = 'Point each kit part at a sub-assembly of its own kit (or delete it), then re-run the migration';
+    END IF;
+END $$;
+
+ALTER TABLE kit_parts
+    ADD CONSTRAINT kit_parts_sub_assembly_same_kit_fkey
+        FOREIGN KEY (sub_assembly_id, kit_id) REFERENCES sub_assemblies(id, kit_id)
+        ON DELETE CASCADE;
+
+ALTER TABLE kit_part_requirements ADD COLUMN IF NOT EXISTS kit_id BIGINT;
+
+UPDATE kit_part_requirements req
+SET kit_id = kp.kit_id
+FROM kit_parts kp
+WHERE kp.id = req.kit_part_id;
+
+DO $$
+DECLARE
+    bad_ids TEXT;
+BEGIN
+    SELECT string_agg(req.id::TEXT, ', ' ORDER BY req.id) INTO bad_ids
+    FROM kit_part_requirements req
+    JOIN runners r ON r.id = req.runner_id
+    WHERE r.kit_id <> req.kit_id;
+
+    IF bad_ids IS NOT NULL THEN
+        RAISE EXCEPTION 'kit_part_requirements using a runner of another kit: %', bad_ids
+            USING HINT = 'Point each requirement at a runner of its kit part''s kit (or delete it), then re-run the migration';
+    END IF;
+END $$;
+
+ALTER TABLE kit_part_requirements ALTER COLUMN kit_id SET NOT NULL;
+
+ALTER TABLE kit_part_requirements
+    ADD CONSTRAINT kit_part_requirements_kit_part_same_kit_fkey
+        FOREIGN KEY (kit_part_id, kit_id) REFERENCES kit_parts(id, kit_id)
+        ON DELETE CASCADE,
+    ADD CONSTRAINT kit_part_requirements_runner_same_kit_fkey
+        FOREIGN KEY (runner_id, kit_id) REFERENCES runners(id, kit_id)
+        ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_kit_part_requirements_kit_id ON kit_part_requirements(kit_id);
@@ -0,0 +1,42 @@
+-- Multi-color runners: ordered color list per runner plus optional per-gate overrides.
+-- Position 0 is the runner's primary color and always mirrors runners.color_id.
+CREATE TABLE IF NOT EXISTS runner_colors (
+    runner_id BIGINT NOT NULL,
+    color_id BIGINT NOT NULL,
+    position INTEGER NOT NULL CHECK (position >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT runner_colors_pkey PRIMARY KEY (runner_id, color_id),
+    CONSTRAINT runner_colors_runner_id_fkey
+        FOREIGN KEY (runner_id) REFERENCES runners(id) ON DELETE CASCADE,
+    CONSTRAINT runner_colors_color_id_fkey
+        FOREIGN KEY (color_id) REFERENCES colors(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_runner_colors_color_id ON runner_colors(color_id);
+
+INSERT INTO runner_colors (runner_id, color_id, position)
+SELECT id, color_id, 0 FROM runners
+ON CONFLICT DO NOTHING;
+
+-- A gate override must use one of the runner's own colors; replacing the primary color
+-- carries its overrides along (ON UPDATE CASCADE), removing a color drops them.
+CREATE TABLE IF NOT EXISTS runner_gate_colors (
+    id BIGSERIAL PRIMARY KEY,
+    runner_id BIGINT NOT NULL,
+    gate TEXT NOT NULL, -- canonical gate without runner prefix, e.g. '12', '3a'
+    color_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    updated_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT runner_gate_colors_runner_color_fkey
+        FOREIGN KEY (runner_id, color_id) REFERENCES runner_colors(runner_id, color_id)
+        ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT runner_gate_colors_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT runner_gate_colors_updated_by_fkey
+        FOREIGN KEY (updated_by) REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT runner_gate_colors_runner_id_gate_key UNIQUE (runner_id, gate)
+);
@@ -0,0 +1,14 @@
+-- CIE Lab (D65) values for nearest-color search (CIEDE2000).
+-- Computed by the API on write; rows created before this migration are normalized
+-- and backfilled at startup. NULL = hex could not be parsed, row is skipped by search.
+ALTER TABLE colors
+    ADD COLUMN IF NOT EXISTS lab_l DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS lab_a DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS lab_b DOUBLE PRECISION;
+
+-- Paints get a swatch color so they can be matched against runner colors
+ALTER TABLE paints
+    ADD COLUMN IF NOT EXISTS hex TEXT,
+    ADD COLUMN IF NOT EXISTS lab_l DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS lab_a DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS lab_b DOUBLE PRECISION;
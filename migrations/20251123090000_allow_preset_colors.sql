@@ -0,0 +1,9 @@
+-- Global color presets (มาตรฐาน Bandai ฯลฯ) ที่ admin ดูแล: colors.user_id IS NULL
+-- ผู้ใช้ทุกคนเห็น/ใช้เป็น runners.color_id ได้ และคัดลอกไปเป็นสีส่วนตัวได้
+ALTER TABLE colors
+    ALTER COLUMN user_id DROP NOT NULL;
+
+-- UNIQUE(user_id, name, code) ไม่กันแถวที่ user_id เป็น NULL ซ้ำกัน
+CREATE UNIQUE INDEX IF NOT EXISTS idx_colors_preset_name_code
+    ON colors(name, code)
+    WHERE user_id IS NULL;
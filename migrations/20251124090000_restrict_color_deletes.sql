@@ -0,0 +1,14 @@
+-- ลบสีที่ยังมี runner ใช้อยู่ = ลบ runner + requirements ทั้งหมดตามไปด้วย (ON DELETE CASCADE)
+-- เปลี่ยนเป็น RESTRICT: API ตอบ 409 พร้อมรายการที่ใช้สีนั้น หรือย้ายไปสีอื่นก่อน (?reassign_to=)
+ALTER TABLE runners
+    DROP CONSTRAINT IF EXISTS runners_color_id_fkey,
+    ADD CONSTRAINT runners_color_id_fkey
+        FOREIGN KEY (color_id) REFERENCES colors(id) ON DELETE RESTRICT;
+
+ALTER TABLE runner_colors
+    DROP CONSTRAINT IF EXISTS runner_colors_color_id_fkey,
+    ADD CONSTRAINT runner_colors_color_id_fkey
+        FOREIGN KEY (color_id) REFERENCES colors(id) ON DELETE RESTRICT;
+
+CREATE INDEX IF NOT EXISTS idx_runners_color_id ON runners(color_id);
+CREATE INDEX IF NOT EXISTS idx_runner_colors_color_id ON runner_colors(color_id);
@@ -0,0 +1,15 @@
+-- ประเภทสี (lacquer/acrylic/enamel) และผิวสี (gloss/matte/...) สำหรับ paints module
+-- NULL = ไม่ระบุ (ข้อมูลเดิม)
+ALTER TABLE paints
+    ADD COLUMN IF NOT EXISTS paint_type TEXT,
+    ADD COLUMN IF NOT EXISTS finish TEXT;
+
+ALTER TABLE paints
+    DROP CONSTRAINT IF EXISTS paints_paint_type_check,
+    ADD CONSTRAINT paints_paint_type_check
+        CHECK (paint_type IN ('lacquer', 'acrylic', 'enamel', 'other')),
+    DROP CONSTRAINT IF EXISTS paints_finish_check,
+    ADD CONSTRAINT paints_finish_check
+        CHECK (finish IN ('gloss', 'semi_gloss', 'matte', 'metallic', 'pearl', 'clear', 'fluorescent'));
+
+CREATE INDEX IF NOT EXISTS idx_paints_user_id_brand ON paints(user_id, brand);
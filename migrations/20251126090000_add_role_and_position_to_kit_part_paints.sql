@@ -0,0 +1,21 @@
+-- kit_part_paints: สีที่ใช้กับ part แต่ละชิ้น พร้อมหน้าที่ (role) และลำดับการทำ (position)
+-- paint เดียวกันใช้ได้หลาย role ใน part เดียว (เช่น gloss white เป็นทั้ง base และ topcoat)
+ALTER TABLE kit_part_paints
+    ADD COLUMN IF NOT EXISTS role TEXT NOT NULL DEFAULT 'base',
+    ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS user_id BIGINT,
+    ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+ALTER TABLE kit_part_paints
+    DROP CONSTRAINT IF EXISTS kit_part_paints_role_check,
+    ADD CONSTRAINT kit_part_paints_role_check
+        CHECK (role IN ('base', 'highlight', 'panel_line', 'topcoat')),
+    DROP CONSTRAINT IF EXISTS kit_part_paints_user_id_fkey,
+    ADD CONSTRAINT kit_part_paints_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE kit_part_paints
+    DROP CONSTRAINT IF EXISTS kit_part_paints_pkey,
+    ADD CONSTRAINT kit_part_paints_pkey PRIMARY KEY (kit_part_id, paint_id, role);
+
+CREATE INDEX IF NOT EXISTS idx_kit_part_paints_paint_id ON kit_part_paints(paint_id);
@@ -0,0 +1,25 @@
+-- สต็อก paint: จำนวนขวด (รวมขวดที่เปิดใช้อยู่) + ระดับที่เหลือในขวดที่เปิด (%)
+-- low_stock_threshold เป็น % ของหนึ่งขวด เช่น 25 = ขวดสุดท้ายเหลือ ≤ 25% ถือว่าใกล้หมด
+--                                         150 = ต้องมีมากกว่าขวดครึ่งถึงจะพอ
+ALTER TABLE paints
+    ADD COLUMN IF NOT EXISTS quantity INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS fill_level SMALLINT,
+    ADD COLUMN IF NOT EXISTS low_stock_threshold INTEGER NOT NULL DEFAULT 25;
+
+ALTER TABLE paints
+    DROP CONSTRAINT IF EXISTS paints_quantity_check,
+    ADD CONSTRAINT paints_quantity_check CHECK (quantity >= 0),
+    DROP CONSTRAINT IF EXISTS paints_fill_level_check,
+    ADD CONSTRAINT paints_fill_level_check CHECK (fill_level BETWEEN 0 AND 100),
+    DROP CONSTRAINT IF EXISTS paints_low_stock_threshold_check,
+    ADD CONSTRAINT paints_low_stock_threshold_check CHECK (low_stock_threshold >= 0);
+
+-- ok / low / out คำนวณจากคอลัมน์ด้านบน (fill_level NULL = ขวดที่เปิดยังเต็ม)
+ALTER TABLE paints
+    ADD COLUMN IF NOT EXISTS stock_status TEXT NOT NULL GENERATED ALWAYS AS (
+        CASE
+            WHEN quantity <= 0 THEN 'out'
+            WHEN (quantity - 1) * 100 + COALESCE(fill_level, 100) <= low_stock_threshold THEN 'low'
+            ELSE 'ok'
+        END
+    ) STORED;
@@ -0,0 +1,42 @@
+-- paint_recipes: สีผสมเอง (หลาย paint ตามสัดส่วน + ทินเนอร์) แยกตาม user เหมือน paints
+-- hex / lab_* เป็นค่าประมาณจาก hex ของ paint ที่ใช้ผสม (คำนวณใหม่ทุกครั้งที่ส่วนผสมเปลี่ยน)
+CREATE TABLE IF NOT EXISTS paint_recipes (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    notes TEXT,
+    thinner_ratio DOUBLE PRECISION CHECK (thinner_ratio >= 0), -- 👈 ทินเนอร์กี่ส่วนต่อสี 1 ส่วน
+    hex TEXT,
+    lab_l DOUBLE PRECISION,
+    lab_a DOUBLE PRECISION,
+    lab_b DOUBLE PRECISION,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_paint_recipes_user_id ON paint_recipes(user_id);
+
+-- ส่วนผสม: ลบ paint ที่ยังอยู่ในสูตรไม่ได้ (สูตรจะเพี้ยนโดยไม่รู้ตัว)
+CREATE TABLE IF NOT EXISTS paint_recipe_components (
+    recipe_id BIGINT NOT NULL REFERENCES paint_recipes(id) ON DELETE CASCADE,
+    paint_id BIGINT NOT NULL REFERENCES paints(id) ON DELETE RESTRICT,
+    ratio DOUBLE PRECISION NOT NULL CHECK (ratio > 0),
+    position INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (recipe_id, paint_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_paint_recipe_components_paint_id ON paint_recipe_components(paint_id);
+
+-- kit_part_recipes: ผูกสูตรกับ part แบบเดียวกับ kit_part_paints (role + position)
+CREATE TABLE IF NOT EXISTS kit_part_recipes (
+    kit_part_id BIGINT NOT NULL REFERENCES kit_parts(id) ON DELETE CASCADE,
+    recipe_id BIGINT NOT NULL REFERENCES paint_recipes(id) ON DELETE CASCADE,
+    role TEXT NOT NULL DEFAULT 'base'
+        CHECK (role IN ('base', 'highlight', 'panel_line', 'topcoat')),
+    position INTEGER NOT NULL DEFAULT 0,
+    user_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (kit_part_id, recipe_id, role)
+);
+
+CREATE INDEX IF NOT EXISTS idx_kit_part_recipes_recipe_id ON kit_part_recipes(recipe_id);
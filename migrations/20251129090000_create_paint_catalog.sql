@@ -0,0 +1,35 @@
+-- paint_catalog: รายการสีสำเร็จรูปกลางของแต่ละยี่ห้อ (admin import จากไฟล์ CSV / JSON)
+-- ไม่มี user_id: ทุกคนเห็นเหมือนกัน แล้วค่อยเพิ่มเข้า inventory ของตัวเอง (paints.catalog_id)
+CREATE TABLE IF NOT EXISTS paint_catalog (
+    id BIGSERIAL PRIMARY KEY,
+    brand TEXT NOT NULL,
+    line TEXT, -- 👈 เช่น "Mr. Color", "XF (Flat)"
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    hex TEXT,
+    lab_l DOUBLE PRECISION,
+    lab_a DOUBLE PRECISION,
+    lab_b DOUBLE PRECISION,
+    paint_type TEXT CHECK (paint_type IN ('lacquer', 'acrylic', 'enamel', 'other')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT paint_catalog_brand_code_key UNIQUE (brand, code)
+);
+
+-- สีเทียบเท่าข้ามยี่ห้อ (เช่น Mr. Color C1 ≈ Tamiya XF-2) เก็บคู่เดียว a < b แล้วค้นสองทาง
+CREATE TABLE IF NOT EXISTS paint_catalog_equivalents (
+    paint_a_id BIGINT NOT NULL REFERENCES paint_catalog(id) ON DELETE CASCADE,
+    paint_b_id BIGINT NOT NULL REFERENCES paint_catalog(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (paint_a_id, paint_b_id),
+    CONSTRAINT paint_catalog_equivalents_order_check CHECK (paint_a_id < paint_b_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_paint_catalog_equivalents_b ON paint_catalog_equivalents(paint_b_id);
+
+-- paint ของ user ที่เพิ่มมาจาก catalog (ลบรายการใน catalog แล้ว paint ยังอยู่)
+ALTER TABLE paints
+    ADD COLUMN IF NOT EXISTS catalog_id BIGINT REFERENCES paint_catalog(id) ON DELETE SET NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS paints_user_id_catalog_id_key
+    ON paints(user_id, catalog_id) WHERE catalog_id IS NOT NULL;
@@ -0,0 +1,35 @@
+-- workflow_stages: ขั้นตอนทำ part ที่ user กำหนดเอง เรียงตาม position (0 = ขั้นแรก)
+-- kit ใช้ workflow ของ owner ของ kit นั้น
+CREATE TABLE IF NOT EXISTS workflow_stages (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT workflow_stages_user_id_name_key UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_stages_user_id ON workflow_stages(user_id, position);
+
+-- ขั้นปัจจุบันของ part (NULL = ยังไม่เริ่ม) — ลบ stage ที่ยังมี part อยู่ไม่ได้
+ALTER TABLE kit_parts
+    ADD COLUMN IF NOT EXISTS stage_id BIGINT REFERENCES workflow_stages(id) ON DELETE RESTRICT,
+    ADD COLUMN IF NOT EXISTS stage_updated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_kit_parts_stage_id ON kit_parts(stage_id);
+
+-- ประวัติการเปลี่ยนขั้น (เก็บชื่อไว้ด้วย เผื่อ stage ถูกเปลี่ยนชื่อ/ลบทีหลัง)
+CREATE TABLE IF NOT EXISTS kit_part_stage_history (
+    id BIGSERIAL PRIMARY KEY,
+    kit_part_id BIGINT NOT NULL REFERENCES kit_parts(id) ON DELETE CASCADE,
+    from_stage_id BIGINT REFERENCES workflow_stages(id) ON DELETE SET NULL,
+    from_stage_name TEXT,
+    to_stage_id BIGINT REFERENCES workflow_stages(id) ON DELETE SET NULL,
+    to_stage_name TEXT,
+    changed_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_kit_part_stage_history_kit_part_id
+    ON kit_part_stage_history(kit_part_id, changed_at);
@@ -0,0 +1,26 @@
+-- paint_catalog: (brand, code) ต้องไม่ซ้ำแบบไม่สนตัวพิมพ์ ให้ตรงกับการจับคู่ตอน import
+-- ("Mr. Color:C1" กับ "mr. color:c1" คือรายการเดียวกัน)
+-- รายการที่ซ้ำกันอยู่แล้วรวมให้อัตโนมัติไม่ได้ (paints.catalog_id / equivalents อ้างถึงทั้งคู่)
+-- จึงหยุด migration ให้ admin ลบหรือแก้ตัวที่ซ้ำก่อน
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(ids, '; ') INTO duplicates
+    FROM (
+        SELECT string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+        FROM paint_catalog
+        GROUP BY LOWER(brand), LOWER(code)
+        HAVING COUNT(*) > 1
+    ) dup;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION 'paint_catalog has entries that differ only by case: %', duplicates
+            USING HINT = 'Delete or rename the duplicates (DELETE /paint_catalog/:id), then re-run the migration';
+    END IF;
+END $$;
+
+ALTER TABLE paint_catalog DROP CONSTRAINT IF EXISTS paint_catalog_brand_code_key;
+
+CREATE UNIQUE INDEX IF NOT EXISTS paint_catalog_brand_code_lower_key
+    ON paint_catalog (LOWER(brand), LOWER(code));
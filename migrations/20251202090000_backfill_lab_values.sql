@@ -0,0 +1,81 @@
+-- เติมค่า Lab ให้สี/paint ที่สร้างก่อนมี lab_l/lab_a/lab_b (แทนการ backfill ทุกครั้งที่ server start)
+-- สูตรเดียวกับ model::lab::normalize_hex + hex_to_lab (sRGB → XYZ D65 → CIE Lab)
+-- hex ที่อ่านไม่ได้คง lab เป็น NULL (search ข้ามไป) และไม่ถูกสแกนซ้ำอีก
+
+-- "#ABC" / "aabbcc" → "#aabbcc" (ไม่ถูกต้อง = NULL)
+CREATE FUNCTION pg_temp.normalize_hex(raw TEXT) RETURNS TEXT AS $$
+    SELECT CASE
+        WHEN d ~ '^[0-9a-f]{6}$' THEN '#' || d
+        WHEN d ~ '^[0-9a-f]{3}$' THEN '#'
+            || repeat(substr(d, 1, 1), 2)
+            || repeat(substr(d, 2, 1), 2)
+            || repeat(substr(d, 3, 1), 2)
+    END
+    FROM (SELECT lower(regexp_replace(btrim(raw, E' \t\r\n'), '^#', '')) AS d) s
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE FUNCTION pg_temp.hex_to_lab(
+    hex TEXT,
+    OUT l DOUBLE PRECISION,
+    OUT a DOUBLE PRECISION,
+    OUT b DOUBLE PRECISION
+) AS $$
+DECLARE
+    rgb DOUBLE PRECISION[] := ARRAY[]::DOUBLE PRECISION[];
+    f DOUBLE PRECISION[] := ARRAY[]::DOUBLE PRECISION[];
+    v DOUBLE PRECISION;
+    t DOUBLE PRECISION;
+    x DOUBLE PRECISION;
+    y DOUBLE PRECISION;
+    z DOUBLE PRECISION;
+BEGIN
+    -- sRGB → linear RGB
+    FOR i IN 0..2 LOOP
+        v := ('x' || substr(hex, 2 + i * 2, 2))::BIT(8)::INT / 255.0;
+        IF v <= 0.04045 THEN
+            rgb := rgb || v / 12.92;
+        ELSE
+            rgb := rgb || power((v + 0.055) / 1.055, 2.4);
+        END IF;
+    END LOOP;
+
+    -- linear RGB → XYZ (D65) แล้วหารด้วย white point
+    x := (0.4124564 * rgb[1] + 0.3575761 * rgb[2] + 0.1804375 * rgb[3]) / 0.95047;
+    y := 0.2126729 * rgb[1] + 0.7151522 * rgb[2] + 0.0721750 * rgb[3];
+    z := (0.0193339 * rgb[1] + 0.119192 * rgb[2] + 0.9503041 * rgb[3]) / 1.08883;
+
+    FOREACH t IN ARRAY ARRAY[x, y, z] LOOP
+        IF t > 216.0 / 24389.0 THEN
+            f := f || cbrt(t);
+        ELSE
+            f := f || (24389.0 / 27.0 * t + 16.0) / 116.0;
+        END IF;
+    END LOOP;
+
+    l := 116.0 * f[2] - 16.0;
+    a := 500.0 * (f[1] - f[2]);
+    b := 200.0 * (f[2] - f[3]);
+END
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+UPDATE colors c
+SET hex = n.hex, lab_l = (lab).l, lab_a = (lab).a, lab_b = (lab).b
+FROM (
+    SELECT id, hex, pg_temp.hex_to_lab(hex) AS lab
+    FROM (SELECT id, pg_temp.normalize_hex(hex) AS hex FROM colors WHERE lab_l IS NULL) s
+    WHERE hex IS NOT NULL
+) n
+WHERE c.id = n.id;
+
+UPDATE paints p
+SET hex = n.hex, lab_l = (lab).l, lab_a = (lab).a, lab_b = (lab).b
+FROM (
+    SELECT id, hex, pg_temp.hex_to_lab(hex) AS lab
+    FROM (
+        SELECT id, pg_temp.normalize_hex(hex) AS hex
+        FROM paints
+        WHERE hex IS NOT NULL AND lab_l IS NULL
+    ) s
+    WHERE hex IS NOT NULL
+) n
+WHERE p.id = n.id;
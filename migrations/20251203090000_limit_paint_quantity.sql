@@ -0,0 +1,5 @@
+-- stock_status คำนวณ (quantity - 1) * 100 เป็น INTEGER: quantity เกิน ~21.4 ล้าน overflow
+-- จำกัดให้ตรงกับ MAX_PAINT_QUANTITY ใน model/paint.rs
+ALTER TABLE paints
+    DROP CONSTRAINT IF EXISTS paints_quantity_check,
+    ADD CONSTRAINT paints_quantity_check CHECK (quantity BETWEEN 0 AND 10000);
@@ -0,0 +1,6 @@
+-- ลบ paint ที่ยังผูกกับ part อยู่ = kit_part_paints หายไปเงียบ ๆ (ON DELETE CASCADE)
+-- เปลี่ยนเป็น RESTRICT เหมือน colors: API ตอบ 409 พร้อมรายการ part / สูตรที่ใช้ paint นั้น
+ALTER TABLE kit_part_paints
+    DROP CONSTRAINT IF EXISTS kit_part_paints_paint_id_fkey,
+    ADD CONSTRAINT kit_part_paints_paint_id_fkey
+        FOREIGN KEY (paint_id) REFERENCES paints(id) ON DELETE RESTRICT;
@@ -1,7 +1,7 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, patch},
+    routing::{get, patch, post},
     Json, Router,
 };
 use sqlx::Error as SqlxError; // 💡 Alias SQLx Error เพื่อใช้ใน map_err
@@ -9,12 +9,26 @@ use sqlx::Error as SqlxError; // 💡 Alias SQLx Error เพื่อใช้
 // Type Alias ที่คุณกำหนดไว้ (ใช้เหมือนเดิม)
 
 use crate::{
-    middleware::auth::AuthUser,
+    api::palette::{export_palette_handler, import_palette_handler},
+    middleware::auth::{AdminUser, AuthUser},
     model::{
-        color::{Color, CreateColorPayload, UpdateColorPayload},
+        color::{
+            Color, ColorDeleteError, ColorSearchQuery, ColorSearchResponse, ColorSearchSource,
+            ColorUsage, CreateColorPayload, DeleteColorQuery, MergeColorsPayload,
+            MergeColorsResponse, UpdateColorPayload,
+        },
         common::Message,
+        lab::{ciede2000, hex_to_lab, normalize_hex, Lab},
+    },
+    repository::{
+        color::{
+            copy_preset_color, create_color, delete_color, delete_preset_color, get_color_by_id,
+            get_color_candidates, get_color_usage_for_user, get_colors, get_paint_candidates,
+            get_preset_colors, merge_colors, update_color, DeleteColorError, DeletePresetError,
+            MergeColorsError,
+        },
+        paint_recipe::get_recipe_candidates,
     },
-    repository::color::{create_color, delete_color, get_color_by_id, get_colors, update_color},
     state::AppState,
 };
 
@@ -36,40 +50,45 @@ async fn create_color_handler(
     auth_user: AuthUser,
     Json(payload): Json<CreateColorPayload>,
 ) -> Result<(StatusCode, Json<Color>), (StatusCode, Json<Message>)> {
+    // 0. ตรวจ + normalize hex ("#ABC" → "#aabbcc") แล้วคำนวณ Lab เก็บไว้ใช้ค้นหาสีใกล้เคียง
+    let (hex, lab) = parse_hex(&payload.hex)?;
+
     // 1. สร้าง new_color (Ownership/Move ถูกต้องแล้วด้วย Struct Update Syntax)
     let new_color = Color {
         id: None,
         name: payload.name,                          // Move
         code: payload.code,                          // Move
-        hex: payload.hex,                            // Move
+        hex,                                         // Normalized
         is_clear: payload.is_clear.unwrap_or(false), // Option Handled
         is_multi: payload.is_multi.unwrap_or(false), // Option Handled
-        user_id: auth_user.user_id,                  // User ID Handled
+        user_id: Some(auth_user.user_id),            // User ID Handled
+        is_preset: false,
         created_at: None,
         updated_at: None,
     };
 
     // 2. เรียก Repository และจัดการ Error (Type Conversion ถูกต้อง)
-    let created_color = create_color(&state.db_pool, new_color)
-        .await
-        .map_err(|e: SqlxError| {
-            // ... Logic จัดการ 409 Conflict และ 500 Internal Error ...
-            let status = if let Some(db_err) = e.as_database_error() {
-                if db_err.message().contains("UNIQUE constraint failed") {
-                    StatusCode::CONFLICT
+    let created_color =
+        create_color(&state.db_pool, new_color, lab)
+            .await
+            .map_err(|e: SqlxError| {
+                // ... Logic จัดการ 409 Conflict และ 500 Internal Error ...
+                let status = if let Some(db_err) = e.as_database_error() {
+                    if db_err.message().contains("UNIQUE constraint failed") {
+                        StatusCode::CONFLICT
+                    } else {
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    }
                 } else {
                     StatusCode::INTERNAL_SERVER_ERROR
-                }
-            } else {
-                StatusCode::INTERNAL_SERVER_ERROR
-            };
-            (
-                status,
-                Json(Message {
-                    message: e.to_string(),
-                }),
-            )
-        })?;
+                };
+                (
+                    status,
+                    Json(Message {
+                        message: e.to_string(),
+                    }),
+                )
+            })?;
 
     // 3. ส่ง Response 201 Created
     Ok((StatusCode::CREATED, Json(created_color)))
@@ -81,9 +100,29 @@ pub async fn update_color_handler(
     auth_user: AuthUser, // <-- ได้มาจาก Auth Middleware เช่น JWT
     Json(payload): Json<UpdateColorPayload>,
 ) -> Result<(StatusCode, Json<Color>), (StatusCode, Json<Message>)> {
+    update_owned_color(&state, id, Some(auth_user.user_id), payload).await
+}
+
+// owner_id = None → preset กลาง (เรียกจาก handler ของ admin)
+async fn update_owned_color(
+    state: &AppState,
+    id: i64,
+    owner_id: Option<i64>,
+    mut payload: UpdateColorPayload,
+) -> Result<(StatusCode, Json<Color>), (StatusCode, Json<Message>)> {
+    // 1. hex ที่ส่งมาต้องถูกต้อง (เก็บแบบ normalize + Lab ใหม่)
+    let lab = match payload.hex.as_deref() {
+        Some(raw) => {
+            let (hex, lab) = parse_hex(raw)?;
+            payload.hex = Some(hex);
+            Some(lab)
+        }
+        None => None,
+    };
+
     // 2. เรียก Repository เพื่ออัปเดตข้อมูล
-    // เราส่ง id, user_id (สำหรับเช็ค ownership), และ payload เข้าไป
-    let update_result = update_color(&state.db_pool, id, auth_user.user_id, payload).await;
+    // เราส่ง id, owner_id (สำหรับเช็ค ownership), และ payload เข้าไป
+    let update_result = update_color(&state.db_pool, id, owner_id, payload, lab).await;
 
     // 3. จัดการผลลัพธ์ (Ok หรือ Err)
     match update_result {
@@ -114,11 +153,13 @@ pub async fn delete_color_handler(
     State(state): State<AppState>,
     Path(id): Path<i64>,
     auth_user: AuthUser, // <-- ได้มาจาก Auth Middleware
-) -> Result<StatusCode, (StatusCode, Json<Message>)> {
+    Query(query): Query<DeleteColorQuery>,
+) -> Result<StatusCode, (StatusCode, Json<ColorDeleteError>)> {
     // 👈 ถ้าสำเร็จ คืนแค่ StatusCode
 
-    // เรียกใช้ repository เพื่อลบข้อมูล
-    let delete_result = delete_color(&state.db_pool, id, auth_user.user_id).await;
+    // เรียกใช้ repository เพื่อลบข้อมูล (มี reassign_to = ย้าย runner ไปสีนั้นก่อนลบ)
+    let delete_result =
+        delete_color(&state.db_pool, id, auth_user.user_id, query.reassign_to).await;
 
     match delete_result {
         Ok(_) => {
@@ -126,22 +167,40 @@ pub async fn delete_color_handler(
             // ที่ทำงานสำเร็จและไม่มีข้อมูลใดๆ ต้องส่งกลับไปใน body
             Ok(StatusCode::NO_CONTENT)
         }
-        Err(e) => {
-            // ไม่สำเร็จ: แปลง Error เป็น HTTP Status Code
-            let status_code = match e {
-                SqlxError::RowNotFound => StatusCode::NOT_FOUND, // 👈 แปลงจาก repository
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-
-            let message = Message {
-                message: format!("Failed to delete color: {}", e),
-            };
-
-            Err((status_code, Json(message)))
+        Err(DeleteColorError::NotFound) => Err(delete_error(
+            StatusCode::NOT_FOUND,
+            "Color not found".to_string(),
+            None,
+        )),
+        // ยังมี runner ใช้อยู่: ส่งรายการกลับไปให้เลือกสีที่จะย้ายไป
+        Err(DeleteColorError::InUse(usage)) => Err(delete_error(
+            StatusCode::CONFLICT,
+            format!(
+                "Color is used by {} runner(s) in {} kit(s); pass reassign_to to move them",
+                usage.total_runner_count(),
+                usage.total_kit_count()
+            ),
+            Some(usage),
+        )),
+        Err(DeleteColorError::InvalidTarget(msg)) => {
+            Err(delete_error(StatusCode::UNPROCESSABLE_ENTITY, msg, None))
         }
+        Err(DeleteColorError::Db(e)) => Err(delete_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete color: {}", e),
+            None,
+        )),
     }
 }
 
+fn delete_error(
+    status: StatusCode,
+    message: String,
+    usage: Option<ColorUsage>,
+) -> (StatusCode, Json<ColorDeleteError>) {
+    (status, Json(ColorDeleteError { message, usage }))
+}
+
 async fn get_color_handler(
     State(state): State<AppState>,
     Path(id): Path<i64>,
@@ -163,9 +222,277 @@ async fn get_color_handler(
     }
 }
 
+// hex → ("#rrggbb", Lab) หรือ 400
+pub fn parse_hex(raw: &str) -> Result<(String, Lab), (StatusCode, Json<Message>)> {
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, Json(Message { message }));
+    let hex = normalize_hex(raw).map_err(bad_request)?;
+    let lab = hex_to_lab(&hex).ok_or_else(|| bad_request(format!("Invalid hex color: {}", raw)))?;
+    Ok((hex, lab))
+}
+
+// GET /colors/search?hex=%23aabbcc&source=all|colors|paints&limit=10&max_distance=
+// เรียงสีของเรา + paint + สูตรผสมสี ตามระยะ CIEDE2000 จากสีที่ค้น (ใกล้สุดก่อน)
+async fn search_colors_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<ColorSearchQuery>,
+) -> Result<Json<ColorSearchResponse>, (StatusCode, Json<Message>)> {
+    let (hex, lab) = parse_hex(&query.hex)?;
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+    let internal = |e: SqlxError| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Message {
+                message: e.to_string(),
+            }),
+        )
+    };
+
+    let mut matches = Vec::new();
+    if query.source != ColorSearchSource::Paints {
+        matches.extend(
+            get_color_candidates(&state.db_pool, auth_user.user_id)
+                .await
+                .map_err(internal)?,
+        );
+    }
+    if query.source != ColorSearchSource::Colors {
+        matches.extend(
+            get_paint_candidates(&state.db_pool, auth_user.user_id)
+                .await
+                .map_err(internal)?,
+        );
+        matches.extend(
+            get_recipe_candidates(&state.db_pool, auth_user.user_id)
+                .await
+                .map_err(internal)?,
+        );
+    }
+
+    for candidate in &mut matches {
+        candidate.distance = ciede2000(lab, candidate.lab);
+    }
+    if let Some(max_distance) = query.max_distance {
+        matches.retain(|m| m.distance <= max_distance);
+    }
+    matches.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    matches.truncate(limit);
+
+    Ok(Json(ColorSearchResponse { hex, lab, matches }))
+}
+
+// GET /colors/:id/usage
+async fn get_color_usage_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+) -> Result<Json<ColorUsage>, (StatusCode, Json<Message>)> {
+    match get_color_usage_for_user(&state.db_pool, id, auth_user.user_id).await {
+        Ok(usage) => Ok(Json(usage)),
+        Err(SqlxError::RowNotFound) => Err(message(
+            StatusCode::NOT_FOUND,
+            "Color not found".to_string(),
+        )),
+        Err(e) => Err(message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// POST /colors/merge
+async fn merge_colors_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<MergeColorsPayload>,
+) -> Result<Json<MergeColorsResponse>, (StatusCode, Json<Message>)> {
+    if payload.source_ids.is_empty() {
+        return Err(message(
+            StatusCode::BAD_REQUEST,
+            "source_ids must contain at least one color".to_string(),
+        ));
+    }
+    let mut source_ids: Vec<i64> = Vec::with_capacity(payload.source_ids.len());
+    for source_id in &payload.source_ids {
+        if *source_id == payload.target_id {
+            return Err(message(
+                StatusCode::BAD_REQUEST,
+                "source_ids must not contain target_id".to_string(),
+            ));
+        }
+        if source_ids.contains(source_id) {
+            return Err(message(
+                StatusCode::BAD_REQUEST,
+                format!("Color {} is listed more than once", source_id),
+            ));
+        }
+        source_ids.push(*source_id);
+    }
+
+    let mut changes = payload.changes;
+    let changes = if changes.is_empty() {
+        None
+    } else {
+        let lab = match changes.hex.as_deref() {
+            Some(raw) => {
+                let (hex, lab) = parse_hex(raw)?;
+                changes.hex = Some(hex);
+                Some(lab)
+            }
+            None => None,
+        };
+        Some((changes, lab))
+    };
+
+    match merge_colors(
+        &state.db_pool,
+        auth_user.user_id,
+        payload.target_id,
+        &source_ids,
+        changes,
+    )
+    .await
+    {
+        Ok((color, runners_updated)) => Ok(Json(MergeColorsResponse {
+            color,
+            merged_ids: source_ids,
+            runners_updated,
+        })),
+        Err(MergeColorsError::NotFound(msg)) => Err(message(StatusCode::NOT_FOUND, msg)),
+        Err(MergeColorsError::Invalid(msg)) => Err(message(StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(MergeColorsError::Conflict(msg)) => Err(message(StatusCode::CONFLICT, msg)),
+        Err(MergeColorsError::Db(e)) => {
+            Err(message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
+    }
+}
+
+// --- PRESETS: สีกลางที่ทุกคนใช้ได้ (admin เป็นคนดูแล) ---
+
+fn message(status: StatusCode, message: String) -> (StatusCode, Json<Message>) {
+    (status, Json(Message { message }))
+}
+
+// GET /colors/presets
+async fn get_presets_handler(
+    State(state): State<AppState>,
+    _auth_user: AuthUser,
+) -> Result<Json<Vec<Color>>, (StatusCode, Json<Message>)> {
+    get_preset_colors(&state.db_pool)
+        .await
+        .map(Json)
+        .map_err(|e| message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+// POST /colors/presets (admin)
+async fn create_preset_handler(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    Json(payload): Json<CreateColorPayload>,
+) -> Result<(StatusCode, Json<Color>), (StatusCode, Json<Message>)> {
+    let (hex, lab) = parse_hex(&payload.hex)?;
+    let new_color = Color {
+        id: None,
+        name: payload.name,
+        code: payload.code,
+        hex,
+        is_clear: payload.is_clear.unwrap_or(false),
+        is_multi: payload.is_multi.unwrap_or(false),
+        user_id: None, // 👈 preset
+        is_preset: true,
+        created_at: None,
+        updated_at: None,
+    };
+
+    match create_color(&state.db_pool, new_color, lab).await {
+        Ok(color) => Ok((StatusCode::CREATED, Json(color))),
+        Err(e) if is_unique_violation(&e) => Err(message(
+            StatusCode::CONFLICT,
+            "A preset with this name and code already exists".to_string(),
+        )),
+        Err(e) => Err(message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// PATCH /colors/presets/:id (admin)
+async fn update_preset_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    _admin: AdminUser,
+    Json(payload): Json<UpdateColorPayload>,
+) -> Result<(StatusCode, Json<Color>), (StatusCode, Json<Message>)> {
+    update_owned_color(&state, id, None, payload).await
+}
+
+// DELETE /colors/presets/:id (admin) — 409 ถ้ายังมี runner ใช้อยู่
+async fn delete_preset_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    _admin: AdminUser,
+) -> Result<StatusCode, (StatusCode, Json<ColorDeleteError>)> {
+    match delete_preset_color(&state.db_pool, id, auth_user.user_id).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(DeletePresetError::NotFound) => Err(delete_error(
+            StatusCode::NOT_FOUND,
+            "Preset color not found".to_string(),
+            None,
+        )),
+        Err(DeletePresetError::InUse(usage)) => Err(delete_error(
+            StatusCode::CONFLICT,
+            format!(
+                "Preset color is used by {} runner(s)",
+                usage.total_runner_count()
+            ),
+            Some(usage),
+        )),
+        Err(DeletePresetError::Db(e)) => Err(delete_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            e.to_string(),
+            None,
+        )),
+    }
+}
+
+// POST /colors/:id/copy — คัดลอก preset เข้า palette ส่วนตัว
+async fn copy_preset_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+) -> Result<(StatusCode, Json<Color>), (StatusCode, Json<Message>)> {
+    match copy_preset_color(&state.db_pool, id, auth_user.user_id).await {
+        Ok(color) => Ok((StatusCode::CREATED, Json(color))),
+        Err(SqlxError::RowNotFound) => Err(message(
+            StatusCode::NOT_FOUND,
+            "Preset color not found".to_string(),
+        )),
+        Err(e) if is_unique_violation(&e) => Err(message(
+            StatusCode::CONFLICT,
+            "You already have a color with this name and code".to_string(),
+        )),
+        Err(e) => Err(message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+fn is_unique_violation(e: &SqlxError) -> bool {
+    e.as_database_error()
+        .is_some_and(|db_err| db_err.is_unique_violation())
+}
+
 pub fn color_router() -> Router<AppState> {
     Router::new()
         .route("/", get(get_colors_handler).post(create_color_handler))
+        .route("/search", get(search_colors_handler))
+        .route("/merge", post(merge_colors_handler))
+        .route("/export", get(export_palette_handler))
+        .route("/import", post(import_palette_handler))
+        .route(
+            "/presets",
+            get(get_presets_handler).post(create_preset_handler),
+        )
+        .route(
+            "/presets/:id",
+            patch(update_preset_handler).delete(delete_preset_handler),
+        )
+        .route("/:id/copy", post(copy_preset_handler))
+        .route("/:id/usage", get(get_color_usage_handler))
         .route(
             "/:id",
             patch(update_color_handler)
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::dashboard::{Dashboard, DashboardQuery},
+    repository::dashboard::get_dashboard,
+    state::AppState,
+};
+
+// ช่วงเริ่มต้นเมื่อไม่ส่ง from มา
+const DEFAULT_RANGE_DAYS: i64 = 30;
+
+// GET /dashboard?from=YYYY-MM-DD&to=YYYY-MM-DD
+pub async fn get_dashboard_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(params): Query<DashboardQuery>,
+) -> Result<Json<Dashboard>, (StatusCode, String)> {
+    let to = params.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = params
+        .from
+        .unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+
+    if from > to {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "from must be on or before to".to_string(),
+        ));
+    }
+
+    match get_dashboard(&state.db_pool, auth_user.user_id, from, to).await {
+        Ok(dashboard) => Ok(Json(dashboard)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub fn dashboard_router() -> Router<AppState> {
+    Router::new().route("/", get(get_dashboard_handler))
+}
@@ -2,7 +2,7 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, patch, post},
+    routing::{delete, get, patch, post},
     Json, Router,
 };
 use sqlx::Error as SqlxError;
@@ -10,6 +10,18 @@ use sqlx::Error as SqlxError;
 // สมมติว่า import สิ่งที่จำเป็น
 
 use crate::{
+    api::{
+        kit_import::import_kits_csv_handler,
+        kit_member::{
+            add_kit_member_handler, get_kit_members_handler, remove_kit_member_handler,
+            update_kit_member_handler,
+        },
+        kit_part_paint::get_paint_plan_handler,
+        paint::{get_kit_shopping_list_handler, get_shopping_list_handler},
+        queue::get_next_kit_handler,
+        share::{create_share_link_handler, get_share_links_handler, revoke_share_link_handler},
+        workflow::get_kit_stage_progress_handler,
+    },
     middleware::auth::AuthUser,
     model::{
         kit::{KitQuery, KitWithRunners},
@@ -29,6 +41,16 @@ use crate::{
     repository::kit::{create, delete_kit, get_all, get_by_id, update, update_status},
 };
 
+fn validate_purchase_price(price: Option<f64>) -> Result<(), (StatusCode, String)> {
+    match price {
+        Some(p) if !p.is_finite() || p < 0.0 => Err((
+            StatusCode::BAD_REQUEST,
+            "purchase_price must be a non-negative number".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
 // --- Handlers for CRUD ---
 
 pub async fn create_kit_handler(
@@ -36,6 +58,8 @@ pub async fn create_kit_handler(
     auth_user: AuthUser, // ได้จาก Auth Middleware
     Json(payload): Json<CreateKitPayload>,
 ) -> Result<(StatusCode, Json<KitWithRunners>), (StatusCode, String)> {
+    validate_purchase_price(payload.purchase_price)?;
+
     match create(&state.db_pool, auth_user.user_id, payload).await {
         Ok(new_kit) => Ok((StatusCode::CREATED, Json(new_kit))),
         Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
@@ -73,6 +97,8 @@ pub async fn update_kit_handler(
     Path(id): Path<i64>,
     Json(payload): Json<UpdateKitPayload>,
 ) -> Result<Json<KitWithRunners>, (StatusCode, String)> {
+    validate_purchase_price(payload.purchase_price)?;
+
     match update(&state.db_pool, id, auth_user.user_id, payload).await {
         Ok(updated_kit) => Ok(Json(updated_kit)),
         Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
@@ -160,6 +186,12 @@ async fn get_all_runners_with_color_handler(
 pub fn kit_router() -> Router<crate::state::AppState> {
     Router::new()
         .route("/", post(create_kit_handler).get(get_all_kits_handler))
+        // 📄 Import หลาย kit จาก CSV (spreadsheet export)
+        .route("/import/csv", post(import_kits_csv_handler))
+        // 🧱 kit ถัดไปที่ควรต่อ (บนสุดของคิวที่ยัง pending)
+        .route("/next", get(get_next_kit_handler))
+        // 🛒 รายการ paint ที่ต้องซื้อ รวมทุก kit ที่ pending / in_progress
+        .route("/shopping-list", get(get_shopping_list_handler))
         .route(
             "/:id",
             get(get_kit_by_id_handler)
@@ -178,6 +210,28 @@ pub fn kit_router() -> Router<crate::state::AppState> {
             get(get_sub_assemblies_by_it_id_handler),
         )
         .route("/:id/kit_parts", get(get_kit_part_by_kit_id_handler))
+        .route("/:id/paint_plan", get(get_paint_plan_handler))
+        .route("/:id/shopping-list", get(get_kit_shopping_list_handler))
+        // 🪜 ความคืบหน้าของ part ตาม workflow stage ของ owner
+        .route("/:id/stage_progress", get(get_kit_stage_progress_handler))
+        // 🔗 Public share links (อ่านอย่างเดียว ไม่ต้อง login)
+        .route(
+            "/:id/share_links",
+            post(create_share_link_handler).get(get_share_links_handler),
+        )
+        .route(
+            "/:id/share_links/:link_id",
+            delete(revoke_share_link_handler),
+        )
+        // 👥 สมาชิกของ kit (owner / editor / viewer)
+        .route(
+            "/:id/members",
+            get(get_kit_members_handler).post(add_kit_member_handler),
+        )
+        .route(
+            "/:id/members/:user_id",
+            patch(update_kit_member_handler).delete(remove_kit_member_handler),
+        )
 }
 
 // // ฟังก์ชันรวม Routes (Option)
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::{
+        kit::KitGrade,
+        kit_import::{
+            parse_grade, parse_purchase_price, parse_status, parse_tags, ImportKitsCsvPayload,
+            KitCsvMapping, KitImportReport, KitImportRowResult, KitImportRowStatus, NewKitRow,
+        },
+    },
+    repository::kit_import::{create_kits, get_existing_kit_keys},
+    state::AppState,
+};
+
+// ชื่อ header ที่รู้จักเมื่อไม่ได้ส่ง mapping มา (เทียบแบบ lowercase)
+const NAME_HEADERS: &[&str] = &["name", "kit", "kit name", "kit_name"];
+const GRADE_HEADERS: &[&str] = &["grade"];
+const STATUS_HEADERS: &[&str] = &["status"];
+const TAGS_HEADERS: &[&str] = &["tags", "tag"];
+const PRICE_HEADERS: &[&str] = &["purchase_price", "purchase price", "price"];
+
+// ตำแหน่งคอลัมน์ของแต่ละฟิลด์ใน CSV
+struct ColumnIndexes {
+    name: usize,
+    grade: usize,
+    status: Option<usize>,
+    tags: Option<usize>,
+    purchase_price: Option<usize>,
+}
+
+fn find_column(
+    headers: &[String],
+    mapped: Option<&String>,
+    aliases: &[&str],
+    field: &str,
+) -> Result<Option<usize>, String> {
+    match mapped {
+        Some(header) => {
+            let header = header.trim().to_lowercase();
+            headers
+                .iter()
+                .position(|h| *h == header)
+                .map(Some)
+                .ok_or_else(|| format!("Column '{}' mapped to {} not found", header, field))
+        }
+        None => Ok(headers.iter().position(|h| aliases.contains(&h.as_str()))),
+    }
+}
+
+fn resolve_columns(headers: &[String], mapping: &KitCsvMapping) -> Result<ColumnIndexes, String> {
+    let name = find_column(headers, mapping.name.as_ref(), NAME_HEADERS, "name")?
+        .ok_or_else(|| "Missing required column: name".to_string())?;
+    let grade = find_column(headers, mapping.grade.as_ref(), GRADE_HEADERS, "grade")?
+        .ok_or_else(|| "Missing required column: grade".to_string())?;
+
+    Ok(ColumnIndexes {
+        name,
+        grade,
+        status: find_column(headers, mapping.status.as_ref(), STATUS_HEADERS, "status")?,
+        tags: find_column(headers, mapping.tags.as_ref(), TAGS_HEADERS, "tags")?,
+        purchase_price: find_column(
+            headers,
+            mapping.purchase_price.as_ref(),
+            PRICE_HEADERS,
+            "purchase_price",
+        )?,
+    })
+}
+
+// POST /kits/import/csv
+// ตรวจทุกแถวก่อน แล้วสร้างเฉพาะแถวที่ผ่านใน transaction เดียว (dry_run = ตรวจอย่างเดียว)
+// kit ที่มีชื่อ + grade เดิมอยู่แล้วจะถูกข้าม (ไม่เขียนทับ status / tags / ราคาเดิม)
+pub async fn import_kits_csv_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<ImportKitsCsvPayload>,
+) -> Result<Json<KitImportReport>, (StatusCode, String)> {
+    let delimiter = payload.delimiter.unwrap_or(',');
+    if !delimiter.is_ascii() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "delimiter must be a single ASCII character".to_string(),
+        ));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(payload.csv.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid CSV header: {}", e),
+            )
+        })?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+
+    let mapping = payload.mapping.unwrap_or_default();
+    let columns =
+        resolve_columns(&headers, &mapping).map_err(|msg| (StatusCode::BAD_REQUEST, msg))?;
+
+    // กันซ้ำทั้งกับ kit ที่มีอยู่แล้วและกับแถวก่อนหน้าในไฟล์เดียวกัน
+    let existing: HashMap<(String, KitGrade), i64> =
+        get_existing_kit_keys(&state.db_pool, auth_user.user_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .map(|(id, name, grade)| ((name, grade), id))
+            .collect();
+    let mut seen: HashMap<(String, KitGrade), u64> = HashMap::new();
+
+    let mut rows: Vec<KitImportRowResult> = Vec::new();
+    let mut new_kits: Vec<NewKitRow> = Vec::new();
+    let mut new_kit_row_indexes: Vec<usize> = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        // +2 = header อยู่บรรทัดที่ 1 (ใช้เมื่อ record parse ไม่ได้และไม่มี position)
+        let fallback_line = index as u64 + 2;
+
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(fallback_line);
+                rows.push(KitImportRowResult {
+                    row: line,
+                    status: KitImportRowStatus::Failed,
+                    name: None,
+                    kit_id: None,
+                    errors: vec![e.to_string()],
+                });
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(fallback_line);
+
+        if record.iter().all(|field| field.is_empty()) {
+            rows.push(KitImportRowResult {
+                row: line,
+                status: KitImportRowStatus::Skipped,
+                name: None,
+                kit_id: None,
+                errors: vec!["Empty row".to_string()],
+            });
+            continue;
+        }
+
+        let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).unwrap_or("");
+        let name = field(Some(columns.name)).to_string();
+        let mut errors = Vec::new();
+
+        if name.is_empty() {
+            errors.push("name is required".to_string());
+        }
+        let grade = parse_grade(field(Some(columns.grade)))
+            .map_err(|e| errors.push(e))
+            .ok();
+        let status = parse_status(field(columns.status))
+            .map_err(|e| errors.push(e))
+            .ok();
+        let purchase_price = parse_purchase_price(field(columns.purchase_price))
+            .map_err(|e| errors.push(e))
+            .ok()
+            .flatten();
+        let tags = parse_tags(field(columns.tags));
+
+        let (Some(grade), Some(status), true) = (grade, status, errors.is_empty()) else {
+            rows.push(KitImportRowResult {
+                row: line,
+                status: KitImportRowStatus::Failed,
+                name: (!name.is_empty()).then_some(name),
+                kit_id: None,
+                errors,
+            });
+            continue;
+        };
+
+        let key = (name.to_lowercase(), grade.clone());
+        if let Some(previous_line) = seen.get(&key) {
+            rows.push(KitImportRowResult {
+                row: line,
+                status: KitImportRowStatus::Skipped,
+                name: Some(name),
+                kit_id: None,
+                errors: vec![format!("Duplicate of row {}", previous_line)],
+            });
+            continue;
+        }
+        if let Some(kit_id) = existing.get(&key) {
+            rows.push(KitImportRowResult {
+                row: line,
+                status: KitImportRowStatus::Skipped,
+                name: Some(name),
+                kit_id: Some(*kit_id),
+                errors: vec!["Kit already exists".to_string()],
+            });
+            continue;
+        }
+        seen.insert(key, line);
+
+        new_kit_row_indexes.push(rows.len());
+        rows.push(KitImportRowResult {
+            row: line,
+            status: KitImportRowStatus::Created,
+            name: Some(name.clone()),
+            kit_id: None,
+            errors: Vec::new(),
+        });
+        new_kits.push(NewKitRow {
+            name,
+            grade,
+            status,
+            tags,
+            purchase_price,
+        });
+    }
+
+    if !payload.dry_run && !new_kits.is_empty() {
+        let ids = create_kits(&state.db_pool, auth_user.user_id, &new_kits)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        for (row_index, id) in new_kit_row_indexes.into_iter().zip(ids) {
+            rows[row_index].kit_id = Some(id);
+        }
+    }
+
+    let count = |status: KitImportRowStatus| rows.iter().filter(|r| r.status == status).count();
+    Ok(Json(KitImportReport {
+        dry_run: payload.dry_run,
+        total: rows.len(),
+        created: count(KitImportRowStatus::Created),
+        skipped: count(KitImportRowStatus::Skipped),
+        failed: count(KitImportRowStatus::Failed),
+        rows,
+    }))
+}
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::kit_member::{AddKitMemberPayload, KitMember, KitRole, UpdateKitMemberPayload},
+    repository::kit_member::{add_member, get_members, remove_member, update_member_role},
+    state::AppState,
+};
+
+// GET /kits/:id/members
+pub async fn get_kit_members_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+) -> Result<Json<Vec<KitMember>>, (StatusCode, String)> {
+    match get_members(&state.db_pool, kit_id, auth_user.user_id).await {
+        Ok(members) => Ok(Json(members)),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// POST /kits/:id/members
+pub async fn add_kit_member_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+    Json(payload): Json<AddKitMemberPayload>,
+) -> Result<(StatusCode, Json<KitMember>), (StatusCode, String)> {
+    if payload.role == KitRole::Owner {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "A kit can only have one owner".to_string(),
+        ));
+    }
+
+    match add_member(&state.db_pool, kit_id, auth_user.user_id, payload).await {
+        Ok(member) => Ok((StatusCode::CREATED, Json(member))),
+        Err(SqlxError::RowNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Kit or user not found".to_string()))
+        }
+        Err(SqlxError::Database(db_err)) if db_err.is_unique_violation() => Err((
+            StatusCode::CONFLICT,
+            "User is already a member of this kit".to_string(),
+        )),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// PATCH /kits/:id/members/:user_id
+pub async fn update_kit_member_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((kit_id, member_user_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateKitMemberPayload>,
+) -> Result<Json<KitMember>, (StatusCode, String)> {
+    if payload.role == KitRole::Owner {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "A kit can only have one owner".to_string(),
+        ));
+    }
+
+    match update_member_role(
+        &state.db_pool,
+        kit_id,
+        auth_user.user_id,
+        member_user_id,
+        payload,
+    )
+    .await
+    {
+        Ok(member) => Ok(Json(member)),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Member not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// DELETE /kits/:id/members/:user_id
+pub async fn remove_kit_member_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((kit_id, member_user_id)): Path<(i64, i64)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match remove_member(&state.db_pool, kit_id, auth_user.user_id, member_user_id).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Member not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
@@ -4,12 +4,18 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, patch, post},
+    routing::{delete, get, patch, post},
     Json, Router,
 };
 use serde::Deserialize;
 use sqlx::Error as SqlxError;
 
+use crate::api::kit_part_paint::{
+    attach_paint_handler, attach_recipe_handler, detach_paint_handler, detach_recipe_handler,
+    get_kit_part_paints_handler, get_kit_part_recipes_handler,
+};
+use crate::api::workflow::{get_stage_history_handler, set_kit_part_stage_handler};
+use crate::repository::reference::ReferenceError;
 use crate::state::AppState;
 use crate::{
     middleware::auth::AuthUser,
@@ -43,7 +49,12 @@ pub async fn create_kit_part_handler(
 ) -> Result<(StatusCode, Json<KitPart>), (StatusCode, String)> {
     match create_kit_part(&state.db_pool, auth_user.user_id, payload).await {
         Ok(part) => Ok((StatusCode::CREATED, Json(part))),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(ReferenceError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(ReferenceError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(ReferenceError::Db(SqlxError::RowNotFound)) => {
+            Err((StatusCode::NOT_FOUND, "Kit not found".to_string()))
+        }
+        Err(ReferenceError::Db(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
 }
 
@@ -176,6 +187,20 @@ pub fn kit_part_router() -> Router<AppState> {
             "/:id/requirements_with_runners_and_color",
             get(get_all_requirements_with_join_runner_color_handler),
         )
+        .route(
+            "/:id/paints",
+            get(get_kit_part_paints_handler).post(attach_paint_handler),
+        )
+        .route("/:id/paints/:paint_id", delete(detach_paint_handler))
+        // 🧪 สูตรผสมสี (ใช้ role / position แบบเดียวกับ paint)
+        .route(
+            "/:id/recipes",
+            get(get_kit_part_recipes_handler).post(attach_recipe_handler),
+        )
+        .route("/:id/recipes/:recipe_id", delete(detach_recipe_handler))
+        // 🪜 ขั้นตอนทำ part (workflow stage) + ประวัติ
+        .route("/:id/stage", patch(set_kit_part_stage_handler))
+        .route("/:id/stage_history", get(get_stage_history_handler))
 }
 
 pub async fn get_all_requirements_with_join_runner_color_handler(
@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::kit_part_paint::{
+        AttachPaintPayload, AttachRecipePayload, DetachPaintQuery, KitPaintPlan, KitPartPaint,
+        KitPartRecipe,
+    },
+    repository::{
+        kit_part_paint::{
+            attach_paint, attach_recipe, detach_paint, detach_recipe, get_paint_plan,
+            get_paints_for_kit_part, get_recipes_for_kit_part,
+        },
+        reference::ReferenceError,
+    },
+    state::AppState,
+};
+
+fn reference_error(e: ReferenceError) -> (StatusCode, String) {
+    match e {
+        ReferenceError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+        ReferenceError::WrongKit(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
+        ReferenceError::Db(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+// GET /kit_parts/:id/paints
+pub async fn get_kit_part_paints_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<KitPartPaint>>, (StatusCode, String)> {
+    get_paints_for_kit_part(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(reference_error)
+}
+
+// POST /kit_parts/:id/paints
+pub async fn attach_paint_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(payload): Json<AttachPaintPayload>,
+) -> Result<Json<Vec<KitPartPaint>>, (StatusCode, String)> {
+    if payload.position.is_some_and(|position| position < 0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "position must not be negative".to_string(),
+        ));
+    }
+
+    attach_paint(&state.db_pool, id, auth_user.user_id, &payload)
+        .await
+        .map_err(reference_error)?;
+    get_kit_part_paints_handler(State(state), auth_user, Path(id)).await
+}
+
+// DELETE /kit_parts/:id/paints/:paint_id?role=
+pub async fn detach_paint_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, paint_id)): Path<(i64, i64)>,
+    Query(query): Query<DetachPaintQuery>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    detach_paint(&state.db_pool, id, paint_id, query.role, auth_user.user_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(reference_error)
+}
+
+// GET /kit_parts/:id/recipes
+pub async fn get_kit_part_recipes_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<KitPartRecipe>>, (StatusCode, String)> {
+    get_recipes_for_kit_part(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(reference_error)
+}
+
+// POST /kit_parts/:id/recipes
+pub async fn attach_recipe_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(payload): Json<AttachRecipePayload>,
+) -> Result<Json<Vec<KitPartRecipe>>, (StatusCode, String)> {
+    if payload.position.is_some_and(|position| position < 0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "position must not be negative".to_string(),
+        ));
+    }
+
+    attach_recipe(&state.db_pool, id, auth_user.user_id, &payload)
+        .await
+        .map_err(reference_error)?;
+    get_kit_part_recipes_handler(State(state), auth_user, Path(id)).await
+}
+
+// DELETE /kit_parts/:id/recipes/:recipe_id?role=
+pub async fn detach_recipe_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, recipe_id)): Path<(i64, i64)>,
+    Query(query): Query<DetachPaintQuery>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    detach_recipe(&state.db_pool, id, recipe_id, query.role, auth_user.user_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(reference_error)
+}
+
+// GET /kits/:id/paint_plan
+pub async fn get_paint_plan_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+) -> Result<Json<KitPaintPlan>, (StatusCode, String)> {
+    match get_paint_plan(&state.db_pool, kit_id, auth_user.user_id).await {
+        Ok(plan) => Ok(Json(plan)),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
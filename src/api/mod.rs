@@ -1,9 +1,24 @@
 pub mod auth;
 pub mod color;
+pub mod dashboard;
 pub mod i18n;
 pub mod kit;
+pub mod kit_import;
+pub mod kit_member;
 pub mod kit_part;
+pub mod kit_part_paint;
+pub mod paint;
+pub mod paint_catalog;
+pub mod paint_recipe;
+pub mod palette;
+pub mod queue;
 pub mod requirement;
 pub mod runner;
+pub mod runner_color;
+pub mod runner_map;
+pub mod share;
+pub mod spare;
 pub mod steam;
 pub mod sub_assembly;
+pub mod trash;
+pub mod workflow;
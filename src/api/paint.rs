@@ -0,0 +1,220 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    api::{color::parse_hex, paint_catalog::get_paint_substitutes_handler},
+    middleware::auth::AuthUser,
+    model::{
+        common::Message,
+        lab::Lab,
+        paint::{
+            validate_inventory, CreatePaintPayload, DeletePaintQuery, Paint, PaintDeleteError,
+            PaintQuery, PaintUsage, ShoppingList, UpdatePaintPayload,
+        },
+    },
+    repository::paint::{
+        create_paint, delete_paint, get_paint_by_id, get_paint_usage_for_user, get_paints,
+        get_shopping_list, update_paint, DeletePaintError,
+    },
+    state::AppState,
+};
+
+fn paint_error(action: &str, e: SqlxError) -> (StatusCode, Json<Message>) {
+    let status = match e {
+        SqlxError::RowNotFound => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (
+        status,
+        Json(Message {
+            message: format!("Failed to {} paint: {}", action, e),
+        }),
+    )
+}
+
+fn bad_request(message: String) -> (StatusCode, Json<Message>) {
+    (StatusCode::BAD_REQUEST, Json(Message { message }))
+}
+
+// hex ไม่บังคับ: ส่งมา = ต้องถูกต้อง (normalize + Lab สำหรับค้นหาสีใกล้เคียง)
+fn parse_optional_hex(
+    hex: &mut Option<String>,
+) -> Result<Option<Lab>, (StatusCode, Json<Message>)> {
+    match hex.as_deref() {
+        Some(raw) => {
+            let (normalized, lab) = parse_hex(raw)?;
+            *hex = Some(normalized);
+            Ok(Some(lab))
+        }
+        None => Ok(None),
+    }
+}
+
+// GET /paints?brand=&paint_type=&finish=
+async fn get_paints_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<PaintQuery>,
+) -> Result<Json<Vec<Paint>>, (StatusCode, Json<Message>)> {
+    get_paints(&state.db_pool, auth_user.user_id, &query)
+        .await
+        .map(Json)
+        .map_err(|e| paint_error("list", e))
+}
+
+async fn get_paint_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+) -> Result<Json<Paint>, (StatusCode, Json<Message>)> {
+    get_paint_by_id(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(|e| paint_error("get", e))
+}
+
+async fn create_paint_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(mut payload): Json<CreatePaintPayload>,
+) -> Result<(StatusCode, Json<Paint>), (StatusCode, Json<Message>)> {
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        return Err(bad_request("name is required".to_string()));
+    }
+    validate_inventory(
+        payload.quantity,
+        payload.fill_level,
+        payload.low_stock_threshold,
+    )
+    .map_err(bad_request)?;
+    let lab = parse_optional_hex(&mut payload.hex)?;
+
+    create_paint(&state.db_pool, auth_user.user_id, payload, lab)
+        .await
+        .map(|paint| (StatusCode::CREATED, Json(paint)))
+        .map_err(|e| paint_error("create", e))
+}
+
+async fn update_paint_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    Json(mut payload): Json<UpdatePaintPayload>,
+) -> Result<Json<Paint>, (StatusCode, Json<Message>)> {
+    if let Some(name) = payload.name.as_mut() {
+        *name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(bad_request("name must not be empty".to_string()));
+        }
+    }
+    validate_inventory(
+        payload.quantity,
+        payload.fill_level,
+        payload.low_stock_threshold,
+    )
+    .map_err(bad_request)?;
+    let lab = parse_optional_hex(&mut payload.hex)?;
+
+    update_paint(&state.db_pool, id, auth_user.user_id, payload, lab)
+        .await
+        .map(Json)
+        .map_err(|e| paint_error("update", e))
+}
+
+// DELETE /paints/:id?reassign_to= — 409 พร้อมรายการ part / สูตรที่ยังใช้ paint นี้อยู่
+async fn delete_paint_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    Query(query): Query<DeletePaintQuery>,
+) -> Result<StatusCode, (StatusCode, Json<PaintDeleteError>)> {
+    match delete_paint(&state.db_pool, id, auth_user.user_id, query.reassign_to).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(DeletePaintError::NotFound) => Err(delete_error(
+            StatusCode::NOT_FOUND,
+            "Paint not found".to_string(),
+            None,
+        )),
+        Err(DeletePaintError::InUse(usage)) => Err(delete_error(
+            StatusCode::CONFLICT,
+            format!(
+                "Paint is used by {} part(s) in {} kit(s) and {} recipe(s); pass reassign_to to move them",
+                usage.part_count + usage.other_part_count,
+                usage.kit_count + usage.other_kit_count,
+                usage.recipes.len()
+            ),
+            Some(usage),
+        )),
+        Err(DeletePaintError::InvalidTarget(msg)) => {
+            Err(delete_error(StatusCode::UNPROCESSABLE_ENTITY, msg, None))
+        }
+        Err(DeletePaintError::Db(e)) => Err(delete_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete paint: {}", e),
+            None,
+        )),
+    }
+}
+
+fn delete_error(
+    status: StatusCode,
+    message: String,
+    usage: Option<PaintUsage>,
+) -> (StatusCode, Json<PaintDeleteError>) {
+    (status, Json(PaintDeleteError { message, usage }))
+}
+
+// GET /paints/:id/usage
+async fn get_paint_usage_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+) -> Result<Json<PaintUsage>, (StatusCode, Json<Message>)> {
+    get_paint_usage_for_user(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(|e| paint_error("load usage of", e))
+}
+
+// GET /kits/:id/shopping-list
+pub async fn get_kit_shopping_list_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+) -> Result<Json<ShoppingList>, (StatusCode, String)> {
+    match get_shopping_list(&state.db_pool, auth_user.user_id, Some(kit_id)).await {
+        Ok(list) => Ok(Json(list)),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// GET /kits/shopping-list (รวมทุก kit ที่ pending / in_progress)
+pub async fn get_shopping_list_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<ShoppingList>, (StatusCode, String)> {
+    get_shopping_list(&state.db_pool, auth_user.user_id, None)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub fn paint_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_paints_handler).post(create_paint_handler))
+        .route(
+            "/:id",
+            get(get_paint_handler)
+                .patch(update_paint_handler)
+                .delete(delete_paint_handler),
+        )
+        .route("/:id/usage", get(get_paint_usage_handler))
+        .route("/:id/substitutes", get(get_paint_substitutes_handler))
+}
@@ -0,0 +1,439 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::{AdminUser, AuthUser},
+    model::{
+        common::Message,
+        lab::hex_to_lab,
+        paint::{validate_inventory, Paint, PaintType},
+        paint_catalog::{
+            catalog_key, parse_catalog, parse_equivalent_ref, AddCatalogPaintPayload, CatalogEntry,
+            CatalogFormat, CatalogImportEntryResult, CatalogImportReport, CatalogKey, CatalogPaint,
+            CatalogQuery, ImportCatalogQuery, Substitute, SubstituteQuery,
+        },
+        palette::PaletteImportStatus,
+    },
+    repository::{
+        paint::get_paint_by_id,
+        paint_catalog::{
+            add_catalog_paint_to_inventory, delete_catalog_paint, get_catalog_paint_by_id,
+            get_catalog_paints, get_substitutes, import_catalog_paints, link_equivalents,
+            unlink_equivalent,
+        },
+    },
+    state::AppState,
+};
+
+const DEFAULT_MAX_DISTANCE: f64 = 5.0; // 👈 ΔE00 ~5 = ต่างกันเห็นได้แต่ยังใช้แทนกันได้
+
+fn message(status: StatusCode, message: String) -> (StatusCode, Json<Message>) {
+    (status, Json(Message { message }))
+}
+
+fn catalog_error(e: SqlxError) -> (StatusCode, Json<Message>) {
+    match e {
+        SqlxError::RowNotFound => {
+            message(StatusCode::NOT_FOUND, "Catalog paint not found".to_string())
+        }
+        e => message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+// GET /paint_catalog?brand=&line=&paint_type=&q=
+async fn get_catalog_handler(
+    State(state): State<AppState>,
+    _auth_user: AuthUser,
+    Query(query): Query<CatalogQuery>,
+) -> Result<Json<Vec<CatalogPaint>>, (StatusCode, Json<Message>)> {
+    get_catalog_paints(&state.db_pool, &query)
+        .await
+        .map(Json)
+        .map_err(catalog_error)
+}
+
+async fn get_catalog_paint_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    _auth_user: AuthUser,
+) -> Result<Json<CatalogPaint>, (StatusCode, Json<Message>)> {
+    get_catalog_paint_by_id(&state.db_pool, id)
+        .await
+        .map(Json)
+        .map_err(catalog_error)
+}
+
+// DELETE /paint_catalog/:id (admin)
+async fn delete_catalog_paint_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    _admin: AdminUser,
+) -> Result<StatusCode, (StatusCode, Json<Message>)> {
+    delete_catalog_paint(&state.db_pool, id)
+        .await
+        .map_err(catalog_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /paint_catalog/:id/inventory — เพิ่มเข้า paint ของเรา (ครั้งเดียวต่อรายการ)
+async fn add_to_inventory_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    payload: Option<Json<AddCatalogPaintPayload>>,
+) -> Result<(StatusCode, Json<Paint>), (StatusCode, Json<Message>)> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    validate_inventory(payload.quantity, None, None)
+        .map_err(|msg| message(StatusCode::BAD_REQUEST, msg))?;
+
+    match add_catalog_paint_to_inventory(
+        &state.db_pool,
+        id,
+        auth_user.user_id,
+        payload.quantity.unwrap_or(1),
+    )
+    .await
+    {
+        Ok(paint) => Ok((StatusCode::CREATED, Json(paint))),
+        Err(e)
+            if e.as_database_error()
+                .is_some_and(|db_err| db_err.is_unique_violation()) =>
+        {
+            Err(message(
+                StatusCode::CONFLICT,
+                "This catalog paint is already in your inventory".to_string(),
+            ))
+        }
+        Err(e) => Err(catalog_error(e)),
+    }
+}
+
+// GET /paint_catalog/:id/substitutes?limit=&max_distance=
+async fn get_catalog_substitutes_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    Query(query): Query<SubstituteQuery>,
+) -> Result<Json<Vec<Substitute>>, (StatusCode, Json<Message>)> {
+    let paint = get_catalog_paint_by_id(&state.db_pool, id)
+        .await
+        .map_err(catalog_error)?;
+    let lab = paint.hex.as_deref().and_then(hex_to_lab);
+
+    get_substitutes(
+        &state.db_pool,
+        auth_user.user_id,
+        Some(paint.id),
+        Some(&paint.brand),
+        lab,
+        query.max_distance.unwrap_or(DEFAULT_MAX_DISTANCE),
+        query.limit.unwrap_or(10).clamp(1, 100),
+    )
+    .await
+    .map(Json)
+    .map_err(catalog_error)
+}
+
+// GET /paints/:id/substitutes — paint ของเราหมด ใช้อะไรแทนได้บ้าง
+pub async fn get_paint_substitutes_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    Query(query): Query<SubstituteQuery>,
+) -> Result<Json<Vec<Substitute>>, (StatusCode, Json<Message>)> {
+    let paint = get_paint_by_id(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map_err(|e| match e {
+            SqlxError::RowNotFound => message(StatusCode::NOT_FOUND, "Paint not found".to_string()),
+            e => message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+    let lab = paint.hex.as_deref().and_then(hex_to_lab);
+
+    get_substitutes(
+        &state.db_pool,
+        auth_user.user_id,
+        paint.catalog_id,
+        paint.brand.as_deref(),
+        lab,
+        query.max_distance.unwrap_or(DEFAULT_MAX_DISTANCE),
+        query.limit.unwrap_or(10).clamp(1, 100),
+    )
+    .await
+    .map(Json)
+    .map_err(catalog_error)
+}
+
+// POST /paint_catalog/:id/equivalents/:other_id (admin)
+async fn link_equivalent_handler(
+    State(state): State<AppState>,
+    Path((id, other_id)): Path<(i64, i64)>,
+    _admin: AdminUser,
+) -> Result<StatusCode, (StatusCode, Json<Message>)> {
+    if id == other_id {
+        return Err(message(
+            StatusCode::BAD_REQUEST,
+            "A paint cannot be equivalent to itself".to_string(),
+        ));
+    }
+    for paint_id in [id, other_id] {
+        get_catalog_paint_by_id(&state.db_pool, paint_id)
+            .await
+            .map_err(catalog_error)?;
+    }
+
+    link_equivalents(&state.db_pool, &[(id, other_id)])
+        .await
+        .map_err(catalog_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// DELETE /paint_catalog/:id/equivalents/:other_id (admin)
+async fn unlink_equivalent_handler(
+    State(state): State<AppState>,
+    Path((id, other_id)): Path<(i64, i64)>,
+    _admin: AdminUser,
+) -> Result<StatusCode, (StatusCode, Json<Message>)> {
+    match unlink_equivalent(&state.db_pool, id, other_id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(SqlxError::RowNotFound) => Err(message(
+            StatusCode::NOT_FOUND,
+            "These paints are not linked".to_string(),
+        )),
+        Err(e) => Err(catalog_error(e)),
+    }
+}
+
+// POST /paint_catalog/import?format=csv|json&dry_run= (admin, multipart field "file")
+// upsert ตาม (brand, code) แบบไม่สนตัวพิมพ์ แล้วผูก equivalents ("Brand:Code") ที่อ้างถึงได้
+async fn import_catalog_handler(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    Query(query): Query<ImportCatalogQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<CatalogImportReport>, (StatusCode, String)> {
+    let mut file = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?
+    {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().map(|name| name.to_string());
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+            file = Some((file_name, bytes));
+            break;
+        }
+    }
+    let (file_name, bytes) = file.ok_or((
+        StatusCode::BAD_REQUEST,
+        "Missing multipart field: file".to_string(),
+    ))?;
+
+    let format = query
+        .format
+        .or_else(|| file_name.as_deref().and_then(CatalogFormat::from_file_name))
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            "Unknown catalog format; pass ?format=csv|json".to_string(),
+        ))?;
+    let parsed = parse_catalog(format, &bytes).map_err(|msg| (StatusCode::BAD_REQUEST, msg))?;
+
+    let existing: HashMap<CatalogKey, CatalogPaint> = get_catalog_paints(
+        &state.db_pool,
+        &CatalogQuery {
+            brand: None,
+            line: None,
+            paint_type: None,
+            q: None,
+        },
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .into_iter()
+    .map(|paint| (catalog_key(&paint.brand, &paint.code), paint))
+    .collect();
+    let mut seen: HashMap<CatalogKey, u64> = HashMap::new();
+
+    let mut entries: Vec<CatalogImportEntryResult> = Vec::new();
+    let mut writes: Vec<(CatalogEntry, Option<PaintType>)> = Vec::new();
+    let mut write_indexes: Vec<usize> = Vec::new();
+    // (index ใน entries, key ของแถว, equivalents ที่อ้างถึง)
+    let mut references: Vec<(usize, CatalogKey, Vec<String>)> = Vec::new();
+
+    for (row, entry) in parsed {
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(msg) => {
+                entries.push(CatalogImportEntryResult {
+                    row,
+                    status: PaletteImportStatus::Failed,
+                    brand: None,
+                    code: None,
+                    catalog_id: None,
+                    errors: vec![msg],
+                    warnings: Vec::new(),
+                });
+                continue;
+            }
+        };
+        let paint_type = match entry.normalize() {
+            Ok(paint_type) => paint_type,
+            Err(errors) => {
+                entries.push(CatalogImportEntryResult {
+                    row,
+                    status: PaletteImportStatus::Failed,
+                    brand: (!entry.brand.is_empty()).then_some(entry.brand),
+                    code: (!entry.code.is_empty()).then_some(entry.code),
+                    catalog_id: None,
+                    errors,
+                    warnings: Vec::new(),
+                });
+                continue;
+            }
+        };
+
+        let key = catalog_key(&entry.brand, &entry.code);
+        if let Some(previous_row) = seen.get(&key) {
+            entries.push(CatalogImportEntryResult {
+                row,
+                status: PaletteImportStatus::Skipped,
+                brand: Some(entry.brand),
+                code: Some(entry.code),
+                catalog_id: None,
+                errors: vec![format!("Duplicate of row {}", previous_row)],
+                warnings: Vec::new(),
+            });
+            continue;
+        }
+        seen.insert(key.clone(), row);
+
+        // unique index ใน DB ก็ไม่สนตัวพิมพ์ ต่างแค่ตัวพิมพ์ = อัปเดตรายการเดิม
+        let (status, catalog_id) = match existing.get(&key) {
+            None => (PaletteImportStatus::Created, None),
+            Some(current) => {
+                let same = current.brand == entry.brand
+                    && current.code == entry.code
+                    && current.line == entry.line
+                    && current.name == entry.name
+                    && current.hex == entry.hex
+                    && current.paint_type == paint_type;
+                let status = if same {
+                    PaletteImportStatus::Unchanged
+                } else {
+                    PaletteImportStatus::Updated
+                };
+                (status, Some(current.id))
+            }
+        };
+
+        if !entry.equivalents.is_empty() {
+            references.push((entries.len(), key, entry.equivalents.clone()));
+        }
+        if status != PaletteImportStatus::Unchanged {
+            write_indexes.push(entries.len());
+            writes.push((entry.clone(), paint_type));
+        }
+        entries.push(CatalogImportEntryResult {
+            row,
+            status,
+            brand: Some(entry.brand),
+            code: Some(entry.code),
+            catalog_id,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        });
+    }
+
+    // equivalents: อ้างถึงรายการในไฟล์นี้หรือที่มีอยู่แล้วใน catalog (เทียบด้วย key)
+    let mut known: HashSet<CatalogKey> = existing.keys().cloned().collect();
+    for entry in &entries {
+        if let (
+            Some(brand),
+            Some(code),
+            PaletteImportStatus::Created
+            | PaletteImportStatus::Updated
+            | PaletteImportStatus::Unchanged,
+        ) = (&entry.brand, &entry.code, entry.status)
+        {
+            known.insert(catalog_key(brand, code));
+        }
+    }
+
+    let mut pairs: HashSet<(CatalogKey, CatalogKey)> = HashSet::new();
+    for (entry_index, key, refs) in references {
+        for raw in refs {
+            let Some((brand, code)) = parse_equivalent_ref(&raw) else {
+                entries[entry_index]
+                    .warnings
+                    .push(format!("Invalid equivalent: {} (expected Brand:Code)", raw));
+                continue;
+            };
+            let other = catalog_key(&brand, &code);
+            if other == key {
+                continue;
+            }
+            if !known.contains(&other) {
+                entries[entry_index]
+                    .warnings
+                    .push(format!("Equivalent not found: {}", raw));
+                continue;
+            }
+            pairs.insert(if key < other {
+                (key.clone(), other)
+            } else {
+                (other, key.clone())
+            });
+        }
+    }
+
+    // upsert + equivalents ใน transaction เดียว (พังกลางทาง = ไม่มีอะไรถูกบันทึก)
+    if !query.dry_run && (!writes.is_empty() || !pairs.is_empty()) {
+        let equivalents: Vec<(CatalogKey, CatalogKey)> = pairs.iter().cloned().collect();
+        let ids = import_catalog_paints(&state.db_pool, &writes, &equivalents)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        for (entry_index, id) in write_indexes.into_iter().zip(ids) {
+            entries[entry_index].catalog_id = Some(id);
+        }
+    }
+
+    let count = |status: PaletteImportStatus| entries.iter().filter(|e| e.status == status).count();
+    Ok(Json(CatalogImportReport {
+        format,
+        dry_run: query.dry_run,
+        total: entries.len(),
+        created: count(PaletteImportStatus::Created),
+        updated: count(PaletteImportStatus::Updated),
+        unchanged: count(PaletteImportStatus::Unchanged),
+        skipped: count(PaletteImportStatus::Skipped),
+        failed: count(PaletteImportStatus::Failed),
+        equivalents: pairs.len(),
+        entries,
+    }))
+}
+
+pub fn paint_catalog_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_catalog_handler))
+        .route("/import", post(import_catalog_handler))
+        .route(
+            "/:id",
+            get(get_catalog_paint_handler).delete(delete_catalog_paint_handler),
+        )
+        .route("/:id/inventory", post(add_to_inventory_handler))
+        .route("/:id/substitutes", get(get_catalog_substitutes_handler))
+        .route(
+            "/:id/equivalents/:other_id",
+            post(link_equivalent_handler).delete(unlink_equivalent_handler),
+        )
+}
@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::{
+        common::Message,
+        paint_recipe::{
+            validate_components, validate_thinner_ratio, CreateRecipePayload, PaintRecipe,
+            UpdateRecipePayload,
+        },
+    },
+    repository::paint_recipe::{
+        create_recipe, delete_recipe, get_recipe_by_id, get_recipes, update_recipe, RecipeError,
+    },
+    state::AppState,
+};
+
+fn message(status: StatusCode, message: String) -> (StatusCode, Json<Message>) {
+    (status, Json(Message { message }))
+}
+
+fn recipe_error(action: &str, e: RecipeError) -> (StatusCode, Json<Message>) {
+    match e {
+        RecipeError::NotFound | RecipeError::Db(SqlxError::RowNotFound) => message(
+            StatusCode::NOT_FOUND,
+            format!("Failed to {} recipe: Recipe not found", action),
+        ),
+        RecipeError::UnknownPaint(paint_id) => message(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Paint {} not found", paint_id),
+        ),
+        RecipeError::Db(e) => message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to {} recipe: {}", action, e),
+        ),
+    }
+}
+
+// GET /recipes
+async fn get_recipes_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<Vec<PaintRecipe>>, (StatusCode, Json<Message>)> {
+    get_recipes(&state.db_pool, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(|e| recipe_error("list", e.into()))
+}
+
+async fn get_recipe_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+) -> Result<Json<PaintRecipe>, (StatusCode, Json<Message>)> {
+    get_recipe_by_id(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(|e| recipe_error("get", e.into()))
+}
+
+async fn create_recipe_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(mut payload): Json<CreateRecipePayload>,
+) -> Result<(StatusCode, Json<PaintRecipe>), (StatusCode, Json<Message>)> {
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        return Err(message(
+            StatusCode::BAD_REQUEST,
+            "name is required".to_string(),
+        ));
+    }
+    validate_thinner_ratio(payload.thinner_ratio)
+        .and_then(|_| validate_components(&payload.components))
+        .map_err(|msg| message(StatusCode::BAD_REQUEST, msg))?;
+
+    create_recipe(&state.db_pool, auth_user.user_id, &payload)
+        .await
+        .map(|recipe| (StatusCode::CREATED, Json(recipe)))
+        .map_err(|e| recipe_error("create", e))
+}
+
+async fn update_recipe_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    Json(mut payload): Json<UpdateRecipePayload>,
+) -> Result<Json<PaintRecipe>, (StatusCode, Json<Message>)> {
+    if let Some(name) = payload.name.as_mut() {
+        *name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(message(
+                StatusCode::BAD_REQUEST,
+                "name must not be empty".to_string(),
+            ));
+        }
+    }
+    validate_thinner_ratio(payload.thinner_ratio)
+        .and_then(|_| match &payload.components {
+            Some(components) => validate_components(components),
+            None => Ok(()),
+        })
+        .map_err(|msg| message(StatusCode::BAD_REQUEST, msg))?;
+
+    update_recipe(&state.db_pool, id, auth_user.user_id, &payload)
+        .await
+        .map(Json)
+        .map_err(|e| recipe_error("update", e))
+}
+
+async fn delete_recipe_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+) -> Result<StatusCode, (StatusCode, Json<Message>)> {
+    delete_recipe(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|e| recipe_error("delete", e.into()))
+}
+
+pub fn recipe_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_recipes_handler).post(create_recipe_handler))
+        .route(
+            "/:id",
+            get(get_recipe_handler)
+                .patch(update_recipe_handler)
+                .delete(delete_recipe_handler),
+        )
+}
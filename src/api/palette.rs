@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Multipart, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::{
+        color::Color,
+        lab::{hex_to_lab, normalize_hex, Lab},
+        palette::{
+            export_palette, parse_palette, ExportPaletteQuery, ImportPaletteQuery, PaletteEntry,
+            PaletteFormat, PaletteImportEntryResult, PaletteImportReport, PaletteImportStatus,
+        },
+    },
+    repository::color::{get_colors, upsert_palette_colors},
+    state::AppState,
+};
+
+// สีส่วนตัวเท่านั้น (preset ไม่ใช่ของ user) ลำดับเดียวกับ GET /colors
+async fn get_personal_colors(
+    state: &AppState,
+    user_id: i64,
+) -> Result<Vec<Color>, (StatusCode, String)> {
+    let colors = get_colors(&state.db_pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(colors.into_iter().filter(|c| !c.is_preset).collect())
+}
+
+// GET /colors/export?format=csv|json|gpl|ase
+pub async fn export_palette_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<ExportPaletteQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let colors = get_personal_colors(&state, auth_user.user_id).await?;
+    let body = export_palette(query.format, &colors)
+        .map_err(|msg| (StatusCode::INTERNAL_SERVER_ERROR, msg))?;
+
+    let disposition = format!(
+        "attachment; filename=\"colors.{}\"",
+        query.format.extension()
+    );
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+// POST /colors/import?format=&dry_run= (multipart, field "file")
+// upsert ตาม (name, code): ไม่มี = สร้าง, มีแล้วค่าต่าง = อัปเดต, เหมือนเดิม = unchanged
+pub async fn import_palette_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<ImportPaletteQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<PaletteImportReport>, (StatusCode, String)> {
+    let mut file = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?
+    {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().map(|name| name.to_string());
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+            file = Some((file_name, bytes));
+            break;
+        }
+    }
+    let (file_name, bytes) = file.ok_or((
+        StatusCode::BAD_REQUEST,
+        "Missing multipart field: file".to_string(),
+    ))?;
+
+    let format = query
+        .format
+        .or_else(|| file_name.as_deref().and_then(PaletteFormat::from_file_name))
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            "Unknown palette format; pass ?format=csv|json|gpl|ase".to_string(),
+        ))?;
+    let parsed = parse_palette(format, &bytes).map_err(|msg| (StatusCode::BAD_REQUEST, msg))?;
+
+    let existing: HashMap<(String, String), Color> = get_personal_colors(&state, auth_user.user_id)
+        .await?
+        .into_iter()
+        .map(|c| ((c.name.clone(), c.code.clone()), c))
+        .collect();
+    let mut seen: HashMap<(String, String), u64> = HashMap::new();
+
+    let mut entries: Vec<PaletteImportEntryResult> = Vec::new();
+    let mut writes: Vec<(PaletteEntry, Lab)> = Vec::new();
+    let mut write_indexes: Vec<usize> = Vec::new();
+
+    for (row, entry) in parsed {
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(msg) => {
+                entries.push(PaletteImportEntryResult {
+                    row,
+                    status: PaletteImportStatus::Failed,
+                    name: None,
+                    code: None,
+                    color_id: None,
+                    errors: vec![msg],
+                });
+                continue;
+            }
+        };
+        entry.name = entry.name.trim().to_string();
+        entry.code = entry.code.trim().to_string();
+
+        let mut errors = Vec::new();
+        if entry.name.is_empty() {
+            errors.push("name is required".to_string());
+        }
+        let lab = match normalize_hex(&entry.hex) {
+            Ok(hex) => {
+                entry.hex = hex;
+                hex_to_lab(&entry.hex)
+            }
+            Err(msg) => {
+                errors.push(msg);
+                None
+            }
+        };
+        let (Some(lab), true) = (lab, errors.is_empty()) else {
+            entries.push(PaletteImportEntryResult {
+                row,
+                status: PaletteImportStatus::Failed,
+                name: (!entry.name.is_empty()).then_some(entry.name),
+                code: Some(entry.code),
+                color_id: None,
+                errors,
+            });
+            continue;
+        };
+
+        let key = (entry.name.clone(), entry.code.clone());
+        if let Some(previous_row) = seen.get(&key) {
+            entries.push(PaletteImportEntryResult {
+                row,
+                status: PaletteImportStatus::Skipped,
+                name: Some(entry.name),
+                code: Some(entry.code),
+                color_id: None,
+                errors: vec![format!("Duplicate of row {}", previous_row)],
+            });
+            continue;
+        }
+        seen.insert(key.clone(), row);
+
+        let (status, color_id) = match existing.get(&key) {
+            None => (PaletteImportStatus::Created, None),
+            Some(current) => {
+                let same = current.hex == entry.hex
+                    && entry.is_clear.is_none_or(|v| v == current.is_clear)
+                    && entry.is_multi.is_none_or(|v| v == current.is_multi);
+                let status = if same {
+                    PaletteImportStatus::Unchanged
+                } else {
+                    PaletteImportStatus::Updated
+                };
+                (status, current.id)
+            }
+        };
+
+        if status != PaletteImportStatus::Unchanged {
+            write_indexes.push(entries.len());
+            writes.push((entry.clone(), lab));
+        }
+        entries.push(PaletteImportEntryResult {
+            row,
+            status,
+            name: Some(entry.name),
+            code: Some(entry.code),
+            color_id,
+            errors: Vec::new(),
+        });
+    }
+
+    if !query.dry_run && !writes.is_empty() {
+        let ids = upsert_palette_colors(&state.db_pool, auth_user.user_id, &writes)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        for (entry_index, id) in write_indexes.into_iter().zip(ids) {
+            entries[entry_index].color_id = Some(id);
+        }
+    }
+
+    let count = |status: PaletteImportStatus| entries.iter().filter(|e| e.status == status).count();
+    Ok(Json(PaletteImportReport {
+        format,
+        dry_run: query.dry_run,
+        total: entries.len(),
+        created: count(PaletteImportStatus::Created),
+        updated: count(PaletteImportStatus::Updated),
+        unchanged: count(PaletteImportStatus::Unchanged),
+        skipped: count(PaletteImportStatus::Skipped),
+        failed: count(PaletteImportStatus::Failed),
+        entries,
+    }))
+}
@@ -0,0 +1,139 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, patch, put},
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::queue::{AddQueueEntryPayload, KitQueueEntry, MoveQueueEntryPayload, NextKit},
+    repository::queue::{
+        add_to_queue, get_next_kit, get_queue, move_queue_entry, remove_from_queue,
+        set_queue_entry_pinned,
+    },
+    state::AppState,
+};
+
+// GET /queue
+pub async fn get_queue_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<Vec<KitQueueEntry>>, (StatusCode, String)> {
+    match get_queue(&state.db_pool, auth_user.user_id).await {
+        Ok(entries) => Ok(Json(entries)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// POST /queue
+pub async fn add_to_queue_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<AddQueueEntryPayload>,
+) -> Result<(StatusCode, Json<KitQueueEntry>), (StatusCode, String)> {
+    match add_to_queue(&state.db_pool, auth_user.user_id, payload).await {
+        Ok(entry) => Ok((StatusCode::CREATED, Json(entry))),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
+        Err(SqlxError::Database(db_err)) if db_err.is_unique_violation() => Err((
+            StatusCode::CONFLICT,
+            "Kit is already in the queue".to_string(),
+        )),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// PATCH /queue/:kit_id/move
+pub async fn move_queue_entry_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+    Json(payload): Json<MoveQueueEntryPayload>,
+) -> Result<Json<KitQueueEntry>, (StatusCode, String)> {
+    if payload.position < 1 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "position must be 1 or greater".to_string(),
+        ));
+    }
+
+    match move_queue_entry(&state.db_pool, auth_user.user_id, kit_id, payload.position).await {
+        Ok(entry) => Ok(Json(entry)),
+        Err(SqlxError::RowNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Kit is not in the queue".to_string()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// PUT /queue/:kit_id/pin
+pub async fn pin_queue_entry_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+) -> Result<Json<KitQueueEntry>, (StatusCode, String)> {
+    match set_queue_entry_pinned(&state.db_pool, auth_user.user_id, kit_id, true).await {
+        Ok(entry) => Ok(Json(entry)),
+        Err(SqlxError::RowNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Kit is not in the queue".to_string()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// DELETE /queue/:kit_id/pin
+pub async fn unpin_queue_entry_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+) -> Result<Json<KitQueueEntry>, (StatusCode, String)> {
+    match set_queue_entry_pinned(&state.db_pool, auth_user.user_id, kit_id, false).await {
+        Ok(entry) => Ok(Json(entry)),
+        Err(SqlxError::RowNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Kit is not in the queue".to_string()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// DELETE /queue/:kit_id
+pub async fn remove_from_queue_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match remove_from_queue(&state.db_pool, auth_user.user_id, kit_id).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(SqlxError::RowNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Kit is not in the queue".to_string()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// GET /kits/next
+pub async fn get_next_kit_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<NextKit>, (StatusCode, String)> {
+    match get_next_kit(&state.db_pool, auth_user.user_id).await {
+        Ok(next) => Ok(Json(next)),
+        Err(SqlxError::RowNotFound) => Err((
+            StatusCode::NOT_FOUND,
+            "No pending kit in the queue".to_string(),
+        )),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub fn queue_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_queue_handler).post(add_to_queue_handler))
+        .route("/:kit_id", delete(remove_from_queue_handler))
+        .route("/:kit_id/move", patch(move_queue_entry_handler))
+        .route(
+            "/:kit_id/pin",
+            put(pin_queue_entry_handler).delete(unpin_queue_entry_handler),
+        )
+}
@@ -1,4 +1,7 @@
 use crate::middleware::auth::AuthUser;
+use crate::model::gate::{
+    compact_gates, parse_gate_expressions, ParseGatesPayload, ParsedGatesResponse,
+};
 use crate::model::kit_part::KitPartRequirement;
 use crate::model::requirement::{
     BulkCreateRequirementsPayload, BulkDeleteRequirementsPayload, BulkSyncRequirementsPayload,
@@ -7,7 +10,9 @@ use crate::model::requirement::{
 use crate::repository::requirement::{
     bulk_create_requirements, bulk_delete_requirements, bulk_sync_requirements,
     bulk_update_requirements, compare_sync_requirements, create_kit_part_requirement,
+    RequirementWriteError,
 };
+use crate::repository::runner::get_runner_gates;
 
 use sqlx::Error as SqlxError;
 
@@ -27,11 +32,18 @@ pub async fn compare_sync_requirements_handler(
 ) -> Result<Json<Vec<KitPartRequirement>>, (StatusCode, String)> {
     match compare_sync_requirements(&state.db_pool, auth_user.user_id, payload).await {
         Ok(reqs) => Ok(Json(reqs)),
-        Err(SqlxError::RowNotFound) => Err((
+        Err(RequirementWriteError::Db(SqlxError::RowNotFound)) => Err((
             StatusCode::NOT_FOUND,
             "Some requirements not found".to_string(),
         )),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(RequirementWriteError::InvalidGates(errors)) => {
+            Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("; ")))
+        }
+        Err(RequirementWriteError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(RequirementWriteError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(RequirementWriteError::Db(e)) => {
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
     }
 }
 
@@ -53,11 +65,18 @@ pub async fn bulk_create_requirements_handler(
 ) -> Result<Json<Vec<KitPartRequirement>>, (StatusCode, String)> {
     match bulk_create_requirements(&state.db_pool, auth_user.user_id, payload).await {
         Ok(reqs) => Ok(Json(reqs)),
-        Err(SqlxError::RowNotFound) => Err((
+        Err(RequirementWriteError::Db(SqlxError::RowNotFound)) => Err((
             StatusCode::NOT_FOUND,
             "Kit part not found for some items".to_string(),
         )),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(RequirementWriteError::InvalidGates(errors)) => {
+            Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("; ")))
+        }
+        Err(RequirementWriteError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(RequirementWriteError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(RequirementWriteError::Db(e)) => {
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
     }
 }
 
@@ -68,11 +87,18 @@ pub async fn bulk_update_requirements_handler(
 ) -> Result<Json<Vec<KitPartRequirement>>, (StatusCode, String)> {
     match bulk_update_requirements(&state.db_pool, auth_user.user_id, payload).await {
         Ok(reqs) => Ok(Json(reqs)),
-        Err(SqlxError::RowNotFound) => Err((
+        Err(RequirementWriteError::Db(SqlxError::RowNotFound)) => Err((
             StatusCode::NOT_FOUND,
             "Some requirements not found".to_string(),
         )),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(RequirementWriteError::InvalidGates(errors)) => {
+            Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("; ")))
+        }
+        Err(RequirementWriteError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(RequirementWriteError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(RequirementWriteError::Db(e)) => {
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
     }
 }
 
@@ -84,7 +110,17 @@ pub async fn create_kit_part_requirement_handler(
     // We should probably verify ownership of the parent kit_part_id here in a real app
     match create_kit_part_requirement(&state.db_pool, auth_user.user_id, payload).await {
         Ok(req) => Ok((StatusCode::CREATED, Json(req))),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(RequirementWriteError::Db(SqlxError::RowNotFound)) => {
+            Err((StatusCode::NOT_FOUND, "Kit part not found".to_string()))
+        }
+        Err(RequirementWriteError::InvalidGates(errors)) => {
+            Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("; ")))
+        }
+        Err(RequirementWriteError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(RequirementWriteError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(RequirementWriteError::Db(e)) => {
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
     }
 }
 
@@ -126,14 +162,66 @@ pub async fn bulk_sync_requirements_handler(
 ) -> Result<Json<Vec<KitPartRequirement>>, (StatusCode, String)> {
     match bulk_sync_requirements(&state.db_pool, auth_user.user_id, payload).await {
         Ok(reqs) => Ok(Json(reqs)),
-        Err(SqlxError::RowNotFound) => Err((
+        Err(RequirementWriteError::Db(SqlxError::RowNotFound)) => Err((
             StatusCode::NOT_FOUND,
             "Some requirements not found".to_string(),
         )),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(RequirementWriteError::InvalidGates(errors)) => {
+            Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("; ")))
+        }
+        Err(RequirementWriteError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(RequirementWriteError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(RequirementWriteError::Db(e)) => {
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
     }
 }
 
+// POST /requirements/gates/parse
+// กระจาย gate expression เป็นรายตัว (ใส่ runner_id เพื่อตรวจกับ gate inventory, ใส่ qty เพื่อเทียบจำนวน)
+pub async fn parse_gates_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<ParseGatesPayload>,
+) -> Result<Json<ParsedGatesResponse>, (StatusCode, String)> {
+    let inventory = match payload.runner_id {
+        Some(runner_id) => {
+            match get_runner_gates(&state.db_pool, runner_id, auth_user.user_id).await {
+                Ok(inventory) => Some(inventory),
+                Err(SqlxError::RowNotFound) => {
+                    return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+                }
+                Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+            }
+        }
+        None => None,
+    };
+
+    let runner_name = inventory.as_ref().map(|i| i.runner_name.as_str());
+    let parsed = parse_gate_expressions(&[payload.expression], runner_name)
+        .map_err(|msg| (StatusCode::UNPROCESSABLE_ENTITY, msg))?;
+
+    let unknown_gates: Vec<String> = match inventory.as_ref().filter(|i| i.is_defined()) {
+        Some(inventory) => parsed
+            .iter()
+            .filter(|g| !inventory.contains(&g.gate))
+            .map(|g| g.to_string())
+            .collect(),
+        None => Vec::new(),
+    };
+    let count = parsed.len();
+
+    Ok(Json(ParsedGatesResponse {
+        gates: parsed.iter().map(|g| g.to_string()).collect(),
+        count,
+        compact: compact_gates(&parsed),
+        unknown_gates,
+        qty_matches: payload
+            .qty
+            .map(|qty| usize::try_from(qty).ok() == Some(count)),
+    }))
+}
+
 pub fn requirement_router() -> Router<AppState> {
     Router::new()
         .route("/", post(create_kit_part_requirement_handler))
@@ -144,6 +232,7 @@ pub fn requirement_router() -> Router<AppState> {
         .route("/sync", patch(bulk_sync_requirements_handler))
         .route("/compare_sync", patch(compare_sync_requirements_handler))
         .route("/bulk_delete", post(bulk_delete_requirements_handler))
+        .route("/gates/parse", post(parse_gates_handler))
     // .route(
     //     "/requirements/:id",
     //     delete(delete_kit_part_requirement_handler).patch(update_kit_part_requirement_handler),
@@ -1,20 +1,33 @@
 use axum::{
-    extract::{Path, State},
+    extract::{DefaultBodyLimit, Path, State},
     http::StatusCode,
-    routing::{get, patch, post},
+    routing::{get, patch, post, put},
     Json, Router,
 };
 use sqlx::Error as SqlxError;
 
 // สมมติว่า import สิ่งที่จำเป็น
+use crate::api::runner_color::{get_runner_colors_handler, set_runner_colors_handler};
+use crate::api::runner_map::{
+    delete_runner_image_handler, get_runner_image_handler, get_runner_map_handler,
+    set_hotspots_handler, upload_runner_image_handler,
+};
+use crate::model::runner_map::MAX_RUNNER_IMAGE_BYTES;
+use crate::repository::reference::ReferenceError;
 use crate::repository::runner::{
-    create_runner, delete_runner, get_all_runners, get_runner_by_id, update_runner,
-    update_runner_is_used,
+    bulk_create_runners, create_runner, delete_runner, get_all_runners, get_runner_by_id,
+    get_runner_gates, set_runner_gates, update_runner, update_runner_is_used,
+    BulkCreateRunnersError,
 };
 use crate::state::AppState;
 use crate::{
     middleware::auth::AuthUser,
-    model::runner::{CreateRunnerPayload, Runner, UpdateIsUsedPayload, UpdateRunnerPayload},
+    model::gate::parse_gate_expressions,
+    model::runner::{
+        expand_runner_spec, BulkCreateRunnersPayload, CreateRunnerPayload, Runner, RunnerGates,
+        SetRunnerGatesPayload, UpdateIsUsedPayload, UpdateRunnerPayload, MAX_BULK_RUNNERS,
+        MAX_RUNNER_GATES,
+    },
 };
 
 // POST /runners
@@ -25,7 +38,108 @@ pub async fn create_runner_handler(
 ) -> Result<(StatusCode, Json<Runner>), (StatusCode, String)> {
     match create_runner(&state.db_pool, auth_user.user_id, payload).await {
         Ok(runner) => Ok((StatusCode::CREATED, Json(runner))),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(ReferenceError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(ReferenceError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(ReferenceError::Db(SqlxError::RowNotFound)) => {
+            Err((StatusCode::NOT_FOUND, "Kit not found".to_string()))
+        }
+        Err(ReferenceError::Db(SqlxError::Database(db_err))) if db_err.is_unique_violation() => {
+            Err((
+                StatusCode::CONFLICT,
+                "A runner with this name already exists in the kit".to_string(),
+            ))
+        }
+        Err(ReferenceError::Db(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// POST /runners/bulk
+pub async fn bulk_create_runners_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<BulkCreateRunnersPayload>,
+) -> Result<(StatusCode, Json<Vec<Runner>>), (StatusCode, String)> {
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, msg);
+    let default_amount = payload.amount.unwrap_or(1);
+
+    // 1. รวม runner จาก spec และ items เป็น (name, color_id, amount)
+    let mut rows: Vec<(String, i64, i32)> = Vec::new();
+    if let Some(spec) = payload.spec.as_deref() {
+        let color_id = payload
+            .color_id
+            .ok_or_else(|| bad_request("color_id is required when using spec".to_string()))?;
+        for name in expand_runner_spec(spec).map_err(bad_request)? {
+            rows.push((name, color_id, default_amount));
+        }
+    }
+    for item in payload.items {
+        let name = item.name.trim().to_string();
+        if name.is_empty() {
+            return Err(bad_request("Runner name is required".to_string()));
+        }
+        let color_id = item
+            .color_id
+            .or(payload.color_id)
+            .ok_or_else(|| bad_request(format!("color_id is required for runner {}", name)))?;
+        rows.push((name, color_id, item.amount.unwrap_or(default_amount)));
+    }
+
+    // 2. ตรวจจำนวน, amount และชื่อซ้ำภายใน request
+    if rows.is_empty() {
+        return Err(bad_request(
+            "Provide a spec or at least one item".to_string(),
+        ));
+    }
+    if rows.len() > MAX_BULK_RUNNERS {
+        return Err(bad_request(format!(
+            "Cannot create more than {} runners at once",
+            MAX_BULK_RUNNERS
+        )));
+    }
+    if let Some((name, _, _)) = rows.iter().find(|(_, _, amount)| *amount < 1) {
+        return Err(bad_request(format!(
+            "amount must be at least 1 (runner {})",
+            name
+        )));
+    }
+    let mut duplicates: Vec<String> = Vec::new();
+    for (i, (name, _, _)) in rows.iter().enumerate() {
+        if rows[..i].iter().any(|(n, _, _)| n == name) && !duplicates.contains(name) {
+            duplicates.push(name.clone());
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err((
+            StatusCode::CONFLICT,
+            format!(
+                "Duplicate runner names in request: {}",
+                duplicates.join(", ")
+            ),
+        ));
+    }
+
+    // 3. สร้างทั้งหมดใน transaction เดียว
+    match bulk_create_runners(&state.db_pool, auth_user.user_id, payload.kit_id, rows).await {
+        Ok(runners) => Ok((StatusCode::CREATED, Json(runners))),
+        Err(BulkCreateRunnersError::KitNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Kit not found".to_string()))
+        }
+        Err(BulkCreateRunnersError::DuplicateNames(names)) => Err((
+            StatusCode::CONFLICT,
+            format!("Runners already exist in the kit: {}", names.join(", ")),
+        )),
+        Err(BulkCreateRunnersError::ColorNotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(BulkCreateRunnersError::Db(SqlxError::Database(db_err)))
+            if db_err.is_unique_violation() =>
+        {
+            Err((
+                StatusCode::CONFLICT,
+                "A runner with this name already exists in the kit".to_string(),
+            ))
+        }
+        Err(BulkCreateRunnersError::Db(e)) => {
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
     }
 }
 
@@ -62,8 +176,27 @@ pub async fn update_runner_handler(
 ) -> Result<Json<Runner>, (StatusCode, String)> {
     match update_runner(&state.db_pool, id, auth_user.user_id, payload).await {
         Ok(runner) => Ok(Json(runner)),
-        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Runner not found".to_string())),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(ReferenceError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(ReferenceError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        Err(ReferenceError::Db(SqlxError::RowNotFound)) => {
+            Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(ReferenceError::Db(SqlxError::Database(db_err))) if db_err.is_unique_violation() => {
+            Err((
+                StatusCode::CONFLICT,
+                "A runner with this name already exists in the kit".to_string(),
+            ))
+        }
+        // ย้าย kit ทั้งที่ยังมี requirement ของ kit เดิมชี้อยู่ (composite FK)
+        Err(ReferenceError::Db(SqlxError::Database(db_err)))
+            if db_err.is_foreign_key_violation() =>
+        {
+            Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Runner is still used by kit parts of its current kit".to_string(),
+            ))
+        }
+        Err(ReferenceError::Db(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
 }
 
@@ -94,12 +227,94 @@ pub async fn delete_runner_handler(
     }
 }
 
+// GET /runners/:id/gates
+pub async fn get_runner_gates_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<RunnerGates>, (StatusCode, String)> {
+    match get_runner_gates(&state.db_pool, id, auth_user.user_id).await {
+        Ok(gates) => Ok(Json(gates)),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Runner not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// PUT /runners/:id/gates
+// ส่ง gate_count หรือ gates อย่างใดอย่างหนึ่ง (ไม่ส่งทั้งคู่ = ล้าง inventory)
+pub async fn set_runner_gates_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(payload): Json<SetRunnerGatesPayload>,
+) -> Result<Json<RunnerGates>, (StatusCode, String)> {
+    let bad_request = |msg: String| Err((StatusCode::BAD_REQUEST, msg));
+
+    if payload.gate_count.is_some() && payload.gates.is_some() {
+        return bad_request("Provide either gate_count or gates, not both".to_string());
+    }
+    if let Some(count) = payload.gate_count {
+        if !(1..=MAX_RUNNER_GATES).contains(&count) {
+            return bad_request(format!(
+                "gate_count must be between 1 and {}",
+                MAX_RUNNER_GATES
+            ));
+        }
+    }
+
+    let gates = match payload.gates {
+        Some(list) => {
+            // ต้องรู้ชื่อ runner ก่อน เพื่อตัด prefix ออก ("A1-3" -> "1", "2", "3")
+            let runner = match get_runner_by_id(&state.db_pool, id, auth_user.user_id).await {
+                Ok(runner) => runner,
+                Err(SqlxError::RowNotFound) => {
+                    return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+                }
+                Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+            };
+
+            let normalized: Vec<String> = parse_gate_expressions(&list, Some(&runner.name))
+                .map_err(|msg| (StatusCode::BAD_REQUEST, msg))?
+                .iter()
+                .map(|g| g.gate.to_string())
+                .collect();
+            if normalized.is_empty() {
+                return bad_request("gates must contain at least one gate".to_string());
+            }
+            if normalized.len() > MAX_RUNNER_GATES as usize {
+                return bad_request(format!(
+                    "A runner cannot have more than {} gates",
+                    MAX_RUNNER_GATES
+                ));
+            }
+            Some(normalized)
+        }
+        None => None,
+    };
+
+    match set_runner_gates(
+        &state.db_pool,
+        id,
+        auth_user.user_id,
+        payload.gate_count,
+        gates,
+    )
+    .await
+    {
+        Ok(gates) => Ok(Json(gates)),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Runner not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
 pub fn runner_router() -> Router<crate::state::AppState> {
     Router::new()
         .route(
             "/",
             post(create_runner_handler).get(get_all_runners_handler),
         )
+        // 📦 สร้างหลาย runner พร้อมกัน (A-H, B1-B4, ...)
+        .route("/bulk", post(bulk_create_runners_handler))
         .route(
             "/:id",
             get(get_runner_by_id_handler)
@@ -108,4 +323,24 @@ pub fn runner_router() -> Router<crate::state::AppState> {
         )
         // 🚀 Route พิเศษสำหรับอัปเดต status
         .route("/:id/status", patch(update_runner_is_used_handler))
+        // 🔢 gate ที่ runner มีจริง (ใช้ตรวจ requirement)
+        .route(
+            "/:id/gates",
+            get(get_runner_gates_handler).put(set_runner_gates_handler),
+        )
+        // 🖼️ รูป runner + ตำแหน่ง gate บนรูป
+        .route(
+            "/:id/image",
+            get(get_runner_image_handler)
+                .post(upload_runner_image_handler)
+                .delete(delete_runner_image_handler)
+                .layer(DefaultBodyLimit::max(MAX_RUNNER_IMAGE_BYTES + 64 * 1024)), // 👈 เผื่อ header ของ multipart
+        )
+        .route("/:id/hotspots", put(set_hotspots_handler))
+        // 🎨 runner หลายสี + สีเฉพาะ gate
+        .route(
+            "/:id/colors",
+            get(get_runner_colors_handler).put(set_runner_colors_handler),
+        )
+        .route("/:id/map", get(get_runner_map_handler))
 }
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::{
+        gate::parse_gate,
+        runner_color::{RunnerColorSet, SetRunnerColorsPayload},
+    },
+    repository::{
+        reference::ReferenceError,
+        runner::get_runner_gates,
+        runner_color::{get_runner_color_sets, replace_runner_colors},
+    },
+    state::AppState,
+};
+
+// GET /runners/:id/colors
+pub async fn get_runner_colors_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<RunnerColorSet>, (StatusCode, String)> {
+    let runner = match get_runner_gates(&state.db_pool, id, auth_user.user_id).await {
+        Ok(runner) => runner,
+        Err(SqlxError::RowNotFound) => {
+            return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+
+    let (colors, gate_colors) = get_runner_color_sets(&state.db_pool, &[id])
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .remove(&id)
+        .unwrap_or_default();
+
+    Ok(Json(RunnerColorSet {
+        runner_id: runner.runner_id,
+        runner_name: runner.runner_name,
+        colors,
+        gate_colors,
+    }))
+}
+
+// PUT /runners/:id/colors (แทนที่ทั้งชุด, color_ids[0] = สีหลัก)
+pub async fn set_runner_colors_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(payload): Json<SetRunnerColorsPayload>,
+) -> Result<Json<RunnerColorSet>, (StatusCode, String)> {
+    if payload.color_ids.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "color_ids must contain at least one color".to_string(),
+        ));
+    }
+    let mut color_ids: Vec<i64> = Vec::with_capacity(payload.color_ids.len());
+    for color_id in &payload.color_ids {
+        if color_ids.contains(color_id) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Color {} is listed more than once", color_id),
+            ));
+        }
+        color_ids.push(*color_id);
+    }
+
+    let inventory = match get_runner_gates(&state.db_pool, id, auth_user.user_id).await {
+        Ok(inventory) => inventory,
+        Err(SqlxError::RowNotFound) => {
+            return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+
+    // ตรวจ gate ราย item เหมือน hotspot
+    let mut errors: Vec<String> = Vec::new();
+    let mut gate_colors: Vec<(String, i64)> = Vec::new();
+    for (index, item) in payload.gate_colors.iter().enumerate() {
+        let gate = match parse_gate(&item.gate, Some(&inventory.runner_name)) {
+            Ok(gate) => gate,
+            Err(msg) => {
+                errors.push(format!("gate_colors[{}]: {}", index, msg));
+                continue;
+            }
+        };
+        if inventory.is_defined() && !inventory.contains(&gate.gate) {
+            errors.push(format!(
+                "gate_colors[{}]: runner {} has no gate {}",
+                index, inventory.runner_name, gate
+            ));
+        }
+        if !color_ids.contains(&item.color_id) {
+            errors.push(format!(
+                "gate_colors[{}]: color {} is not one of the runner's colors",
+                index, item.color_id
+            ));
+        }
+
+        let key = gate.gate.to_string();
+        if gate_colors.iter().any(|(g, _)| *g == key) {
+            errors.push(format!("gate_colors[{}]: duplicate gate {}", index, gate));
+        }
+        gate_colors.push((key, item.color_id));
+    }
+    if !errors.is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("; ")));
+    }
+
+    match replace_runner_colors(
+        &state.db_pool,
+        id,
+        auth_user.user_id,
+        &color_ids,
+        &gate_colors,
+    )
+    .await
+    {
+        Ok(_) => get_runner_colors_handler(State(state), auth_user, Path(id)).await,
+        Err(ReferenceError::NotFound(msg)) => Err((StatusCode::NOT_FOUND, msg)),
+        Err(ReferenceError::WrongKit(msg)) => Err((StatusCode::UNPROCESSABLE_ENTITY, msg)),
+        // อ่านได้แต่ไม่ใช่ owner/editor
+        Err(ReferenceError::Db(SqlxError::RowNotFound)) => {
+            Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(ReferenceError::Db(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::path::Path as FsPath;
+
+use axum::{
+    extract::{Multipart, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::{
+        gate::{full_gate_name, parse_gate, sort_gates},
+        runner_map::{
+            detect_image_extension, image_content_type, HotspotCutState, RunnerMap,
+            RunnerMapHotspot, SetHotspotsPayload, MAX_RUNNER_IMAGE_BYTES,
+        },
+    },
+    repository::{
+        runner::get_runner_gates,
+        runner_map::{
+            get_hotspots, get_runner_image_path, get_runner_requirement_gates, replace_hotspots,
+            set_runner_image,
+        },
+    },
+    state::AppState,
+};
+
+// รูปไม่ได้เสิร์ฟเป็น static file: ต้องผ่าน GET /runners/:id/image ที่ตรวจสิทธิ์ kit_members
+fn image_url(runner_id: i64) -> String {
+    format!("/v2/api/runners/{}/image", runner_id)
+}
+
+// ลบไฟล์รูปเก่า (ไม่เจอไฟล์ก็ไม่เป็นไร)
+pub async fn remove_upload(upload_dir: &str, path: &str) {
+    let _ = tokio::fs::remove_file(FsPath::new(upload_dir).join(path)).await;
+}
+
+// GET /runners/:id/image
+pub async fn get_runner_image_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let not_found = || (StatusCode::NOT_FOUND, "Runner image not found".to_string());
+
+    let path = match get_runner_image_path(&state.db_pool, id, auth_user.user_id).await {
+        Ok(Some(path)) => path,
+        Ok(None) | Err(SqlxError::RowNotFound) => return Err(not_found()),
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+    let bytes = tokio::fs::read(FsPath::new(&state.upload_dir).join(&path))
+        .await
+        .map_err(|_| not_found())?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, image_content_type(&path)),
+            (header::CACHE_CONTROL, "private, no-cache"),
+        ],
+        bytes,
+    ))
+}
+
+// POST /runners/:id/image (multipart, field "image": png / jpg / webp)
+pub async fn upload_runner_image_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<Json<RunnerMap>, (StatusCode, String)> {
+    let mut bytes = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?
+    {
+        if field.name() == Some("image") {
+            bytes = Some(
+                field
+                    .bytes()
+                    .await
+                    .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?,
+            );
+            break;
+        }
+    }
+
+    let bytes = bytes.ok_or((
+        StatusCode::BAD_REQUEST,
+        "Missing multipart field: image".to_string(),
+    ))?;
+    if bytes.len() > MAX_RUNNER_IMAGE_BYTES {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Image must be at most {} MB",
+                MAX_RUNNER_IMAGE_BYTES / 1024 / 1024
+            ),
+        ));
+    }
+    let extension = detect_image_extension(&bytes).ok_or((
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        "Image must be PNG, JPEG or WebP".to_string(),
+    ))?;
+
+    // 1. เขียนไฟล์ใหม่ก่อน แล้วค่อยชี้ runner ไปที่ไฟล์
+    let file_name = format!(
+        "runner-{}-{}.{}",
+        id,
+        chrono::Utc::now().timestamp_millis(),
+        extension
+    );
+    tokio::fs::create_dir_all(&state.upload_dir)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tokio::fs::write(FsPath::new(&state.upload_dir).join(&file_name), &bytes)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // 2. อัปเดต DB (ไม่มีสิทธิ์ = ลบไฟล์ที่เพิ่งเขียนทิ้ง)
+    match set_runner_image(&state.db_pool, id, auth_user.user_id, Some(&file_name)).await {
+        Ok(previous) => {
+            if let Some(previous) = previous {
+                remove_upload(&state.upload_dir, &previous).await;
+            }
+        }
+        Err(e) => {
+            remove_upload(&state.upload_dir, &file_name).await;
+            return Err(match e {
+                SqlxError::RowNotFound => (StatusCode::NOT_FOUND, "Runner not found".to_string()),
+                e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            });
+        }
+    }
+
+    get_runner_map_handler(State(state), auth_user, Path(id)).await
+}
+
+// DELETE /runners/:id/image
+pub async fn delete_runner_image_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match set_runner_image(&state.db_pool, id, auth_user.user_id, None).await {
+        Ok(previous) => {
+            if let Some(previous) = previous {
+                remove_upload(&state.upload_dir, &previous).await;
+            }
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Runner not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// PUT /runners/:id/hotspots (แทนที่ทั้งชุด, ส่ง [] = ลบทั้งหมด)
+pub async fn set_hotspots_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(mut payload): Json<SetHotspotsPayload>,
+) -> Result<Json<RunnerMap>, (StatusCode, String)> {
+    let inventory = match get_runner_gates(&state.db_pool, id, auth_user.user_id).await {
+        Ok(inventory) => inventory,
+        Err(SqlxError::RowNotFound) => {
+            return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+
+    let in_range = |v: f64| v.is_finite() && (0.0..=1.0).contains(&v);
+    let mut errors: Vec<String> = Vec::new();
+    let mut seen: Vec<String> = Vec::new();
+
+    for (index, hotspot) in payload.hotspots.iter_mut().enumerate() {
+        let gate = match parse_gate(&hotspot.gate, Some(&inventory.runner_name)) {
+            Ok(gate) => gate,
+            Err(msg) => {
+                errors.push(format!("hotspots[{}]: {}", index, msg));
+                continue;
+            }
+        };
+        if inventory.is_defined() && !inventory.contains(&gate.gate) {
+            errors.push(format!(
+                "hotspots[{}]: runner {} has no gate {}",
+                index, inventory.runner_name, gate
+            ));
+        }
+        if !in_range(hotspot.x) || !in_range(hotspot.y) {
+            errors.push(format!(
+                "hotspots[{}]: x and y must be between 0 and 1",
+                index
+            ));
+        }
+        if let Some(polygon) = &hotspot.polygon {
+            if polygon.len() < 3 || polygon.iter().flatten().any(|v| !in_range(*v)) {
+                errors.push(format!(
+                    "hotspots[{}]: polygon needs at least 3 points between 0 and 1",
+                    index
+                ));
+            }
+        }
+
+        let key = gate.gate.to_string();
+        if seen.contains(&key) {
+            errors.push(format!("hotspots[{}]: duplicate gate {}", index, gate));
+        }
+        seen.push(key.clone());
+        hotspot.gate = key;
+    }
+
+    if !errors.is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, errors.join("; ")));
+    }
+
+    match replace_hotspots(&state.db_pool, id, auth_user.user_id, &payload.hotspots).await {
+        Ok(_) => get_runner_map_handler(State(state), auth_user, Path(id)).await,
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Runner not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// GET /runners/:id/map
+// รูป + hotspot ของแต่ละ gate พร้อมสถานะการตัดจาก kit_part_requirements
+pub async fn get_runner_map_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<RunnerMap>, (StatusCode, String)> {
+    let internal = |e: SqlxError| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+
+    let inventory = match get_runner_gates(&state.db_pool, id, auth_user.user_id).await {
+        Ok(inventory) => inventory,
+        Err(SqlxError::RowNotFound) => {
+            return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(e) => return Err(internal(e)),
+    };
+    let image_path = get_runner_image_path(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map_err(internal)?;
+    let hotspots = get_hotspots(&state.db_pool, id).await.map_err(internal)?;
+    let requirements = get_runner_requirement_gates(&state.db_pool, id)
+        .await
+        .map_err(internal)?;
+
+    // gate -> (จำนวน requirement, ตัดครบหรือยัง)
+    let mut usage: HashMap<String, (usize, bool)> = HashMap::new();
+    for (gates, is_cut) in &requirements {
+        for gate in gates {
+            let Ok(parsed) = parse_gate(gate, Some(&inventory.runner_name)) else {
+                continue;
+            };
+            let entry = usage.entry(parsed.gate.to_string()).or_insert((0, true));
+            entry.0 += 1;
+            entry.1 &= *is_cut;
+        }
+    }
+
+    let mut hotspots: Vec<RunnerMapHotspot> = hotspots
+        .into_iter()
+        .map(|hotspot| {
+            let (requirement_count, all_cut) =
+                usage.get(&hotspot.gate).copied().unwrap_or((0, false));
+            let state = match (requirement_count, all_cut) {
+                (0, _) => HotspotCutState::Unused,
+                (_, true) => HotspotCutState::Cut,
+                (_, false) => HotspotCutState::Uncut,
+            };
+            RunnerMapHotspot {
+                gate: full_gate_name(&inventory.runner_name, &hotspot.gate),
+                x: hotspot.x,
+                y: hotspot.y,
+                polygon: hotspot.polygon,
+                state,
+                requirement_count,
+            }
+        })
+        .collect();
+
+    // เรียงตามลำดับ gate
+    let mut order: Vec<String> = hotspots.iter().map(|h| h.gate.clone()).collect();
+    sort_gates(&mut order);
+    hotspots.sort_by_key(|h| order.iter().position(|g| *g == h.gate));
+
+    Ok(Json(RunnerMap {
+        runner_id: inventory.runner_id,
+        runner_name: inventory.runner_name,
+        image_url: image_path.map(|_| image_url(id)),
+        hotspots,
+    }))
+}
@@ -0,0 +1,87 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::share::{CreateShareLinkPayload, KitShareLink, PublicKit, MAX_SHARE_LINK_HOURS},
+    repository::share::{
+        create_share_link, get_public_kit_by_token, get_share_links_for_kit, revoke_share_link,
+    },
+    state::AppState,
+};
+
+// POST /kits/:id/share_links
+pub async fn create_share_link_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+    Json(payload): Json<CreateShareLinkPayload>,
+) -> Result<(StatusCode, Json<KitShareLink>), (StatusCode, String)> {
+    if matches!(payload.expires_in_hours, Some(hours) if !(1..=MAX_SHARE_LINK_HOURS).contains(&hours))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "expires_in_hours must be between 1 and {}",
+                MAX_SHARE_LINK_HOURS
+            ),
+        ));
+    }
+
+    match create_share_link(&state.db_pool, kit_id, auth_user.user_id, payload).await {
+        Ok(link) => Ok((StatusCode::CREATED, Json(link))),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// GET /kits/:id/share_links
+pub async fn get_share_links_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+) -> Result<Json<Vec<KitShareLink>>, (StatusCode, String)> {
+    match get_share_links_for_kit(&state.db_pool, kit_id, auth_user.user_id).await {
+        Ok(links) => Ok(Json(links)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// DELETE /kits/:id/share_links/:link_id
+pub async fn revoke_share_link_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((kit_id, link_id)): Path<(i64, i64)>,
+) -> Result<Json<KitShareLink>, (StatusCode, String)> {
+    match revoke_share_link(&state.db_pool, kit_id, link_id, auth_user.user_id).await {
+        Ok(link) => Ok(Json(link)),
+        Err(SqlxError::RowNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Share link not found".to_string()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// GET /public/kits/:token (ไม่ต้องมี AuthUser)
+pub async fn get_public_kit_handler(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<PublicKit>, (StatusCode, String)> {
+    match get_public_kit_by_token(&state.db_pool, &token).await {
+        Ok(kit) => Ok(Json(kit)),
+        Err(SqlxError::RowNotFound) => Err((
+            StatusCode::NOT_FOUND,
+            "Shared kit not found or link expired".to_string(),
+        )),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub fn public_router() -> Router<AppState> {
+    Router::new().route("/kits/:token", get(get_public_kit_handler))
+}
@@ -0,0 +1,183 @@
+use std::str::FromStr;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::{
+        gate::{full_gate_name, parse_gate},
+        kit::KitGrade,
+        spare::{SparePart, SpareQuery, UpdateSparePayload},
+    },
+    repository::{
+        runner::get_runner_by_id,
+        spare::{delete_spare_note, get_spares, upsert_spare_note},
+    },
+    state::AppState,
+};
+
+// GET /spares?kit_id=&grade=&color_id=&color=&tag=
+// ค้น spare ข้ามทุก kit ที่เป็นสมาชิก เช่น ?grade=hg&color=gray
+pub async fn get_spares_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(params): Query<SpareQuery>,
+) -> Result<Json<Vec<SparePart>>, (StatusCode, String)> {
+    let grade = match params.grade.as_deref() {
+        Some(grade) => {
+            let grade = grade.trim().to_lowercase();
+            KitGrade::from_str(&grade).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+            Some(grade)
+        }
+        None => None,
+    };
+    let color = params
+        .color
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+
+    let spares = get_spares(
+        &state.db_pool,
+        auth_user.user_id,
+        params.kit_id,
+        grade.as_deref(),
+        params.color_id,
+        color,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let spares = match params.tag.as_deref().map(str::trim) {
+        Some(tag) if !tag.is_empty() => spares
+            .into_iter()
+            .filter(|s| s.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            .collect(),
+        _ => spares,
+    };
+    Ok(Json(spares))
+}
+
+// หา spare ชิ้นเดียวจาก runner_id + gate ("A12" หรือ "12")
+async fn find_spare(
+    state: &AppState,
+    user_id: i64,
+    runner_id: i64,
+    gate: &str,
+) -> Result<(SparePart, String), (StatusCode, String)> {
+    let runner = match get_runner_by_id(&state.db_pool, runner_id, user_id).await {
+        Ok(runner) => runner,
+        Err(SqlxError::RowNotFound) => {
+            return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+    let key = parse_gate(gate, Some(&runner.name))
+        .map_err(|msg| (StatusCode::BAD_REQUEST, msg))?
+        .gate
+        .to_string();
+    let full_name = full_gate_name(&runner.name, &key);
+
+    let spares = get_spares(
+        &state.db_pool,
+        user_id,
+        Some(runner.kit_id),
+        None,
+        None,
+        None,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    spares
+        .into_iter()
+        .find(|s| s.runner_id == runner_id && s.gate == full_name)
+        .map(|spare| (spare, key))
+        .ok_or((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Gate {} is not a spare on this runner", full_name),
+        ))
+}
+
+// PUT /spares/:runner_id/:gate
+pub async fn update_spare_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((runner_id, gate)): Path<(i64, String)>,
+    Json(payload): Json<UpdateSparePayload>,
+) -> Result<Json<SparePart>, (StatusCode, String)> {
+    let (_, key) = find_spare(&state, auth_user.user_id, runner_id, &gate).await?;
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in &payload.tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    let note = payload
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+
+    match upsert_spare_note(
+        &state.db_pool,
+        auth_user.user_id,
+        runner_id,
+        &key,
+        &tags,
+        note,
+    )
+    .await
+    {
+        Ok(_) => {
+            let (spare, _) = find_spare(&state, auth_user.user_id, runner_id, &key).await?;
+            Ok(Json(spare))
+        }
+        // อ่านได้แต่ไม่ใช่ owner/editor
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Runner not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// DELETE /spares/:runner_id/:gate (ลบ tag/note ออก spare ยังอยู่)
+pub async fn delete_spare_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((runner_id, gate)): Path<(i64, String)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let runner = match get_runner_by_id(&state.db_pool, runner_id, auth_user.user_id).await {
+        Ok(runner) => runner,
+        Err(SqlxError::RowNotFound) => {
+            return Err((StatusCode::NOT_FOUND, "Runner not found".to_string()))
+        }
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+    let key = parse_gate(&gate, Some(&runner.name))
+        .map_err(|msg| (StatusCode::BAD_REQUEST, msg))?
+        .gate
+        .to_string();
+
+    match delete_spare_note(&state.db_pool, auth_user.user_id, runner_id, &key).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(SqlxError::RowNotFound) => Err((
+            StatusCode::NOT_FOUND,
+            "No tags or note on this spare".to_string(),
+        )),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub fn spare_router() -> Router<AppState> {
+    Router::new().route("/", get(get_spares_handler)).route(
+        "/:runner_id/:gate",
+        put(update_spare_handler).delete(delete_spare_handler),
+    )
+}
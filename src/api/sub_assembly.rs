@@ -3,12 +3,13 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, post},
+    routing::{get, patch, post},
     Json, Router,
 };
 use serde::Deserialize;
 use sqlx::Error as SqlxError;
 
+use crate::api::workflow::set_sub_assembly_stage_handler;
 use crate::repository::sub_assembly::{
     create_sub_assembly, delete_sub_assembly, get_all_sub_assemblies_for_kit,
     get_sub_assembly_by_id, update_sub_assembly,
@@ -31,6 +32,7 @@ pub async fn create_sub_assembly_handler(
 ) -> Result<(StatusCode, Json<SubAssembly>), (StatusCode, String)> {
     match create_sub_assembly(&state.db_pool, auth_user.user_id, payload).await {
         Ok(sa) => Ok((StatusCode::CREATED, Json(sa))),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
         Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
 }
@@ -71,6 +73,11 @@ pub async fn update_sub_assembly_handler(
         Err(SqlxError::RowNotFound) => {
             Err((StatusCode::NOT_FOUND, "Sub-assembly not found".to_string()))
         }
+        // ย้าย kit ทั้งที่ยังมี kit part ของ kit เดิมอยู่ (composite FK)
+        Err(SqlxError::Database(db_err)) if db_err.is_foreign_key_violation() => Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Sub-assembly still has kit parts in its current kit".to_string(),
+        )),
         Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
 }
@@ -101,5 +108,7 @@ pub fn sub_assembly_router() -> Router<crate::state::AppState> {
                 .patch(update_sub_assembly_handler)
                 .delete(delete_sub_assembly_handler),
         )
+        // 🪜 ย้ายทุก part ใน sub-assembly ไป workflow stage เดียวกัน
+        .route("/:id/stage", patch(set_sub_assembly_stage_handler))
     // 🚀 Route พิเศษสำหรับอัปเดต status
 }
@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    api::runner_map::remove_upload,
+    middleware::auth::AuthUser,
+    model::trash::{TrashItem, TrashItemType},
+    repository::trash::{empty_trash, list_trash, purge_item, restore_item, RestoreError},
+    state::AppState,
+};
+
+// GET /trash
+pub async fn list_trash_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<Vec<TrashItem>>, (StatusCode, String)> {
+    match list_trash(
+        &state.db_pool,
+        auth_user.user_id,
+        state.trash_retention_days,
+    )
+    .await
+    {
+        Ok(items) => Ok(Json(items)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// POST /trash/:item_type/:id/restore
+pub async fn restore_trash_item_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((item_type, id)): Path<(TrashItemType, i64)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match restore_item(&state.db_pool, auth_user.user_id, item_type, id).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(RestoreError::NotFound) => {
+            Err((StatusCode::NOT_FOUND, "Item not found in trash".to_string()))
+        }
+        // 409: ต้อง restore parent ก่อน
+        Err(RestoreError::ParentInTrash(parent)) => Err((
+            StatusCode::CONFLICT,
+            format!(
+                "Parent {} is in the trash, restore it first",
+                parent.as_str()
+            ),
+        )),
+        // 409: ชื่อซ้ำกับรายการที่ยังใช้งานอยู่ (เช่น runner ชื่อเดียวกันใน kit)
+        Err(RestoreError::Db(SqlxError::Database(db_err))) if db_err.is_unique_violation() => {
+            Err((
+                StatusCode::CONFLICT,
+                "An item with the same name already exists".to_string(),
+            ))
+        }
+        Err(RestoreError::Db(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// DELETE /trash/:item_type/:id
+pub async fn purge_trash_item_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((item_type, id)): Path<(TrashItemType, i64)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match purge_item(&state.db_pool, auth_user.user_id, item_type, id).await {
+        Ok(image_paths) => {
+            for path in &image_paths {
+                remove_upload(&state.upload_dir, path).await;
+            }
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Err(SqlxError::RowNotFound) => {
+            Err((StatusCode::NOT_FOUND, "Item not found in trash".to_string()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// DELETE /trash
+pub async fn empty_trash_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<u64>, (StatusCode, String)> {
+    match empty_trash(&state.db_pool, auth_user.user_id).await {
+        Ok((count, image_paths)) => {
+            for path in &image_paths {
+                remove_upload(&state.upload_dir, path).await;
+            }
+            Ok(Json(count))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub fn trash_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_trash_handler).delete(empty_trash_handler))
+        .route("/:item_type/:id", delete(purge_trash_item_handler))
+        .route("/:item_type/:id/restore", post(restore_trash_item_handler))
+}
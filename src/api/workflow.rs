@@ -0,0 +1,224 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch, put},
+    Json, Router,
+};
+use sqlx::Error as SqlxError;
+
+use crate::{
+    middleware::auth::AuthUser,
+    model::{
+        common::Message,
+        workflow::{
+            BulkSetStagePayload, BulkStageResult, CreateStagePayload, KitPartStage,
+            KitStageProgress, ReorderStagesPayload, SetStagePayload, StageProgressQuery,
+            StageTransition, UpdateStagePayload, WorkflowStage,
+        },
+    },
+    repository::{
+        reference::ReferenceError,
+        workflow::{
+            create_stage, delete_stage, get_kit_stage_progress, get_stage_history, get_stages,
+            reorder_stages, set_kit_part_stage, set_sub_assembly_stage, update_stage, StageError,
+        },
+    },
+    state::AppState,
+};
+
+fn message(status: StatusCode, message: String) -> (StatusCode, Json<Message>) {
+    (status, Json(Message { message }))
+}
+
+fn stage_error(e: SqlxError) -> (StatusCode, Json<Message>) {
+    match e {
+        SqlxError::RowNotFound => message(StatusCode::NOT_FOUND, "Stage not found".to_string()),
+        e if e
+            .as_database_error()
+            .is_some_and(|db_err| db_err.is_unique_violation()) =>
+        {
+            message(
+                StatusCode::CONFLICT,
+                "A stage with this name already exists".to_string(),
+            )
+        }
+        e => message(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+fn stage_result_error(e: StageError) -> (StatusCode, Json<Message>) {
+    match e {
+        StageError::NotFound => stage_error(SqlxError::RowNotFound),
+        StageError::InUse(count) => message(
+            StatusCode::CONFLICT,
+            format!("Stage is used by {} kit part(s)", count),
+        ),
+        StageError::Invalid(msg) => message(StatusCode::BAD_REQUEST, msg),
+        StageError::Db(e) => stage_error(e),
+    }
+}
+
+fn reference_error(e: ReferenceError) -> (StatusCode, String) {
+    match e {
+        ReferenceError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+        ReferenceError::WrongKit(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
+        ReferenceError::Db(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+fn trimmed_name(name: &str) -> Result<String, (StatusCode, Json<Message>)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(message(
+            StatusCode::BAD_REQUEST,
+            "name must not be empty".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+// GET /workflow_stages (ยังไม่มีเลย = ได้ขั้นเริ่มต้น)
+async fn get_stages_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<Vec<WorkflowStage>>, (StatusCode, Json<Message>)> {
+    get_stages(&state.db_pool, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(stage_error)
+}
+
+async fn create_stage_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<CreateStagePayload>,
+) -> Result<(StatusCode, Json<WorkflowStage>), (StatusCode, Json<Message>)> {
+    let name = trimmed_name(&payload.name)?;
+    create_stage(&state.db_pool, auth_user.user_id, &name, payload.position)
+        .await
+        .map(|stage| (StatusCode::CREATED, Json(stage)))
+        .map_err(stage_error)
+}
+
+async fn update_stage_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+    Json(payload): Json<UpdateStagePayload>,
+) -> Result<Json<WorkflowStage>, (StatusCode, Json<Message>)> {
+    let name = payload.name.as_deref().map(trimmed_name).transpose()?;
+    update_stage(
+        &state.db_pool,
+        id,
+        auth_user.user_id,
+        name.as_deref(),
+        payload.position,
+    )
+    .await
+    .map(Json)
+    .map_err(stage_error)
+}
+
+// PUT /workflow_stages/order
+async fn reorder_stages_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<ReorderStagesPayload>,
+) -> Result<Json<Vec<WorkflowStage>>, (StatusCode, Json<Message>)> {
+    reorder_stages(&state.db_pool, auth_user.user_id, &payload.stage_ids)
+        .await
+        .map(Json)
+        .map_err(stage_result_error)
+}
+
+// DELETE /workflow_stages/:id — 409 ถ้ายังมี part อยู่ขั้นนี้
+async fn delete_stage_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    auth_user: AuthUser,
+) -> Result<StatusCode, (StatusCode, Json<Message>)> {
+    delete_stage(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(stage_result_error)
+}
+
+// PATCH /kit_parts/:id/stage
+pub async fn set_kit_part_stage_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(payload): Json<SetStagePayload>,
+) -> Result<Json<Option<KitPartStage>>, (StatusCode, String)> {
+    set_kit_part_stage(&state.db_pool, id, auth_user.user_id, payload.stage_id)
+        .await
+        .map(Json)
+        .map_err(reference_error)
+}
+
+// GET /kit_parts/:id/stage_history
+pub async fn get_stage_history_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<StageTransition>>, (StatusCode, String)> {
+    get_stage_history(&state.db_pool, id, auth_user.user_id)
+        .await
+        .map(Json)
+        .map_err(reference_error)
+}
+
+// PATCH /sub_assemblies/:id/stage
+pub async fn set_sub_assembly_stage_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(payload): Json<BulkSetStagePayload>,
+) -> Result<Json<BulkStageResult>, (StatusCode, String)> {
+    let updated = set_sub_assembly_stage(
+        &state.db_pool,
+        id,
+        auth_user.user_id,
+        payload.stage_id,
+        payload.advance_only,
+    )
+    .await
+    .map_err(reference_error)?;
+
+    Ok(Json(BulkStageResult {
+        sub_assembly_id: id,
+        stage_id: payload.stage_id,
+        updated,
+    }))
+}
+
+// GET /kits/:id/stage_progress?sub_assembly_id=
+pub async fn get_kit_stage_progress_handler(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(kit_id): Path<i64>,
+    Query(query): Query<StageProgressQuery>,
+) -> Result<Json<KitStageProgress>, (StatusCode, String)> {
+    match get_kit_stage_progress(
+        &state.db_pool,
+        kit_id,
+        auth_user.user_id,
+        query.sub_assembly_id,
+    )
+    .await
+    {
+        Ok(progress) => Ok(Json(progress)),
+        Err(SqlxError::RowNotFound) => Err((StatusCode::NOT_FOUND, "Kit not found".to_string())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub fn workflow_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_stages_handler).post(create_stage_handler))
+        .route("/order", put(reorder_stages_handler))
+        .route(
+            "/:id",
+            patch(update_stage_handler).delete(delete_stage_handler),
+        )
+}
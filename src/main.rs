@@ -74,12 +74,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 🚀 ส่วนที่แก้ไข: การดึงค่า PORT
     migrate!("./migrations").run(&pool).await?;
     let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set in .env file");
+    // 🗑️ จำนวนวันที่เก็บรายการไว้ในถังขยะก่อนลบถาวร (ค่าเริ่มต้น 30 วัน)
+    let trash_retention_days: i64 = std::env::var("TRASH_RETENTION_DAYS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse()
+        .expect("TRASH_RETENTION_DAYS must be a valid number of days");
+    // 🖼️ โฟลเดอร์เก็บรูป runner (ไม่เปิดเป็น static, อ่านผ่าน GET /runners/:id/image)
+    let upload_dir = std::env::var("UPLOAD_DIR").unwrap_or_else(|_| "uploads".to_string());
     // 2. สร้าง AppState struct (ตัวแปรที่หายไป)
     let app_state = AppState {
         db_pool: pool,
         jwt_secret,
+        trash_retention_days,
+        upload_dir: upload_dir.clone(),
     };
 
+    // 🧹 Background task: ลบรายการที่อยู่ในถังขยะเกิน retention ทุกๆ 1 ชั่วโมง
+    let purge_pool = app_state.db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+        loop {
+            interval.tick().await;
+            match repository::trash::purge_expired(&purge_pool, trash_retention_days).await {
+                Ok((count, image_paths)) => {
+                    // 🖼️ ลบไฟล์รูปของ runner ที่ถูกลบถาวรไปด้วย
+                    for path in &image_paths {
+                        api::runner_map::remove_upload(&upload_dir, path).await;
+                    }
+                    if count > 0 {
+                        println!("Trash purge: removed {} expired item(s)", count);
+                    }
+                }
+                Err(e) => eprintln!("Trash purge error: {:?}", e),
+            }
+        }
+    });
+
     // 1. Setup State (Client, DB_Name)
     // ... (โค้ดการสร้าง client และ AppState)
 
@@ -94,12 +124,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Router::new()
                 .nest("/auth", api::auth::auth_router())
                 .nest("/colors", api::color::color_router())
+                .nest("/paints", api::paint::paint_router())
+                .nest("/paint_catalog", api::paint_catalog::paint_catalog_router())
+                .nest("/recipes", api::paint_recipe::recipe_router())
                 .nest("/kits", api::kit::kit_router())
                 .nest("/runners", api::runner::runner_router())
                 .nest("/sub_assemblies", api::sub_assembly::sub_assembly_router())
                 .nest("/kit_parts", kit_part_router())
+                .nest("/workflow_stages", api::workflow::workflow_router())
                 .nest("/requirements", requirement_router())
-                .nest("/steam", steam_router()),
+                .nest("/steam", steam_router())
+                .nest("/dashboard", api::dashboard::dashboard_router())
+                .nest("/queue", api::queue::queue_router())
+                .nest("/spares", api::spare::spare_router())
+                .nest("/trash", api::trash::trash_router())
+                // 🌐 Public routes: ไม่ต้องมี Authorization header
+                .nest("/public", api::share::public_router()),
             // URL: /v2/api/auth/...
             // .nest("/kits", api::kit::kit_router()), // URL: /v2/api/kits/...
         )
@@ -1,6 +1,7 @@
 // src/middleware/auth.rs
 
 use crate::model::jwt::Claims;
+use crate::repository::user::get_user_by_id;
 use crate::state::AppState;
 use async_trait::async_trait; // 👈 ต้องมี Dependency นี้ใน Cargo.toml
 use axum::{
@@ -75,3 +76,39 @@ impl FromRequestParts<AppState> for AuthUser {
         })
     }
 }
+
+// ----------------------------------------------------
+// 2. Extractor สำหรับ route ของ admin (users.role = 'admin')
+// ----------------------------------------------------
+
+// อ่าน role จาก DB ทุกครั้ง (ไม่ได้อยู่ใน JWT) เปลี่ยน role แล้วมีผลทันที
+// ใช้เป็น guard อย่างเดียว (route ที่ต้องรู้ user_id ใช้ AuthUser)
+#[derive(Debug, Clone)]
+pub struct AdminUser;
+
+#[async_trait]
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
+
+        let user = get_user_by_id(&state.db_pool, auth_user.user_id)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    (StatusCode::UNAUTHORIZED, "User not found".to_string())
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            })?;
+
+        if user.role != "admin" {
+            return Err((StatusCode::FORBIDDEN, "Admin access required".to_string()));
+        }
+
+        Ok(AdminUser)
+    }
+}
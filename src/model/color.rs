@@ -4,6 +4,8 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow; // 🚨 ต้องใช้ FromRow สำหรับ SQLx
 
+use crate::model::lab::Lab;
+
 // 🚨 Color Struct สำหรับ SQLx Mapping
 #[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
 pub struct Color {
@@ -20,7 +22,8 @@ pub struct Color {
     pub is_multi: bool,
 
     // 🚨 Foreign Key: ใช้ User ID (i64) แทน Struct User
-    pub user_id: i64,
+    pub user_id: Option<i64>, // 👈 None = preset กลางที่ admin ดูแล
+    pub is_preset: bool,
 
     // Timestamps (NaiveDateTime ถูกต้องแล้วสำหรับ SQLx/chrono)
     pub created_at: Option<NaiveDateTime>,
@@ -46,6 +49,16 @@ pub struct UpdateColorPayload {
     // ไม่รวม user_id, created_at
 }
 
+impl UpdateColorPayload {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.code.is_none()
+            && self.hex.is_none()
+            && self.is_clear.is_none()
+            && self.is_multi.is_none()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RunnerColor {
     pub id: i64,
@@ -55,3 +68,117 @@ pub struct RunnerColor {
     pub is_clear: bool,
     pub is_multi: bool,
 }
+
+// --- Nearest-color search: GET /colors/search?hex=...&source=&limit=&max_distance= ---
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorSearchSource {
+    #[default]
+    All,
+    Colors, // 👈 สีของ user (colors)
+    Paints, // 👈 สีจาก paint ที่มี hex + สูตรผสมสี
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ColorSearchQuery {
+    pub hex: String,
+    #[serde(default)]
+    pub source: ColorSearchSource,
+    pub limit: Option<usize>,
+    pub max_distance: Option<f64>, // 👈 ΔE00 สูงสุดที่ยอมรับ
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMatchKind {
+    Color,
+    Paint,
+    Recipe, // 👈 สีผสมจาก paint_recipes (hex เป็นค่าประมาณ)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ColorMatch {
+    pub kind: ColorMatchKind,
+    pub id: i64,
+    pub name: String,
+    pub code: Option<String>,
+    pub brand: Option<String>, // 👈 เฉพาะ paint
+    pub hex: String,
+    pub lab: Lab,
+    pub distance: f64, // 👈 ΔE00 จากสีที่ค้น (น้อย = ใกล้)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColorSearchResponse {
+    pub hex: String,
+    pub lab: Lab,
+    pub matches: Vec<ColorMatch>,
+}
+
+// --- Usage report: runner ที่ใช้สีนี้ (สีหลัก หรือสีรอง/สีเฉพาะ gate) ---
+#[derive(Debug, Serialize, Clone)]
+pub struct ColorUsageRunner {
+    pub runner_id: i64,
+    pub runner_name: String,
+    pub kit_id: i64,
+    pub kit_name: String,
+    pub is_primary: bool, // 👈 runners.color_id = สีนี้
+    pub gate_count: i64,  // 👈 จำนวน gate ที่ override เป็นสีนี้
+    pub in_trash: bool,   // 👈 runner/kit อยู่ในถังขยะ (ยังนับเพราะกู้คืนได้)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ColorUsage {
+    pub color_id: i64,
+    pub kit_count: i64,
+    pub runner_count: i64,
+    pub runners: Vec<ColorUsageRunner>,
+    pub other_kit_count: i64, // 👈 kit ที่เราไม่ได้เป็นสมาชิก (นับอย่างเดียว ไม่แสดงชื่อ)
+    pub other_runner_count: i64, // 👈 runner ใน kit เหล่านั้น
+    #[serde(skip)]
+    pub other_runners: Vec<ColorUsageRunner>, // 👈 ใช้ภายในตอนย้ายสี ไม่ส่งออกไป
+}
+
+impl ColorUsage {
+    pub fn is_used(&self) -> bool {
+        self.total_runner_count() > 0
+    }
+
+    pub fn total_runner_count(&self) -> i64 {
+        self.runner_count + self.other_runner_count
+    }
+
+    pub fn total_kit_count(&self) -> i64 {
+        self.kit_count + self.other_kit_count
+    }
+}
+
+// --- DELETE /colors/:id?reassign_to=:other ---
+#[derive(Debug, Deserialize)]
+pub struct DeleteColorQuery {
+    pub reassign_to: Option<i64>, // 👈 ย้าย runner ทั้งหมดไปสีนี้ก่อนลบ
+}
+
+// error body ของ DELETE (รูปแบบเดียวกับ Message + รายการที่ยังใช้อยู่เมื่อ 409)
+#[derive(Debug, Serialize)]
+pub struct ColorDeleteError {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ColorUsage>,
+}
+
+// --- POST /colors/merge: รวมสีซ้ำหลายตัวเข้าเป็นสีเดียว ---
+#[derive(Debug, Deserialize)]
+pub struct MergeColorsPayload {
+    pub target_id: i64,       // 👈 สีที่เหลืออยู่ (สีของเราหรือ preset)
+    pub source_ids: Vec<i64>, // 👈 สีที่ถูกรวมแล้วลบทิ้ง (สีของเราเท่านั้น)
+    #[serde(flatten)]
+    pub changes: UpdateColorPayload, // 👈 แก้ target หลังรวม (ไม่ส่ง = ไม่แก้)
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeColorsResponse {
+    pub color: Color,
+    pub merged_ids: Vec<i64>,
+    pub runners_updated: i64,
+}
@@ -0,0 +1,68 @@
+// src/model/dashboard.rs
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::model::kit::{KitGrade, KitStatus};
+
+// --- Query: GET /dashboard?from=YYYY-MM-DD&to=YYYY-MM-DD ---
+// ไม่ส่งมา = 30 วันล่าสุด (นับรวมวันนี้)
+#[derive(Debug, Deserialize)]
+pub struct DashboardQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KitStatusCount {
+    pub status: KitStatus,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KitGradeCount {
+    pub grade: KitGrade,
+    pub count: i64,
+}
+
+// จำนวน part / gate ที่ตัดในแต่ละช่วง (period = วันแรกของช่วง)
+#[derive(Debug, Serialize)]
+pub struct CutProgress {
+    pub period: NaiveDate,
+    pub parts: i64,
+    pub gates: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunnerColorCount {
+    pub color_id: i64,
+    pub name: String,
+    pub code: String,
+    pub hex: String,
+    pub runners: i64,
+    pub amount: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecentKit {
+    pub id: i64,
+    pub name: String,
+    pub grade: KitGrade,
+    pub status: KitStatus,
+    pub touched_at: NaiveDateTime, // 👈 เวลาล่าสุดที่ kit หรือลูก (runner/sub_assembly/kit_part) ถูกแก้ไข
+}
+
+// --- Response ---
+#[derive(Debug, Serialize)]
+pub struct Dashboard {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub total_kits: i64,
+    pub kits_by_status: Vec<KitStatusCount>,
+    pub kits_by_grade: Vec<KitGradeCount>,
+    pub cut_per_day: Vec<CutProgress>,
+    pub cut_per_week: Vec<CutProgress>,
+    pub runner_colors: Vec<RunnerColorCount>,
+    pub steam_wishlist_total: i64,
+    pub recently_touched: Vec<RecentKit>,
+}
@@ -0,0 +1,373 @@
+// src/model/gate.rs
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+// --- Gate grammar ---
+// expression := item (("," | ";") item)*
+// item       := gate | gate "-" gate | gate "~" gate
+// gate       := [runner prefix] digits [sub-letter]     เช่น "A12", "3a", "B1-5", "C4a-C4c"
+//
+// รูปแบบมาตรฐาน (canonical) = ชื่อ runner ตัวใหญ่ + เลข + ตัวอักษรย่อยตัวเล็ก เช่น "A12", "A3a"
+// เรียงจากน้อยไปมาก และไม่มีตัวซ้ำ เพื่อให้นับจำนวนได้ตรง
+
+pub const MAX_GATES_PER_EXPRESSION: usize = 500;
+const MAX_GATE_NUMBER: u32 = 9999;
+
+// gate เดียวของ runner (ไม่รวม prefix)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gate {
+    pub number: u32,
+    pub suffix: Option<char>, // 👈 ตัวอักษรย่อย เช่น 3a, 3b (เก็บเป็นตัวเล็กเสมอ)
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.suffix {
+            Some(suffix) => write!(f, "{}{}", self.number, suffix),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
+
+// gate พร้อม prefix ที่อ่านได้ (prefix ว่าง = ไม่ได้ระบุ runner)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PrefixedGate {
+    pub prefix: String,
+    pub gate: Gate,
+}
+
+impl fmt::Display for PrefixedGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix, self.gate)
+    }
+}
+
+// ชื่อเต็มของ gate ในรูปแบบมาตรฐาน: prefix เป็นตัวใหญ่เสมอ ไม่ว่าจะอ่านมาจาก token หรือชื่อ runner
+pub fn full_gate_name(runner_name: &str, gate: impl fmt::Display) -> String {
+    format!("{}{}", runner_name.trim().to_uppercase(), gate)
+}
+
+// "12" / "3a" (ไม่มี prefix แล้ว)
+fn parse_bare_gate(value: &str, original: &str) -> Result<Gate, String> {
+    let digits_end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, rest) = value.split_at(digits_end);
+
+    let number: u32 = digits
+        .parse()
+        .map_err(|_| format!("Invalid gate: {}", original))?;
+    if number == 0 || number > MAX_GATE_NUMBER {
+        return Err(format!("Invalid gate number: {}", original));
+    }
+
+    let mut rest_chars = rest.chars();
+    let suffix = match (rest_chars.next(), rest_chars.next()) {
+        (None, _) => None,
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(c.to_ascii_lowercase()),
+        _ => return Err(format!("Invalid gate: {}", original)),
+    };
+    Ok(Gate { number, suffix })
+}
+
+// อ่าน gate เดียว; ถ้ารู้ชื่อ runner จะตัดชื่อ runner ออก และไม่ยอมรับ prefix อื่น
+fn parse_single(token: &str, runner_name: Option<&str>) -> Result<PrefixedGate, String> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err("Empty gate".to_string());
+    }
+
+    match runner_name {
+        Some(runner) => {
+            let runner = runner.trim();
+            let rest = match token.get(..runner.len()) {
+                Some(head)
+                    if head.eq_ignore_ascii_case(runner)
+                        && token[runner.len()..].starts_with(|c: char| c.is_ascii_digit()) =>
+                {
+                    &token[runner.len()..]
+                }
+                _ if token.starts_with(|c: char| c.is_ascii_digit()) => token,
+                _ => {
+                    return Err(format!(
+                        "Gate {} does not belong to runner {}",
+                        token, runner
+                    ))
+                }
+            };
+            Ok(PrefixedGate {
+                prefix: runner.to_uppercase(),
+                gate: parse_bare_gate(rest, token)?,
+            })
+        }
+        None => {
+            let prefix_end = token
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(token.len());
+            let (prefix, rest) = token.split_at(prefix_end);
+            Ok(PrefixedGate {
+                prefix: prefix.to_uppercase(),
+                gate: parse_bare_gate(rest, token)?,
+            })
+        }
+    }
+}
+
+// gate เดียว (ไม่รับช่วง) เช่น "A12", "3a"
+pub fn parse_gate(token: &str, runner_name: Option<&str>) -> Result<PrefixedGate, String> {
+    parse_single(token, runner_name)
+}
+
+// อ่าน item เดียว ("A1", "A1-5", "3a-3c") แล้วกระจายเป็นรายตัว
+fn parse_item(item: &str, runner_name: Option<&str>) -> Result<Vec<PrefixedGate>, String> {
+    let item = item.trim();
+    let Some((start, end)) = item.split_once(['-', '~']) else {
+        return Ok(vec![parse_single(item, runner_name)?]);
+    };
+
+    let start = parse_single(start, runner_name)?;
+    // ปลายช่วงไม่ต้องใส่ prefix ซ้ำได้ ("A1-5" = "A1-A5")
+    let end_token = end.trim();
+    let end = if end_token.starts_with(|c: char| c.is_ascii_digit()) {
+        PrefixedGate {
+            prefix: start.prefix.clone(),
+            gate: parse_bare_gate(end_token, end_token)?,
+        }
+    } else {
+        parse_single(end_token, runner_name)?
+    };
+
+    if !start.prefix.eq_ignore_ascii_case(&end.prefix) {
+        return Err(format!("Range {} mixes different runners", item));
+    }
+    if start.gate > end.gate {
+        return Err(format!("Range {} is reversed", item));
+    }
+
+    let gates: Vec<Gate> = match (start.gate.suffix, end.gate.suffix) {
+        (None, None) => (start.gate.number..=end.gate.number)
+            .map(|number| Gate {
+                number,
+                suffix: None,
+            })
+            .collect(),
+        (Some(from), Some(to)) if start.gate.number == end.gate.number => (from..=to)
+            .map(|suffix| Gate {
+                number: start.gate.number,
+                suffix: Some(suffix),
+            })
+            .collect(),
+        _ => {
+            return Err(format!(
+                "Range {} must be numbers (1-5) or sub-letters of one gate (3a-3c)",
+                item
+            ))
+        }
+    };
+
+    if gates.len() > MAX_GATES_PER_EXPRESSION {
+        return Err(format!("Range {} is too large", item));
+    }
+    Ok(gates
+        .into_iter()
+        .map(|gate| PrefixedGate {
+            prefix: start.prefix.clone(),
+            gate,
+        })
+        .collect())
+}
+
+// อ่านหลาย expression แล้วรวมเป็นรายการเดียว เรียงและตัดตัวซ้ำ
+pub fn parse_gate_expressions(
+    expressions: &[String],
+    runner_name: Option<&str>,
+) -> Result<Vec<PrefixedGate>, String> {
+    let mut gates: Vec<PrefixedGate> = Vec::new();
+    for expression in expressions {
+        for item in expression.split([',', ';']) {
+            if item.trim().is_empty() {
+                continue;
+            }
+            gates.extend(parse_item(item, runner_name)?);
+            if gates.len() > MAX_GATES_PER_EXPRESSION {
+                return Err(format!("Too many gates (max {})", MAX_GATES_PER_EXPRESSION));
+            }
+        }
+    }
+    gates.sort();
+    gates.dedup();
+    Ok(gates)
+}
+
+// เรียงตามลำดับ gate จริง (A2 ก่อน A10); ค่าที่อ่านไม่ได้อยู่ท้ายสุด
+pub fn sort_gates(gates: &mut [String]) {
+    gates.sort_by_cached_key(|g| {
+        let parsed = parse_single(g, None).ok();
+        (parsed.is_none(), parsed, g.clone())
+    });
+}
+
+// แสดงแบบย่อ: A1, A2, A3, A5, A7a, A7b -> "A1-3, A5, A7a-7b"
+pub fn compact_gates(gates: &[PrefixedGate]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < gates.len() {
+        let start = &gates[i];
+        let mut j = i;
+        while let Some(next) = gates.get(j + 1) {
+            let current = &gates[j].gate;
+            let consecutive = next.prefix == start.prefix
+                && match (current.suffix, next.gate.suffix) {
+                    (None, None) => next.gate.number == current.number + 1,
+                    (Some(a), Some(b)) => {
+                        next.gate.number == current.number && b as u32 == a as u32 + 1
+                    }
+                    _ => false,
+                };
+            if !consecutive {
+                break;
+            }
+            j += 1;
+        }
+
+        if j == i {
+            parts.push(start.to_string());
+        } else {
+            parts.push(format!("{}-{}", start, gates[j].gate));
+        }
+        i = j + 1;
+    }
+    parts.join(", ")
+}
+
+// --- Payload / Response: POST /requirements/gates/parse ---
+#[derive(Debug, Deserialize)]
+pub struct ParseGatesPayload {
+    pub expression: String,     // 👈 เช่น "A1-5, A12, A3a-3c"
+    pub runner_id: Option<i64>, // 👈 ระบุแล้วจะใช้ชื่อ runner เป็น prefix และตรวจกับ gate inventory
+    pub qty: Option<i32>,       // 👈 ระบุแล้วจะเทียบกับจำนวน gate
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParsedGatesResponse {
+    pub gates: Vec<String>,
+    pub count: usize,
+    pub compact: String,
+    pub unknown_gates: Vec<String>, // 👈 gate ที่ runner ไม่มี (ว่างถ้าไม่ได้ระบุ runner หรือยังไม่กำหนด inventory)
+    pub qty_matches: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(expressions: &[&str], runner_name: Option<&str>) -> Result<Vec<String>, String> {
+        let expressions: Vec<String> = expressions.iter().map(|e| e.to_string()).collect();
+        parse_gate_expressions(&expressions, runner_name)
+            .map(|gates| gates.iter().map(|g| g.to_string()).collect())
+    }
+
+    #[test]
+    fn parses_single_gates_to_canonical_form() {
+        assert_eq!(names(&["a12"], None).unwrap(), ["A12"]);
+        assert_eq!(names(&["3B"], None).unwrap(), ["3b"]);
+        assert_eq!(names(&[" c4a "], None).unwrap(), ["C4a"]);
+    }
+
+    #[test]
+    fn rejects_invalid_gates() {
+        assert!(parse_gate("", None).is_err());
+        assert!(parse_gate("A", None).is_err());
+        assert!(parse_gate("A0", None).is_err());
+        assert!(parse_gate("A10000", None).is_err());
+        assert!(parse_gate("A3ab", None).is_err());
+        assert!(parse_gate("A3-", None).is_err());
+    }
+
+    #[test]
+    fn expands_number_ranges_with_and_without_repeated_prefix() {
+        assert_eq!(names(&["A1-3"], None).unwrap(), ["A1", "A2", "A3"]);
+        assert_eq!(names(&["A1-A3"], None).unwrap(), ["A1", "A2", "A3"]);
+        assert_eq!(names(&["a1~a3"], None).unwrap(), ["A1", "A2", "A3"]);
+        assert_eq!(names(&["A2-2"], None).unwrap(), ["A2"]);
+    }
+
+    #[test]
+    fn expands_sub_letter_ranges() {
+        assert_eq!(names(&["A3a-3c"], None).unwrap(), ["A3a", "A3b", "A3c"]);
+        assert_eq!(names(&["C4a-C4c"], None).unwrap(), ["C4a", "C4b", "C4c"]);
+    }
+
+    #[test]
+    fn rejects_reversed_ranges() {
+        assert!(names(&["A5-1"], None).unwrap_err().contains("reversed"));
+        assert!(names(&["A3c-3a"], None).unwrap_err().contains("reversed"));
+    }
+
+    #[test]
+    fn rejects_mixed_ranges() {
+        assert!(names(&["A1-B3"], None).unwrap_err().contains("mixes"));
+        assert!(names(&["A3-4a"], None)
+            .unwrap_err()
+            .contains("must be numbers"));
+        assert!(names(&["A3a-4b"], None)
+            .unwrap_err()
+            .contains("must be numbers"));
+    }
+
+    #[test]
+    fn rejects_ranges_over_the_limit() {
+        assert!(names(&["A1-9999"], None).unwrap_err().contains("too large"));
+        assert!(names(&["A1-300", "B1-300"], None)
+            .unwrap_err()
+            .contains("Too many gates"));
+    }
+
+    #[test]
+    fn sorts_and_dedups_across_expressions() {
+        assert_eq!(
+            names(&["A10, A2; A1-3", "A2"], None).unwrap(),
+            ["A1", "A2", "A3", "A10"]
+        );
+        assert_eq!(names(&["A1,, ;"], None).unwrap(), ["A1"]);
+    }
+
+    #[test]
+    fn uses_the_runner_name_as_prefix() {
+        assert_eq!(names(&["1-2, b3"], Some("b")).unwrap(), ["B1", "B2", "B3"]);
+        assert_eq!(names(&["Ab1"], Some("AB")).unwrap(), ["AB1"]);
+        assert!(names(&["A1"], Some("B"))
+            .unwrap_err()
+            .contains("does not belong"));
+    }
+
+    #[test]
+    fn full_gate_name_uppercases_the_prefix() {
+        let gate = Gate {
+            number: 3,
+            suffix: Some('a'),
+        };
+        assert_eq!(full_gate_name(" b ", gate), "B3a");
+    }
+
+    #[test]
+    fn sorts_gate_strings_naturally() {
+        let mut gates = vec![
+            "A10".to_string(),
+            "x?".to_string(),
+            "A2".to_string(),
+            "A2a".to_string(),
+        ];
+        sort_gates(&mut gates);
+        assert_eq!(gates, ["A2", "A2a", "A10", "x?"]);
+    }
+
+    #[test]
+    fn compacts_consecutive_gates() {
+        let expressions = vec!["A1-3, A5, A7a-7b, B1".to_string()];
+        let gates = parse_gate_expressions(&expressions, None).unwrap();
+        assert_eq!(compact_gates(&gates), "A1-3, A5, A7a-7b, B1");
+    }
+}
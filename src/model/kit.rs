@@ -23,6 +23,16 @@ pub enum KitStatus {
     Done,
 }
 
+impl KitStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KitStatus::Pending => "pending",
+            KitStatus::InProgress => "in_progress",
+            KitStatus::Done => "done",
+        }
+    }
+}
+
 impl FromStr for KitStatus {
     type Err = String;
 
@@ -36,7 +46,7 @@ impl FromStr for KitStatus {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "TEXT")]
 #[sqlx(rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
@@ -50,6 +60,20 @@ pub enum KitGrade {
     Other,
 }
 
+impl KitGrade {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KitGrade::Eg => "eg",
+            KitGrade::Hg => "hg",
+            KitGrade::Rg => "rg",
+            KitGrade::Mg => "mg",
+            KitGrade::Mgsd => "mgsd",
+            KitGrade::Pg => "pg",
+            KitGrade::Other => "other",
+        }
+    }
+}
+
 impl FromStr for KitGrade {
     type Err = String;
 
@@ -75,7 +99,10 @@ pub struct Kit {
     pub name: String,
     pub grade: KitGrade,
     pub status: KitStatus,
-    pub user_id: i64,
+    pub tags: Vec<String>,
+    pub purchase_price: Option<f64>,
+    pub user_id: i64,              // 👈 owner ของ kit
+    pub updated_by: Option<i64>,   // 👈 ผู้แก้ไขล่าสุด (สมาชิกคนไหนก็ได้)
     pub created_at: NaiveDateTime, // 👈 เมื่อดึงจาก DB จะมีค่าเสมอ
     pub updated_at: NaiveDateTime,
 }
@@ -99,6 +126,9 @@ pub struct KitWithRunners {
 pub struct CreateKitPayload {
     pub name: String,
     pub grade: KitGrade,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub purchase_price: Option<f64>,
 }
 
 // ใช้สำหรับอัปเดตข้อมูล Kit (PATCH /kits/:id)
@@ -107,6 +137,8 @@ pub struct CreateKitPayload {
 pub struct UpdateKitPayload {
     pub name: Option<String>,
     pub grade: Option<KitGrade>,
+    pub tags: Option<Vec<String>>,
+    pub purchase_price: Option<f64>,
 }
 
 // ใช้สำหรับอัปเดตเฉพาะ status (เช่น PATCH /kits/:id/status)
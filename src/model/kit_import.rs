@@ -0,0 +1,269 @@
+// src/model/kit_import.rs
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::model::kit::{KitGrade, KitStatus};
+
+// --- Payload: POST /kits/import/csv ---
+#[derive(Debug, Deserialize)]
+pub struct ImportKitsCsvPayload {
+    pub csv: String,                    // 👈 เนื้อหาไฟล์ CSV ทั้งไฟล์ (ต้องมี header แถวแรก)
+    pub mapping: Option<KitCsvMapping>, // 👈 ไม่ส่งมา = เดาจากชื่อ header
+    pub delimiter: Option<char>,        // 👈 default ','
+    #[serde(default)]
+    pub dry_run: bool, // 👈 true = ตรวจอย่างเดียว ไม่บันทึก
+}
+
+// ชื่อ header ในไฟล์ที่ตรงกับแต่ละฟิลด์ (ไม่สนตัวพิมพ์เล็ก/ใหญ่)
+#[derive(Debug, Deserialize, Default)]
+pub struct KitCsvMapping {
+    pub name: Option<String>,
+    pub grade: Option<String>,
+    pub status: Option<String>,
+    pub tags: Option<String>,
+    pub purchase_price: Option<String>,
+}
+
+// --- Report ---
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KitImportRowStatus {
+    Created,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KitImportRowResult {
+    pub row: u64, // 👈 เลขบรรทัดในไฟล์ (header = บรรทัดที่ 1)
+    pub status: KitImportRowStatus,
+    pub name: Option<String>,
+    pub kit_id: Option<i64>, // 👈 มีค่าเมื่อบันทึกจริง (ไม่ใช่ dry run) หรือเป็น kit เดิมที่ข้ามไป
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KitImportReport {
+    pub dry_run: bool,
+    pub total: usize,
+    pub created: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub rows: Vec<KitImportRowResult>,
+}
+
+// แถวที่ผ่านการตรวจแล้ว พร้อม insert
+#[derive(Debug, Clone)]
+pub struct NewKitRow {
+    pub name: String,
+    pub grade: KitGrade,
+    pub status: KitStatus,
+    pub tags: Vec<String>,
+    pub purchase_price: Option<f64>,
+}
+
+// --- Parsing helpers (รองรับค่าที่มาจาก spreadsheet เช่น "HG", "In Progress", "฿1,250.00") ---
+
+pub fn parse_grade(value: &str) -> Result<KitGrade, String> {
+    let normalized: String = value
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+        .collect();
+    KitGrade::from_str(&normalized)
+}
+
+pub fn parse_status(value: &str) -> Result<KitStatus, String> {
+    let normalized = value.trim().to_lowercase().replace([' ', '-'], "_");
+    if normalized.is_empty() {
+        return Ok(KitStatus::Pending);
+    }
+    KitStatus::from_str(&normalized)
+}
+
+// แยก tag ด้วย ; | หรือ , แล้วตัดตัวซ้ำ
+pub fn parse_tags(value: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in value.split([';', '|', ',']) {
+        let tag = tag.trim();
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+// สัญลักษณ์/รหัสสกุลเงินที่ตัดออกได้ (หน้าหรือหลังตัวเลข)
+const CURRENCY_SYMBOLS: &[&str] = &[
+    "฿",
+    "$",
+    "€",
+    "£",
+    "¥",
+    "บาท",
+    "THB",
+    "USD",
+    "EUR",
+    "GBP",
+    "JPY",
+];
+
+// แยกจุดทศนิยมกับตัวคั่นหลักพัน: "1,250.00" / "1.250,00" / "1 250,00" / "12,5" / "1.250"
+// ตัวคั่นตัวสุดท้ายเป็นทศนิยม ยกเว้นตามด้วยเลข 3 หลักพอดีและไม่มีตัวคั่นอีกแบบอยู่ก่อน (= หลักพัน)
+// ตัวคั่นหลักพันต้องเป็นแบบเดียวกันทั้งหมดและคั่นทีละ 3 หลัก ไม่งั้นคืน None
+fn normalize_price(number: &str) -> Option<String> {
+    let last = number.rfind(['.', ',']);
+    let (integer, decimal) = match last {
+        Some(i) => {
+            let separator = &number[i..i + 1];
+            let fraction = &number[i + 1..];
+            let other = if separator == "." { ',' } else { '.' };
+            if fraction.len() != 3 || number[..i].contains(other) {
+                (&number[..i], Some((separator, fraction)))
+            } else {
+                (number, None)
+            }
+        }
+        None => (number, None),
+    };
+
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let fraction = match decimal {
+        Some((_, fraction)) if !is_digits(fraction) => return None,
+        Some((_, fraction)) => fraction,
+        None => "0",
+    };
+    if integer.is_empty() && decimal.is_some() {
+        return Some(format!("0.{}", fraction)); // 👈 ".5"
+    }
+
+    let separators: Vec<&str> = integer.matches(['.', ',']).collect();
+    let groups: Vec<&str> = integer.split(['.', ',']).collect();
+    if let Some(thousands) = separators.first() {
+        if separators.iter().any(|s| s != thousands)
+            || decimal.is_some_and(|(separator, _)| separator == *thousands)
+            || groups[0].len() > 3
+            || groups[1..].iter().any(|g| g.len() != 3)
+        {
+            return None;
+        }
+    }
+    if !groups.iter().all(|g| is_digits(g)) {
+        return None;
+    }
+    Some(format!("{}.{}", groups.concat(), fraction))
+}
+
+// ตัดสัญลักษณ์สกุลเงินกับช่องว่างออก จัดการตัวคั่นหลักพัน ที่เหลือต้องเป็นตัวเลขล้วน
+pub fn parse_purchase_price(value: &str) -> Result<Option<f64>, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || format!("Invalid purchase price: {}", trimmed);
+
+    let mut number = trimmed;
+    for symbol in CURRENCY_SYMBOLS {
+        if let Some(rest) = number.strip_prefix(symbol) {
+            number = rest;
+            break;
+        }
+        if let Some(rest) = number.strip_suffix(symbol) {
+            number = rest;
+            break;
+        }
+    }
+    let number: String = number.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let price = normalize_price(&number)
+        .and_then(|n| n.parse::<f64>().ok())
+        .ok_or_else(invalid)?;
+    if !price.is_finite() {
+        return Err(invalid());
+    }
+    Ok(Some(price))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(value: &str) -> Option<f64> {
+        parse_purchase_price(value).unwrap()
+    }
+
+    #[test]
+    fn empty_price_is_none() {
+        assert_eq!(price(""), None);
+        assert_eq!(price("   "), None);
+    }
+
+    #[test]
+    fn parses_plain_and_decimal_prices() {
+        assert_eq!(price("1250"), Some(1250.0));
+        assert_eq!(price("12.5"), Some(12.5));
+        assert_eq!(price("12,5"), Some(12.5));
+        assert_eq!(price(".5"), Some(0.5));
+    }
+
+    #[test]
+    fn parses_thousands_separators_in_both_conventions() {
+        assert_eq!(price("1,250.00"), Some(1250.0));
+        assert_eq!(price("1.250,00"), Some(1250.0));
+        assert_eq!(price("1 250,00"), Some(1250.0));
+        assert_eq!(price("1.250"), Some(1250.0));
+        assert_eq!(price("1,250"), Some(1250.0));
+        assert_eq!(price("1.250.000"), Some(1_250_000.0));
+        assert_eq!(price("1,250,000.75"), Some(1_250_000.75));
+    }
+
+    #[test]
+    fn strips_currency_symbols_and_codes() {
+        assert_eq!(price("฿1,250.00"), Some(1250.0));
+        assert_eq!(price("$ 49.99"), Some(49.99));
+        assert_eq!(price("1.250,00 €"), Some(1250.0));
+        assert_eq!(price("1250 บาท"), Some(1250.0));
+        assert_eq!(price("USD 12"), Some(12.0));
+    }
+
+    #[test]
+    fn rejects_malformed_prices() {
+        for value in [
+            "abc",
+            "12abc",
+            "-5",
+            "5.",
+            "1,25,000",
+            "12345,678",
+            "1.250.00",
+            "1,250,00",
+            "1.250,000.00",
+            "1e5",
+            "NaN",
+            "$",
+            "฿1,250$",
+        ] {
+            let err = parse_purchase_price(value).unwrap_err();
+            assert!(err.starts_with("Invalid purchase price"), "{value}: {err}");
+        }
+    }
+
+    #[test]
+    fn parses_grade_and_status_loosely() {
+        assert!(parse_grade("High-Grade").is_err());
+        assert_eq!(parse_grade("H G").unwrap(), KitGrade::Hg);
+        assert_eq!(parse_status("In Progress").unwrap(), KitStatus::InProgress);
+        assert_eq!(parse_status("").unwrap(), KitStatus::Pending);
+        assert!(parse_status("sold").is_err());
+    }
+
+    #[test]
+    fn splits_and_dedups_tags() {
+        assert_eq!(
+            parse_tags("Gundam; UC | gundam, , Zaku"),
+            ["Gundam", "UC", "Zaku"]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// src/model/kit_member.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+// --- Enums ---
+// owner: จัดการสมาชิก / ลบ kit / แชร์ลิงก์
+// editor: แก้ไข kit และลูกทั้งหมด (runners, sub_assemblies, kit_parts, requirements)
+// viewer: อ่านอย่างเดียว
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum KitRole {
+    Owner,
+    Editor,
+    Viewer,
+}
+
+impl KitRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KitRole::Owner => "owner",
+            KitRole::Editor => "editor",
+            KitRole::Viewer => "viewer",
+        }
+    }
+}
+
+impl FromStr for KitRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(KitRole::Owner),
+            "editor" => Ok(KitRole::Editor),
+            "viewer" => Ok(KitRole::Viewer),
+            _ => Err(format!("Invalid role: {}", s)),
+        }
+    }
+}
+
+// --- Main Model: KitMember ---
+#[derive(Debug, Serialize, Clone)]
+pub struct KitMember {
+    pub kit_id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub full_name: Option<String>,
+    pub role: KitRole,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// --- Payload Structs ---
+
+// ใช้สำหรับเชิญสมาชิก (POST /kits/:id/members)
+#[derive(Debug, Deserialize)]
+pub struct AddKitMemberPayload {
+    pub username: String,
+    pub role: KitRole,
+}
+
+// ใช้สำหรับเปลี่ยน role (PATCH /kits/:id/members/:user_id)
+#[derive(Debug, Deserialize)]
+pub struct UpdateKitMemberPayload {
+    pub role: KitRole,
+}
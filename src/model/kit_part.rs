@@ -1,6 +1,8 @@
 // src/models/kit_part.rs
 
+use crate::model::kit_part_paint::{KitPartPaint, KitPartRecipe};
 use crate::model::sub_assembly::SubAssembly;
+use crate::model::workflow::KitPartStage;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -14,6 +16,7 @@ pub struct KitPart {
     pub kit_id: i64,
     pub sub_assembly_id: i64,
     pub user_id: i64,
+    pub updated_by: Option<i64>, // 👈 ผู้แก้ไขล่าสุด
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -37,6 +40,7 @@ pub struct KitPartRequirement {
     pub runner_id: i64,   // Foreign key to runners table
     pub kit_part_id: i64, // Foreign key to kit_parts table
     pub user_id: i64,     // Foreign key to users table
+    pub updated_by: Option<i64>,
 }
 
 // --- Response: KitPart with SubAssembly and requirements ---
@@ -46,6 +50,9 @@ pub struct KitPartWithSubAssemblyAndRequirements {
     pub kit_part: KitPart,
     pub sub_assembly: SubAssembly,
     pub requirements: Vec<KitPartRequirement>,
+    pub paints: Vec<KitPartPaint>, // 👈 เรียงตาม position
+    pub recipes: Vec<KitPartRecipe>,
+    pub stage: Option<KitPartStage>, // 👈 None = ยังไม่เริ่ม workflow
 }
 
 // --- Payloads for KitPart ---
@@ -0,0 +1,117 @@
+// src/model/kit_part_paint.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::paint::{PaintFinish, PaintType};
+
+// หน้าที่ของสีบน part (เรียงตามขั้นตอนทำสีโดยทั่วไป)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PaintRole {
+    Base,
+    Highlight,
+    PanelLine,
+    Topcoat,
+}
+
+impl PaintRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaintRole::Base => "base",
+            PaintRole::Highlight => "highlight",
+            PaintRole::PanelLine => "panel_line",
+            PaintRole::Topcoat => "topcoat",
+        }
+    }
+}
+
+// สีที่ผูกกับ kit part หนึ่งชิ้น (ใส่ใน KitPartWithSubAssemblyAndRequirements ด้วย)
+#[derive(Debug, Serialize, Clone)]
+pub struct KitPartPaint {
+    pub paint_id: i64,
+    pub name: String,
+    pub brand: Option<String>,
+    pub code: Option<String>,
+    pub paint_type: Option<PaintType>,
+    pub finish: Option<PaintFinish>,
+    pub hex: Option<String>,
+    pub role: PaintRole,
+    pub position: i32, // 👈 ลำดับการทำสีบน part นี้ (น้อย = ทำก่อน)
+}
+
+// --- Payload: POST /kit_parts/:id/paints (ผูกซ้ำ paint + role เดิม = แก้ position) ---
+#[derive(Debug, Deserialize)]
+pub struct AttachPaintPayload {
+    pub paint_id: i64,
+    pub role: PaintRole,
+    pub position: Option<i32>, // 👈 ไม่ส่ง = ต่อท้าย
+}
+
+// สูตรผสมสีที่ผูกกับ kit part (ใช้ role / position แบบเดียวกับ paint)
+#[derive(Debug, Serialize, Clone)]
+pub struct KitPartRecipe {
+    pub recipe_id: i64,
+    pub name: String,
+    pub hex: Option<String>, // 👈 สีประมาณจากส่วนผสม
+    pub role: PaintRole,
+    pub position: i32,
+}
+
+// --- Payload: POST /kit_parts/:id/recipes ---
+#[derive(Debug, Deserialize)]
+pub struct AttachRecipePayload {
+    pub recipe_id: i64,
+    pub role: PaintRole,
+    pub position: Option<i32>, // 👈 ไม่ส่ง = ต่อท้าย
+}
+
+// --- Query: DELETE /kit_parts/:id/paints/:paint_id?role= (และ /recipes/:recipe_id; ไม่ส่ง role = ถอดทุก role) ---
+#[derive(Debug, Deserialize)]
+pub struct DetachPaintQuery {
+    pub role: Option<PaintRole>,
+}
+
+// --- Response: GET /kits/:id/paint_plan (จัดกลุ่ม part ตาม paint) ---
+#[derive(Debug, Serialize, Clone)]
+pub struct PaintPlanPart {
+    pub kit_part_id: i64,
+    pub code: Option<String>,
+    pub sub_assembly_id: i64,
+    pub sub_assembly_name: String,
+    pub is_cut: bool,
+    pub role: PaintRole,
+    pub position: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PaintPlanEntry {
+    pub paint_id: i64,
+    pub name: String,
+    pub brand: Option<String>,
+    pub code: Option<String>,
+    pub paint_type: Option<PaintType>,
+    pub finish: Option<PaintFinish>,
+    pub hex: Option<String>,
+    pub part_count: i64, // 👈 จำนวน part ไม่ซ้ำ (part เดียวหลาย role นับครั้งเดียว)
+    pub parts: Vec<PaintPlanPart>,
+}
+
+// สูตรผสมในแผน: part ที่ใช้สูตร (ส่วนผสมดูได้จาก GET /recipes/:id)
+#[derive(Debug, Serialize, Clone)]
+pub struct RecipePlanEntry {
+    pub recipe_id: i64,
+    pub name: String,
+    pub hex: Option<String>,
+    pub part_count: i64,
+    pub parts: Vec<PaintPlanPart>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KitPaintPlan {
+    pub kit_id: i64,
+    pub paints: Vec<PaintPlanEntry>,
+    pub recipes: Vec<RecipePlanEntry>,
+    pub unpainted_part_count: i64, // 👈 part ที่ยังไม่ได้กำหนดสี (ทั้ง paint และสูตรผสม)
+}
@@ -0,0 +1,316 @@
+// src/model/lab.rs
+// แปลง hex → CIE Lab (D65) และวัดระยะห่างของสีแบบ CIEDE2000 (ใกล้เคียงที่ตาเห็นจริง)
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+// รับ "#RGB", "RGB", "#RRGGBB", "RRGGBB" (ตัวพิมพ์เล็ก/ใหญ่ก็ได้) แล้วคืนรูปแบบมาตรฐาน "#rrggbb"
+pub fn normalize_hex(input: &str) -> Result<String, String> {
+    let raw = input.trim();
+    let digits = raw.strip_prefix('#').unwrap_or(raw);
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid hex color: {}", input));
+    }
+    let digits = digits.to_ascii_lowercase();
+    match digits.len() {
+        3 => Ok(format!(
+            "#{}",
+            digits.chars().flat_map(|c| [c, c]).collect::<String>()
+        )),
+        6 => Ok(format!("#{}", digits)),
+        _ => Err(format!(
+            "Invalid hex color: {} (expected #RGB or #RRGGBB)",
+            input
+        )),
+    }
+}
+
+// hex ที่ normalize แล้วหรือยังก็ได้ (ไม่ถูกต้อง = None)
+pub fn hex_to_lab(hex: &str) -> Option<Lab> {
+    let hex = normalize_hex(hex).ok()?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let (r, g, b) = (channel(1)?, channel(3)?, channel(5)?);
+
+    // sRGB → linear RGB
+    let linear = |v: u8| {
+        let v = v as f64 / 255.0;
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+
+    // linear RGB → XYZ (D65) แล้วหารด้วย white point
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.119192 * g + 0.9503041 * b) / 1.08883;
+
+    let f = |t: f64| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+
+    Some(Lab {
+        l: 116.0 * fy - 16.0,
+        a: 500.0 * (fx - fy),
+        b: 200.0 * (fy - fz),
+    })
+}
+
+// ΔE00 (Sharma, Wu, Dalal 2005) — ต่ำกว่า ~1 แทบแยกไม่ออก, ~2-3 ใกล้มาก
+pub fn ciede2000(lab1: Lab, lab2: Lab) -> f64 {
+    let (l1, a1, b1) = (lab1.l, lab1.a, lab1.b);
+    let (l2, a2, b2) = (lab2.l, lab2.a, lab2.b);
+
+    let c1 = a1.hypot(b1);
+    let c2 = a2.hypot(b2);
+    let c_bar = (c1 + c2) / 2.0;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt());
+
+    let a1p = (1.0 + g) * a1;
+    let a2p = (1.0 + g) * a2;
+    let c1p = a1p.hypot(b1);
+    let c2p = a2p.hypot(b2);
+
+    let hue = |b: f64, ap: f64| {
+        if b == 0.0 && ap == 0.0 {
+            0.0
+        } else {
+            let h = b.atan2(ap).to_degrees();
+            if h < 0.0 {
+                h + 360.0
+            } else {
+                h
+            }
+        }
+    };
+    let h1p = hue(b1, a1p);
+    let h2p = hue(b2, a2p);
+
+    let delta_lp = l2 - l1;
+    let delta_cp = c2p - c1p;
+    let delta_hp = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p - h1p > 180.0 {
+        h2p - h1p - 360.0
+    } else {
+        h2p - h1p + 360.0
+    };
+    let delta_big_hp = 2.0 * (c1p * c2p).sqrt() * (delta_hp / 2.0).to_radians().sin();
+
+    let l_bar_p = (l1 + l2) / 2.0;
+    let c_bar_p = (c1p + c2p) / 2.0;
+    let h_bar_p = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar_p - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar_p).to_radians().cos()
+        + 0.32 * (3.0 * h_bar_p + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar_p - 63.0).to_radians().cos();
+    let delta_theta = 30.0 * (-((h_bar_p - 275.0) / 25.0).powi(2)).exp();
+    let c_bar_p7 = c_bar_p.powi(7);
+    let r_c = 2.0 * (c_bar_p7 / (c_bar_p7 + 25f64.powi(7))).sqrt();
+    let l_term = (l_bar_p - 50.0).powi(2);
+    let s_l = 1.0 + 0.015 * l_term / (20.0 + l_term).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar_p;
+    let s_h = 1.0 + 0.015 * c_bar_p * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let dl = delta_lp / s_l;
+    let dc = delta_cp / s_c;
+    let dh = delta_big_hp / s_h;
+    (dl * dl + dc * dc + dh * dh + r_t * dc * dh).sqrt()
+}
+
+// ประมาณสีที่ได้จากการผสม: เฉลี่ยถ่วงน้ำหนักใน linear RGB แล้วแปลงกลับเป็น hex
+// (สีจริงผสมแบบ subtractive จะเข้มกว่านี้เล็กน้อย แต่พอใช้จับคู่สีใกล้เคียง)
+pub fn mix_hex(components: &[(&str, f64)]) -> Option<String> {
+    let mut total = 0.0;
+    let mut sum = [0.0; 3];
+    for (hex, weight) in components {
+        let Ok(hex) = normalize_hex(hex) else {
+            continue;
+        };
+        if *weight <= 0.0 {
+            continue;
+        }
+        for (i, channel) in sum.iter_mut().enumerate() {
+            let v = u8::from_str_radix(&hex[1 + i * 2..3 + i * 2], 16).ok()? as f64 / 255.0;
+            let linear = if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            };
+            *channel += linear * weight;
+        }
+        total += weight;
+    }
+    if total <= 0.0 {
+        return None;
+    }
+
+    let to_srgb = |v: f64| {
+        let v = (v / total).clamp(0.0, 1.0);
+        let v = if v <= 0.0031308 {
+            v * 12.92
+        } else {
+            1.055 * v.powf(1.0 / 2.4) - 0.055
+        };
+        (v * 255.0).round() as u8
+    };
+    Some(format!(
+        "#{:02x}{:02x}{:02x}",
+        to_srgb(sum[0]),
+        to_srgb(sum[1]),
+        to_srgb(sum[2])
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lab(l: f64, a: f64, b: f64) -> Lab {
+        Lab { l, a, b }
+    }
+
+    // ชุดทดสอบอ้างอิงจาก Sharma, Wu, Dalal (2005) ตาราง 1: (L1, a1, b1, L2, a2, b2, ΔE00)
+    const SHARMA_PAIRS: &[[f64; 7]] = &[
+        [50.0, 2.6772, -79.7751, 50.0, 0.0, -82.7485, 2.0425],
+        [50.0, 3.1571, -77.2803, 50.0, 0.0, -82.7485, 2.8615],
+        [50.0, 2.8361, -74.0200, 50.0, 0.0, -82.7485, 3.4412],
+        [50.0, -1.3802, -84.2814, 50.0, 0.0, -82.7485, 1.0000],
+        [50.0, -1.1848, -84.8006, 50.0, 0.0, -82.7485, 1.0000],
+        [50.0, -0.9009, -85.5211, 50.0, 0.0, -82.7485, 1.0000],
+        [50.0, 0.0, 0.0, 50.0, -1.0, 2.0, 2.3669],
+        [50.0, -1.0, 2.0, 50.0, 0.0, 0.0, 2.3669],
+        [50.0, 2.4900, -0.0010, 50.0, -2.4900, 0.0009, 7.1792],
+        [50.0, 2.4900, -0.0010, 50.0, -2.4900, 0.0010, 7.1792],
+        [50.0, 2.4900, -0.0010, 50.0, -2.4900, 0.0011, 7.2195],
+        [50.0, 2.4900, -0.0010, 50.0, -2.4900, 0.0012, 7.2195],
+        [50.0, -0.0010, 2.4900, 50.0, 0.0009, -2.4900, 4.8045],
+        [50.0, -0.0010, 2.4900, 50.0, 0.0010, -2.4900, 4.8045],
+        [50.0, -0.0010, 2.4900, 50.0, 0.0011, -2.4900, 4.7461],
+        [50.0, 2.5, 0.0, 50.0, 0.0, -2.5, 4.3065],
+        [50.0, 2.5, 0.0, 73.0, 25.0, -18.0, 27.1492],
+        [50.0, 2.5, 0.0, 61.0, -5.0, 29.0, 22.8977],
+        [50.0, 2.5, 0.0, 56.0, -27.0, -3.0, 31.9030],
+        [50.0, 2.5, 0.0, 58.0, 24.0, 15.0, 19.4535],
+        [50.0, 2.5, 0.0, 50.0, 3.1736, 0.5854, 1.0000],
+        [50.0, 2.5, 0.0, 50.0, 3.2972, 0.0, 1.0000],
+        [50.0, 2.5, 0.0, 50.0, 1.8634, 0.5757, 1.0000],
+        [50.0, 2.5, 0.0, 50.0, 3.2592, 0.3350, 1.0000],
+        [
+            60.2574, -34.0099, 36.2677, 60.4626, -34.1751, 39.4387, 1.2644,
+        ],
+        [
+            63.0109, -31.0961, -5.8663, 62.8187, -29.7946, -4.0864, 1.2630,
+        ],
+        [61.2901, 3.7196, -5.3901, 61.4292, 2.2480, -4.9620, 1.8731],
+        [35.0831, -44.1164, 3.7933, 35.0232, -40.0716, 1.5901, 1.8645],
+        [
+            22.7233, 20.0904, -46.6940, 23.0331, 14.9730, -42.5619, 2.0373,
+        ],
+        [36.4612, 47.8580, 18.3852, 36.2715, 50.5065, 21.2231, 1.4146],
+        [90.8027, -2.0831, 1.4410, 91.1528, -1.6435, 0.0447, 1.4441],
+        [90.9257, -0.5406, -0.9208, 88.6381, -0.8985, -0.7239, 1.5381],
+        [6.7747, -0.2908, -2.4247, 5.8714, -0.0985, -2.2286, 0.6377],
+        [2.0776, 0.0795, -1.1350, 0.9033, -0.0636, -0.5514, 0.9082],
+    ];
+
+    #[test]
+    fn ciede2000_matches_sharma_reference_pairs() {
+        for (i, p) in SHARMA_PAIRS.iter().enumerate() {
+            let lab1 = lab(p[0], p[1], p[2]);
+            let lab2 = lab(p[3], p[4], p[5]);
+            let forward = ciede2000(lab1, lab2);
+            let backward = ciede2000(lab2, lab1);
+            assert!(
+                (forward - p[6]).abs() < 1e-4,
+                "pair {}: {forward} != {}",
+                i + 1,
+                p[6]
+            );
+            assert!(
+                (backward - p[6]).abs() < 1e-4,
+                "pair {} reversed: {backward}",
+                i + 1
+            );
+        }
+    }
+
+    #[test]
+    fn ciede2000_of_identical_colors_is_zero() {
+        let red = hex_to_lab("#ff0000").unwrap();
+        assert_eq!(ciede2000(red, red), 0.0);
+    }
+
+    #[test]
+    fn normalizes_hex() {
+        assert_eq!(normalize_hex("#ABC").unwrap(), "#aabbcc");
+        assert_eq!(normalize_hex(" 12AbEf ").unwrap(), "#12abef");
+        assert!(normalize_hex("#12345").is_err());
+        assert!(normalize_hex("#ggg").is_err());
+        assert!(normalize_hex("").is_err());
+    }
+
+    #[test]
+    fn converts_hex_to_lab() {
+        let close = |a: Lab, l: f64, aa: f64, b: f64| {
+            (a.l - l).abs() < 0.01 && (a.a - aa).abs() < 0.01 && (a.b - b).abs() < 0.01
+        };
+        assert!(close(hex_to_lab("#ffffff").unwrap(), 100.0, 0.0, 0.0));
+        assert!(close(hex_to_lab("#000").unwrap(), 0.0, 0.0, 0.0));
+        assert!(close(hex_to_lab("#ff0000").unwrap(), 53.24, 80.09, 67.20));
+        assert!(hex_to_lab("not a color").is_none());
+    }
+
+    #[test]
+    fn mixes_in_linear_rgb() {
+        assert_eq!(mix_hex(&[("#ff0000", 1.0)]).unwrap(), "#ff0000");
+        assert_eq!(
+            mix_hex(&[("#000000", 1.0), ("#ffffff", 1.0)]).unwrap(),
+            "#bcbcbc"
+        );
+        assert_eq!(
+            mix_hex(&[("#ff0000", 1.0), ("#0000ff", 1.0)]).unwrap(),
+            "#bc00bc"
+        );
+        assert_eq!(
+            mix_hex(&[("#ff0000", 3.0), ("#ff0000", 1.0)]).unwrap(),
+            "#ff0000"
+        );
+    }
+
+    #[test]
+    fn mix_skips_invalid_components() {
+        assert_eq!(
+            mix_hex(&[("#fff", 1.0), ("nope", 5.0), ("#000", 0.0)]).unwrap(),
+            "#ffffff"
+        );
+        assert_eq!(mix_hex(&[("nope", 1.0)]), None);
+        assert_eq!(mix_hex(&[]), None);
+    }
+}
@@ -1,12 +1,28 @@
 pub mod auth;
 pub mod color;
 pub mod common;
+pub mod dashboard;
+pub mod gate;
 pub mod jwt;
 pub mod kit;
+pub mod kit_import;
+pub mod kit_member;
 pub mod kit_part;
+pub mod kit_part_paint;
+pub mod lab;
 pub mod paint;
+pub mod paint_catalog;
+pub mod paint_recipe;
+pub mod palette;
+pub mod queue;
 pub mod requirement;
 pub mod runner;
+pub mod runner_color;
+pub mod runner_map;
+pub mod share;
+pub mod spare;
 pub mod steam;
 pub mod sub_assembly;
+pub mod trash;
 pub mod user;
+pub mod workflow;
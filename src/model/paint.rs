@@ -1,22 +1,243 @@
-// src/models/paint.rs
-
-// use chrono::NaiveDateTime;
-// use serde::{Deserialize, Serialize};
-// use sqlx::FromRow;
-
-// #[derive(Debug, Serialize, Clone, FromRow)]
-// pub struct Paint {
-//     pub id: i64,
-//     pub name: String,
-//     pub brand: Option<String>,
-//     pub code: Option<String>,
-//     pub user_id: i64,
-//     pub created_at: NaiveDateTime,
-//     pub updated_at: NaiveDateTime,
-// }
-// #[derive(Debug, Deserialize)]
-// pub struct CreatePaintPayload {
-//     pub name: String,
-//     pub brand: Option<String>,
-//     pub code: Option<String>,
-// }
+// src/model/paint.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// --- Enums (เก็บเป็น TEXT ใน DB) ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PaintType {
+    Lacquer,
+    Acrylic,
+    Enamel,
+    Other,
+}
+
+impl PaintType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaintType::Lacquer => "lacquer",
+            PaintType::Acrylic => "acrylic",
+            PaintType::Enamel => "enamel",
+            PaintType::Other => "other",
+        }
+    }
+
+    // ค่าจากไฟล์ import (ไม่สนตัวพิมพ์)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "lacquer" => Some(PaintType::Lacquer),
+            "acrylic" => Some(PaintType::Acrylic),
+            "enamel" => Some(PaintType::Enamel),
+            "other" => Some(PaintType::Other),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PaintFinish {
+    Gloss,
+    SemiGloss,
+    Matte,
+    Metallic,
+    Pearl,
+    Clear,
+    Fluorescent,
+}
+
+impl PaintFinish {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaintFinish::Gloss => "gloss",
+            PaintFinish::SemiGloss => "semi_gloss",
+            PaintFinish::Matte => "matte",
+            PaintFinish::Metallic => "metallic",
+            PaintFinish::Pearl => "pearl",
+            PaintFinish::Clear => "clear",
+            PaintFinish::Fluorescent => "fluorescent",
+        }
+    }
+}
+
+// สถานะสต็อก (คอลัมน์ generated ใน DB จาก quantity / fill_level / low_stock_threshold)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum StockStatus {
+    Ok,
+    Low,
+    Out,
+}
+
+impl StockStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StockStatus::Ok => "ok",
+            StockStatus::Low => "low",
+            StockStatus::Out => "out",
+        }
+    }
+}
+
+// --- Main Model: Paint (สีพ่น/สีทา ของ user แต่ละคน) ---
+#[derive(Debug, Serialize, Clone)]
+pub struct Paint {
+    pub id: i64,
+    pub name: String,
+    pub brand: Option<String>, // 👈 เช่น "Mr. Color", "Tamiya"
+    pub code: Option<String>,  // 👈 เช่น "C1", "X-2"
+    pub paint_type: Option<PaintType>,
+    pub finish: Option<PaintFinish>,
+    pub hex: Option<String>,      // 👈 "#rrggbb" ใช้จับคู่กับสี runner
+    pub quantity: i32,            // 👈 จำนวนขวดที่มี (รวมขวดที่เปิดอยู่)
+    pub fill_level: Option<i16>,  // 👈 % ที่เหลือในขวดที่เปิด (None = ยังเต็ม)
+    pub low_stock_threshold: i32, // 👈 % ของหนึ่งขวด: เหลือรวม ≤ ค่านี้ = low
+    pub stock_status: StockStatus,
+    pub catalog_id: Option<i64>, // 👈 เพิ่มมาจาก paint_catalog
+    pub user_id: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// --- Query: GET /paints?brand=&paint_type=&finish= ---
+#[derive(Debug, Deserialize)]
+pub struct PaintQuery {
+    pub brand: Option<String>, // 👈 ไม่สนตัวพิมพ์เล็ก/ใหญ่
+    pub paint_type: Option<PaintType>,
+    pub finish: Option<PaintFinish>,
+    pub stock_status: Option<StockStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePaintPayload {
+    pub name: String,
+    pub brand: Option<String>,
+    pub code: Option<String>,
+    pub paint_type: Option<PaintType>,
+    pub finish: Option<PaintFinish>,
+    pub hex: Option<String>,
+    pub quantity: Option<i32>,
+    pub fill_level: Option<i16>,
+    pub low_stock_threshold: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePaintPayload {
+    // Fields ที่อนุญาตให้อัปเดต (ไม่ส่ง = ไม่แก้)
+    pub name: Option<String>,
+    pub brand: Option<String>,
+    pub code: Option<String>,
+    pub paint_type: Option<PaintType>,
+    pub finish: Option<PaintFinish>,
+    pub hex: Option<String>,
+    pub quantity: Option<i32>,
+    pub fill_level: Option<i16>,
+    pub low_stock_threshold: Option<i32>,
+}
+
+// จำนวนขวดสูงสุด: stock_status คำนวณ (quantity - 1) * 100 เป็น INT ใน DB ค่าใหญ่กว่านี้มาก ๆ จะ overflow
+pub const MAX_PAINT_QUANTITY: i32 = 10_000;
+
+// ตรวจค่า inventory ที่ส่งมา (DB มี CHECK กันไว้อีกชั้น)
+pub fn validate_inventory(
+    quantity: Option<i32>,
+    fill_level: Option<i16>,
+    low_stock_threshold: Option<i32>,
+) -> Result<(), String> {
+    if quantity.is_some_and(|q| q < 0) {
+        return Err("quantity must not be negative".to_string());
+    }
+    if quantity.is_some_and(|q| q > MAX_PAINT_QUANTITY) {
+        return Err(format!("quantity must be at most {}", MAX_PAINT_QUANTITY));
+    }
+    if fill_level.is_some_and(|f| !(0..=100).contains(&f)) {
+        return Err("fill_level must be between 0 and 100".to_string());
+    }
+    if low_stock_threshold.is_some_and(|t| t < 0) {
+        return Err("low_stock_threshold must not be negative".to_string());
+    }
+    Ok(())
+}
+
+// --- Usage report: part / สูตรผสม ที่ใช้ paint นี้ (GET /paints/:id/usage, 409 ตอนลบ) ---
+#[derive(Debug, Serialize, Clone)]
+pub struct PaintUsagePart {
+    pub kit_part_id: i64,
+    pub code: Option<String>,
+    pub kit_id: i64,
+    pub kit_name: String,
+    pub roles: Vec<String>, // 👈 base / highlight / panel_line / topcoat
+    pub in_trash: bool,     // 👈 part/kit อยู่ในถังขยะ (ยังนับเพราะกู้คืนได้)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PaintUsageRecipe {
+    pub recipe_id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PaintUsage {
+    pub paint_id: i64,
+    pub kit_count: i64,
+    pub part_count: i64,
+    pub parts: Vec<PaintUsagePart>,
+    pub other_kit_count: i64, // 👈 kit ที่เราไม่ได้เป็นสมาชิกแล้ว (นับอย่างเดียว ไม่แสดงชื่อ)
+    pub other_part_count: i64, // 👈 part ใน kit เหล่านั้น
+    pub recipes: Vec<PaintUsageRecipe>,
+}
+
+impl PaintUsage {
+    pub fn is_used(&self) -> bool {
+        self.part_count + self.other_part_count > 0 || !self.recipes.is_empty()
+    }
+}
+
+// --- DELETE /paints/:id?reassign_to=:other ---
+#[derive(Debug, Deserialize)]
+pub struct DeletePaintQuery {
+    pub reassign_to: Option<i64>, // 👈 ย้าย part / สูตรผสมทั้งหมดไป paint นี้ก่อนลบ
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaintDeleteError {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<PaintUsage>,
+}
+
+// --- Shopping list: GET /kits/:id/shopping-list, GET /kits/shopping-list ---
+#[derive(Debug, Serialize, Clone)]
+pub struct ShoppingListKit {
+    pub kit_id: i64,
+    pub kit_name: String,
+    pub part_count: i64, // 👈 part ใน kit นี้ที่ใช้ paint นี้
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShoppingListItem {
+    pub paint_id: i64,
+    pub name: String,
+    pub brand: Option<String>,
+    pub code: Option<String>,
+    pub hex: Option<String>,
+    pub quantity: i32,
+    pub fill_level: Option<i16>,
+    pub low_stock_threshold: i32,
+    pub stock_status: StockStatus, // 👈 low / out เท่านั้น
+    pub kits: Vec<ShoppingListKit>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShoppingList {
+    pub kit_id: Option<i64>, // 👈 None = รวมทุก kit ที่ pending / in_progress
+    pub paint_count: usize,  // 👈 จำนวน paint ที่ตรวจทั้งหมด (รวมที่ยังพอ)
+    pub items: Vec<ShoppingListItem>,
+}
@@ -0,0 +1,284 @@
+// src/model/paint_catalog.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{
+    lab::normalize_hex,
+    paint::{PaintType, StockStatus},
+    palette::PaletteImportStatus,
+};
+
+// --- Main Model: CatalogPaint (สีสำเร็จรูปกลาง ไม่มีเจ้าของ) ---
+#[derive(Debug, Serialize, Clone)]
+pub struct CatalogPaint {
+    pub id: i64,
+    pub brand: String,
+    pub line: Option<String>,
+    pub code: String,
+    pub name: String,
+    pub hex: Option<String>,
+    pub paint_type: Option<PaintType>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// --- Query: GET /paint_catalog?brand=&line=&paint_type=&q= ---
+#[derive(Debug, Deserialize)]
+pub struct CatalogQuery {
+    pub brand: Option<String>,
+    pub line: Option<String>,
+    pub paint_type: Option<PaintType>,
+    pub q: Option<String>, // 👈 ค้นใน code / name
+}
+
+// --- Payload: POST /paint_catalog/:id/inventory ---
+#[derive(Debug, Deserialize, Default)]
+pub struct AddCatalogPaintPayload {
+    pub quantity: Option<i32>, // 👈 ไม่ส่ง = 1 ขวด
+}
+
+// --- Substitutes: GET /paint_catalog/:id/substitutes, GET /paints/:id/substitutes ---
+#[derive(Debug, Deserialize)]
+pub struct SubstituteQuery {
+    pub limit: Option<usize>,
+    pub max_distance: Option<f64>, // 👈 ΔE00 สูงสุดของสีที่ใกล้เคียง (ค่าเริ่มต้น 5)
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubstituteSource {
+    Mapping, // 👈 admin ระบุว่าเทียบเท่ากัน
+    Color,   // 👈 สีใกล้เคียงจาก hex (ยี่ห้ออื่น)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Substitute {
+    #[serde(flatten)]
+    pub paint: CatalogPaint,
+    pub source: SubstituteSource,
+    pub distance: Option<f64>,       // 👈 ΔE00 (None = ไม่มี hex ให้เทียบ)
+    pub owned_paint_id: Option<i64>, // 👈 มีใน inventory ของเราแล้ว
+    pub stock_status: Option<StockStatus>,
+}
+
+// --- Import (admin): POST /paint_catalog/import?format=&dry_run= (multipart field "file") ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogFormat {
+    Csv,
+    Json,
+}
+
+impl CatalogFormat {
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        match extension.to_lowercase().as_str() {
+            "csv" => Some(CatalogFormat::Csv),
+            "json" => Some(CatalogFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportCatalogQuery {
+    pub format: Option<CatalogFormat>, // 👈 ไม่ส่งมา = เดาจากนามสกุลไฟล์
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// สีหนึ่งรายการในไฟล์ (equivalents = ["Tamiya:XF-2", ...] อ้างถึงยี่ห้อ:รหัส)
+#[derive(Debug, Deserialize, Clone)]
+pub struct CatalogEntry {
+    pub brand: String,
+    #[serde(default)]
+    pub line: Option<String>,
+    pub code: String,
+    pub name: String,
+    #[serde(default)]
+    pub hex: Option<String>,
+    #[serde(default, alias = "type")]
+    pub paint_type: Option<String>,
+    #[serde(default)]
+    pub equivalents: Vec<String>,
+}
+
+impl CatalogEntry {
+    // trim + ตรวจค่า แล้วคืน paint_type ที่อ่านได้ (hex ถูก normalize ในตัว)
+    pub fn normalize(&mut self) -> Result<Option<PaintType>, Vec<String>> {
+        self.brand = self.brand.trim().to_string();
+        self.code = self.code.trim().to_string();
+        self.name = self.name.trim().to_string();
+        self.line = self
+            .line
+            .take()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty());
+
+        let mut errors = Vec::new();
+        for (field, value) in [
+            ("brand", &self.brand),
+            ("code", &self.code),
+            ("name", &self.name),
+        ] {
+            if value.is_empty() {
+                errors.push(format!("{} is required", field));
+            }
+        }
+        match self.hex.as_deref().map(str::trim) {
+            None | Some("") => self.hex = None,
+            Some(raw) => match normalize_hex(raw) {
+                Ok(hex) => self.hex = Some(hex),
+                Err(msg) => errors.push(msg),
+            },
+        }
+        let paint_type = match self.paint_type.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(raw) => {
+                let parsed = PaintType::parse(raw);
+                if parsed.is_none() {
+                    errors.push(format!(
+                        "Unknown paint type: {} (expected lacquer, acrylic, enamel or other)",
+                        raw
+                    ));
+                }
+                parsed
+            }
+        };
+
+        if errors.is_empty() {
+            Ok(paint_type)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+// (brand, code) แบบ lowercase ใช้จับคู่รายการ catalog (ตรงกับ unique index ใน DB)
+pub type CatalogKey = (String, String);
+
+pub fn catalog_key(brand: &str, code: &str) -> CatalogKey {
+    (brand.to_lowercase(), code.to_lowercase())
+}
+
+// "Brand:Code" → (brand, code)
+pub fn parse_equivalent_ref(raw: &str) -> Option<(String, String)> {
+    let (brand, code) = raw.rsplit_once(':')?;
+    let (brand, code) = (brand.trim(), code.trim());
+    (!brand.is_empty() && !code.is_empty()).then(|| (brand.to_string(), code.to_string()))
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatalogImportEntryResult {
+    pub row: u64, // 👈 บรรทัดใน csv หรือลำดับใน json เริ่มที่ 1
+    pub status: PaletteImportStatus,
+    pub brand: Option<String>,
+    pub code: Option<String>,
+    pub catalog_id: Option<i64>, // 👈 dry run: มีเฉพาะรายการที่มีอยู่แล้ว
+    pub errors: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>, // 👈 เช่น equivalents ที่หาไม่เจอ (ไม่ทำให้ทั้งแถว fail)
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatalogImportReport {
+    pub format: CatalogFormat,
+    pub dry_run: bool,
+    pub total: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub equivalents: usize, // 👈 คู่ที่อ้างถึงได้ (dry run นับรวมคู่ที่มีอยู่แล้ว)
+    pub entries: Vec<CatalogImportEntryResult>,
+}
+
+pub type ParsedCatalogEntry = (u64, Result<CatalogEntry, String>);
+
+pub fn parse_catalog(
+    format: CatalogFormat,
+    bytes: &[u8],
+) -> Result<Vec<ParsedCatalogEntry>, String> {
+    match format {
+        CatalogFormat::Csv => parse_csv(bytes),
+        CatalogFormat::Json => parse_json(bytes),
+    }
+}
+
+// คอลัมน์: brand, line, code, name, hex, type, equivalents (คั่นด้วย | หรือ ;)
+fn parse_csv(bytes: &[u8]) -> Result<Vec<ParsedCatalogEntry>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+    let brand_col = column(&["brand"]).ok_or("Missing CSV column: brand")?;
+    let code_col = column(&["code"]).ok_or("Missing CSV column: code")?;
+    let name_col = column(&["name"]).ok_or("Missing CSV column: name")?;
+    let line_col = column(&["line", "range", "series"]);
+    let hex_col = column(&["hex", "color", "colour"]);
+    let type_col = column(&["type", "paint_type"]);
+    let equivalents_col = column(&["equivalents", "equivalent"]);
+
+    let mut entries = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let fallback_line = index as u64 + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(fallback_line);
+                entries.push((line, Err(e.to_string())));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(fallback_line);
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+
+        let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).unwrap_or("");
+        let optional = |idx: Option<usize>| Some(field(idx).to_string()).filter(|v| !v.is_empty());
+        entries.push((
+            line,
+            Ok(CatalogEntry {
+                brand: field(Some(brand_col)).to_string(),
+                line: optional(line_col),
+                code: field(Some(code_col)).to_string(),
+                name: field(Some(name_col)).to_string(),
+                hex: optional(hex_col),
+                paint_type: optional(type_col),
+                equivalents: field(equivalents_col)
+                    .split(['|', ';'])
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            }),
+        ));
+    }
+    Ok(entries)
+}
+
+fn parse_json(bytes: &[u8]) -> Result<Vec<ParsedCatalogEntry>, String> {
+    let items: Vec<serde_json::Value> =
+        serde_json::from_slice(bytes).map_err(|e| format!("Invalid JSON catalog: {}", e))?;
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            (
+                index as u64 + 1,
+                serde_json::from_value::<CatalogEntry>(item).map_err(|e| e.to_string()),
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,80 @@
+// src/model/paint_recipe.rs
+
+use serde::{Deserialize, Serialize};
+
+// ส่วนผสมหนึ่งตัวในสูตร
+#[derive(Debug, Serialize, Clone)]
+pub struct RecipeComponent {
+    pub paint_id: i64,
+    pub name: String,
+    pub brand: Option<String>,
+    pub code: Option<String>,
+    pub hex: Option<String>, // 👈 ไม่มี hex = ไม่นับตอนประมาณสี
+    pub ratio: f64,
+    pub percent: f64, // 👈 สัดส่วนเทียบทั้งสูตร (ไม่รวมทินเนอร์)
+}
+
+// --- Main Model: PaintRecipe (สีผสมเอง) ---
+#[derive(Debug, Serialize, Clone)]
+pub struct PaintRecipe {
+    pub id: i64,
+    pub name: String,
+    pub notes: Option<String>,
+    pub thinner_ratio: Option<f64>, // 👈 ทินเนอร์กี่ส่วนต่อสี 1 ส่วน
+    pub hex: Option<String>,        // 👈 ประมาณจาก hex ของส่วนผสม
+    pub user_id: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub components: Vec<RecipeComponent>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RecipeComponentInput {
+    pub paint_id: i64,
+    pub ratio: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRecipePayload {
+    pub name: String,
+    pub notes: Option<String>,
+    pub thinner_ratio: Option<f64>,
+    pub components: Vec<RecipeComponentInput>,
+}
+
+// components ส่งมา = แทนที่ส่วนผสมทั้งชุด (เรียงตามลำดับที่ส่ง)
+#[derive(Debug, Deserialize)]
+pub struct UpdateRecipePayload {
+    pub name: Option<String>,
+    pub notes: Option<String>,
+    pub thinner_ratio: Option<f64>,
+    pub components: Option<Vec<RecipeComponentInput>>,
+}
+
+pub fn validate_components(components: &[RecipeComponentInput]) -> Result<(), String> {
+    if components.is_empty() {
+        return Err("components must contain at least one paint".to_string());
+    }
+    if let Some(c) = components
+        .iter()
+        .find(|c| !c.ratio.is_finite() || c.ratio <= 0.0)
+    {
+        return Err(format!(
+            "ratio for paint {} must be greater than 0",
+            c.paint_id
+        ));
+    }
+    let mut ids: Vec<i64> = components.iter().map(|c| c.paint_id).collect();
+    ids.sort_unstable();
+    if ids.windows(2).any(|w| w[0] == w[1]) {
+        return Err("each paint may appear only once in a recipe".to_string());
+    }
+    Ok(())
+}
+
+pub fn validate_thinner_ratio(thinner_ratio: Option<f64>) -> Result<(), String> {
+    if thinner_ratio.is_some_and(|t| !t.is_finite() || t < 0.0) {
+        return Err("thinner_ratio must not be negative".to_string());
+    }
+    Ok(())
+}
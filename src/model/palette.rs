@@ -0,0 +1,748 @@
+// src/model/palette.rs
+// import/export สีของ user เป็นไฟล์ palette: CSV, JSON, GIMP (.gpl), Adobe Swatch Exchange (.ase)
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{color::Color, lab::normalize_hex};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteFormat {
+    Csv,
+    Json,
+    Gpl,
+    Ase,
+}
+
+impl PaletteFormat {
+    // เดาจากนามสกุลไฟล์ที่อัปโหลด
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(PaletteFormat::Csv),
+            "json" => Some(PaletteFormat::Json),
+            "gpl" => Some(PaletteFormat::Gpl),
+            "ase" => Some(PaletteFormat::Ase),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PaletteFormat::Csv => "csv",
+            PaletteFormat::Json => "json",
+            PaletteFormat::Gpl => "gpl",
+            PaletteFormat::Ase => "ase",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            PaletteFormat::Csv => "text/csv; charset=utf-8",
+            PaletteFormat::Json => "application/json",
+            PaletteFormat::Gpl => "text/plain; charset=utf-8",
+            PaletteFormat::Ase => "application/octet-stream",
+        }
+    }
+}
+
+// --- Query: GET /colors/export?format= ---
+#[derive(Debug, Deserialize)]
+pub struct ExportPaletteQuery {
+    pub format: PaletteFormat,
+}
+
+// --- Query: POST /colors/import?format=&dry_run= (multipart field "file") ---
+#[derive(Debug, Deserialize)]
+pub struct ImportPaletteQuery {
+    pub format: Option<PaletteFormat>, // 👈 ไม่ส่งมา = เดาจากนามสกุลไฟล์
+    #[serde(default)]
+    pub dry_run: bool, // 👈 true = ตรวจอย่างเดียว ไม่บันทึก
+}
+
+// สีหนึ่งรายการในไฟล์ (is_clear/is_multi = None → ไม่มีคอลัมน์นี้ ใช้ค่าเดิม/false)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PaletteEntry {
+    pub name: String,
+    #[serde(default)]
+    pub code: String,
+    pub hex: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_clear: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_multi: Option<bool>,
+}
+
+// ผลการอ่านแต่ละรายการ: (บรรทัด/ลำดับ, entry หรือ error)
+pub type ParsedEntry = (u64, Result<PaletteEntry, String>);
+
+// --- Report ---
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteImportStatus {
+    Created,
+    Updated,
+    Unchanged,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaletteImportEntryResult {
+    pub row: u64, // 👈 บรรทัดในไฟล์ (csv/gpl) หรือลำดับรายการ (json/ase) เริ่มที่ 1
+    pub status: PaletteImportStatus,
+    pub name: Option<String>,
+    pub code: Option<String>,
+    pub color_id: Option<i64>, // 👈 dry run: มีเฉพาะสีที่มีอยู่แล้ว
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaletteImportReport {
+    pub format: PaletteFormat,
+    pub dry_run: bool,
+    pub total: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub entries: Vec<PaletteImportEntryResult>,
+}
+
+// --- ชื่อใน GPL/ASE มีช่องเดียว: เก็บเป็น "Name [CODE]" ---
+
+fn swatch_name(name: &str, code: &str) -> String {
+    if code.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} [{}]", name, code)
+    }
+}
+
+fn split_swatch_name(raw: &str) -> (String, String) {
+    let raw = raw.trim();
+    if let Some(rest) = raw.strip_suffix(']') {
+        if let Some((name, code)) = rest.rsplit_once(" [") {
+            if !name.trim().is_empty() {
+                return (name.trim().to_string(), code.trim().to_string());
+            }
+        }
+    }
+    (raw.to_string(), String::new())
+}
+
+fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+    let hex = normalize_hex(hex).ok()?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(1)?, channel(3)?, channel(5)?))
+}
+
+fn rgb_to_hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+// ค่า 0.0-1.0 จาก ASE → 0-255
+fn unit_to_byte(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+// true/false, yes/no, 1/0, y/n, x (ช่องติ๊กจาก spreadsheet) — ว่าง = ไม่ระบุ
+pub fn parse_flag(value: &str) -> Result<Option<bool>, String> {
+    match value.trim().to_lowercase().as_str() {
+        "" => Ok(None),
+        "true" | "yes" | "y" | "1" | "x" => Ok(Some(true)),
+        "false" | "no" | "n" | "0" => Ok(Some(false)),
+        other => Err(format!("Invalid boolean: {}", other)),
+    }
+}
+
+// ====================================================
+// EXPORT (รับสีที่เรียงแล้วตาม get_colors)
+// ====================================================
+
+pub fn export_palette(format: PaletteFormat, colors: &[Color]) -> Result<Vec<u8>, String> {
+    match format {
+        PaletteFormat::Csv => export_csv(colors),
+        PaletteFormat::Json => {
+            let entries: Vec<PaletteEntry> = colors
+                .iter()
+                .map(|c| PaletteEntry {
+                    name: c.name.clone(),
+                    code: c.code.clone(),
+                    hex: c.hex.clone(),
+                    is_clear: Some(c.is_clear),
+                    is_multi: Some(c.is_multi),
+                })
+                .collect();
+            serde_json::to_vec_pretty(&entries).map_err(|e| e.to_string())
+        }
+        PaletteFormat::Gpl => Ok(export_gpl(colors).into_bytes()),
+        PaletteFormat::Ase => Ok(export_ase(colors)),
+    }
+}
+
+fn export_csv(colors: &[Color]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["name", "code", "hex", "is_clear", "is_multi"])
+        .map_err(|e| e.to_string())?;
+    for c in colors {
+        writer
+            .write_record([
+                c.name.as_str(),
+                c.code.as_str(),
+                c.hex.as_str(),
+                if c.is_clear { "true" } else { "false" },
+                if c.is_multi { "true" } else { "false" },
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+fn export_gpl(colors: &[Color]) -> String {
+    let mut out = String::from("GIMP Palette\nName: Runner colors\nColumns: 0\n#\n");
+    for c in colors {
+        // hex ที่บันทึกไว้ normalize แล้ว (ถ้าอ่านไม่ได้ให้เป็นสีดำดีกว่าทำไฟล์พัง)
+        let (r, g, b) = hex_to_rgb(&c.hex).unwrap_or((0, 0, 0));
+        out.push_str(&format!(
+            "{:>3} {:>3} {:>3}\t{}\n",
+            r,
+            g,
+            b,
+            swatch_name(&c.name, &c.code)
+        ));
+    }
+    out
+}
+
+// ASE: big-endian ทั้งหมด, "ASEF" + version 1.0 + จำนวน block, สีละ 1 block (type 0x0001)
+fn export_ase(colors: &[Color]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(b"ASEF");
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&(colors.len() as u32).to_be_bytes());
+
+    for c in colors {
+        let (r, g, b) = hex_to_rgb(&c.hex).unwrap_or((0, 0, 0));
+        let name: Vec<u16> = swatch_name(&c.name, &c.code)
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let mut block = Vec::new();
+        block.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        for unit in &name {
+            block.extend_from_slice(&unit.to_be_bytes());
+        }
+        block.extend_from_slice(b"RGB ");
+        for value in [r, g, b] {
+            block.extend_from_slice(&(value as f32 / 255.0).to_be_bytes());
+        }
+        block.extend_from_slice(&2u16.to_be_bytes()); // 👈 color type: normal
+
+        out.extend_from_slice(&0x0001u16.to_be_bytes());
+        out.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        out.extend_from_slice(&block);
+    }
+    out
+}
+
+// ====================================================
+// IMPORT (error ของทั้งไฟล์ = Err, error รายรายการอยู่ใน ParsedEntry)
+// ====================================================
+
+pub fn parse_palette(format: PaletteFormat, bytes: &[u8]) -> Result<Vec<ParsedEntry>, String> {
+    match format {
+        PaletteFormat::Csv => parse_csv(bytes),
+        PaletteFormat::Json => parse_json(bytes),
+        PaletteFormat::Gpl => parse_gpl(bytes),
+        PaletteFormat::Ase => parse_ase(bytes),
+    }
+}
+
+fn parse_csv(bytes: &[u8]) -> Result<Vec<ParsedEntry>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+    let name_col = column(&["name"]).ok_or("Missing CSV column: name")?;
+    let hex_col = column(&["hex", "color", "colour"]).ok_or("Missing CSV column: hex")?;
+    let code_col = column(&["code"]);
+    let clear_col = column(&["is_clear", "clear"]);
+    let multi_col = column(&["is_multi", "multi"]);
+
+    let mut entries = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let fallback_line = index as u64 + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(fallback_line);
+                entries.push((line, Err(e.to_string())));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(fallback_line);
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+
+        let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).unwrap_or("");
+        let mut errors = Vec::new();
+        let is_clear = parse_flag(field(clear_col))
+            .map_err(|e| errors.push(format!("is_clear: {}", e)))
+            .ok()
+            .flatten();
+        let is_multi = parse_flag(field(multi_col))
+            .map_err(|e| errors.push(format!("is_multi: {}", e)))
+            .ok()
+            .flatten();
+        if !errors.is_empty() {
+            entries.push((line, Err(errors.join("; "))));
+            continue;
+        }
+
+        entries.push((
+            line,
+            Ok(PaletteEntry {
+                name: field(Some(name_col)).to_string(),
+                code: field(code_col).to_string(),
+                hex: field(Some(hex_col)).to_string(),
+                is_clear,
+                is_multi,
+            }),
+        ));
+    }
+    Ok(entries)
+}
+
+fn parse_json(bytes: &[u8]) -> Result<Vec<ParsedEntry>, String> {
+    let items: Vec<serde_json::Value> =
+        serde_json::from_slice(bytes).map_err(|e| format!("Invalid JSON palette: {}", e))?;
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            (
+                index as u64 + 1,
+                serde_json::from_value::<PaletteEntry>(item).map_err(|e| e.to_string()),
+            )
+        })
+        .collect())
+}
+
+// "R G B<ws>Name" ต่อบรรทัด, ข้าม header (Name:/Columns:) และ comment (#)
+fn parse_gpl(bytes: &[u8]) -> Result<Vec<ParsedEntry>, String> {
+    let text = std::str::from_utf8(bytes).map_err(|_| "GPL palette must be UTF-8".to_string())?;
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, first)) if first.trim_start_matches('\u{feff}').trim() == "GIMP Palette" => {}
+        _ => return Err("Not a GIMP palette (missing \"GIMP Palette\" header)".to_string()),
+    }
+
+    let mut entries = Vec::new();
+    for (index, line) in lines {
+        let line_no = index as u64 + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("Name:")
+            || trimmed.starts_with("Columns:")
+        {
+            continue;
+        }
+
+        // คอลัมน์ RGB จัดชิดขวาด้วยช่องว่างหลายตัว ("255   0   0") ชื่อคือส่วนที่เหลือทั้งหมด
+        let mut rest = trimmed;
+        let mut channels = Vec::with_capacity(3);
+        for _ in 0..3 {
+            rest = rest.trim_start();
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            match rest[..end].parse::<u8>() {
+                Ok(value) => channels.push(value),
+                Err(_) => break,
+            }
+            rest = &rest[end..];
+        }
+        if channels.len() != 3 {
+            entries.push((line_no, Err(format!("Invalid GPL color line: {}", trimmed))));
+            continue;
+        }
+
+        let hex = rgb_to_hex(channels[0], channels[1], channels[2]);
+        let raw_name = rest.trim();
+        // GIMP ใส่ "Untitled" ให้สีที่ไม่มีชื่อ → ใช้ hex เป็นชื่อแทน
+        let (name, code) = if raw_name.is_empty() || raw_name == "Untitled" {
+            (hex.clone(), String::new())
+        } else {
+            split_swatch_name(raw_name)
+        };
+        entries.push((
+            line_no,
+            Ok(PaletteEntry {
+                name,
+                code,
+                hex,
+                is_clear: None,
+                is_multi: None,
+            }),
+        ));
+    }
+    Ok(entries)
+}
+
+// อ่าน ASE: รองรับ RGB / Gray / CMYK (LAB ยังไม่รองรับ → error รายรายการ), group block ข้ามไป
+fn parse_ase(bytes: &[u8]) -> Result<Vec<ParsedEntry>, String> {
+    let mut reader = AseReader { bytes, pos: 0 };
+    if reader.take(4)? != b"ASEF" {
+        return Err("Not an ASE file (missing ASEF signature)".to_string());
+    }
+    let _version = (reader.u16()?, reader.u16()?);
+    let block_count = reader.u32()?;
+
+    let mut entries = Vec::new();
+    let mut color_index = 0u64;
+    for _ in 0..block_count {
+        let block_type = reader.u16()?;
+        let length = reader.u32()? as usize;
+        let block = reader.take(length)?;
+        if block_type != 0x0001 {
+            continue; // 👈 group start/end
+        }
+        color_index += 1;
+        entries.push((color_index, parse_ase_color(block)));
+    }
+    Ok(entries)
+}
+
+fn parse_ase_color(block: &[u8]) -> Result<PaletteEntry, String> {
+    let mut reader = AseReader {
+        bytes: block,
+        pos: 0,
+    };
+    let name_len = reader.u16()? as usize;
+    let mut units = Vec::with_capacity(name_len);
+    for _ in 0..name_len {
+        units.push(reader.u16()?);
+    }
+    while units.last() == Some(&0) {
+        units.pop();
+    }
+    let raw_name = String::from_utf16(&units).map_err(|_| "Invalid swatch name".to_string())?;
+
+    let model = reader.take(4)?;
+    let (r, g, b) = match model {
+        b"RGB " => {
+            let (r, g, b) = (reader.f32()?, reader.f32()?, reader.f32()?);
+            (unit_to_byte(r), unit_to_byte(g), unit_to_byte(b))
+        }
+        b"Gray" => {
+            let v = unit_to_byte(reader.f32()?);
+            (v, v, v)
+        }
+        b"CMYK" => {
+            let (c, m, y, k) = (reader.f32()?, reader.f32()?, reader.f32()?, reader.f32()?);
+            let channel = |v: f32| unit_to_byte((1.0 - v) * (1.0 - k));
+            (channel(c), channel(m), channel(y))
+        }
+        other => {
+            return Err(format!(
+                "Unsupported color model: {}",
+                String::from_utf8_lossy(other).trim()
+            ))
+        }
+    };
+
+    let hex = rgb_to_hex(r, g, b);
+    let (name, code) = if raw_name.trim().is_empty() {
+        (hex.clone(), String::new())
+    } else {
+        split_swatch_name(&raw_name)
+    };
+    Ok(PaletteEntry {
+        name,
+        code,
+        hex,
+        is_clear: None,
+        is_multi: None,
+    })
+}
+
+struct AseReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> AseReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("Unexpected end of ASE data")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        let b = self.take(4)?;
+        Ok(f32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(name: &str, code: &str, hex: &str, is_clear: bool) -> Color {
+        Color {
+            id: None,
+            name: name.to_string(),
+            code: code.to_string(),
+            hex: hex.to_string(),
+            is_clear,
+            is_multi: false,
+            user_id: Some(1),
+            is_preset: false,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn sample() -> Vec<Color> {
+        vec![
+            color("Gundam White", "C1", "#f5f5f0", false),
+            color("Clear Red", "", "#cc0000", true),
+            color("Ärmel, \"Blau\"", "X-2", "#1a2b3c", false),
+        ]
+    }
+
+    fn entries(format: PaletteFormat, bytes: &[u8]) -> Vec<(u64, PaletteEntry)> {
+        parse_palette(format, bytes)
+            .unwrap()
+            .into_iter()
+            .map(|(row, entry)| (row, entry.unwrap()))
+            .collect()
+    }
+
+    fn assert_round_trip(format: PaletteFormat, with_flags: bool) {
+        let colors = sample();
+        let bytes = export_palette(format, &colors).unwrap();
+        let parsed = entries(format, &bytes);
+        assert_eq!(parsed.len(), colors.len());
+        for ((_, entry), c) in parsed.iter().zip(&colors) {
+            assert_eq!(entry.name, c.name);
+            assert_eq!(entry.code, c.code);
+            assert_eq!(entry.hex, c.hex);
+            if with_flags {
+                assert_eq!(entry.is_clear, Some(c.is_clear));
+                assert_eq!(entry.is_multi, Some(c.is_multi));
+            } else {
+                assert_eq!(entry.is_clear, None);
+            }
+        }
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        assert_round_trip(PaletteFormat::Csv, true);
+        assert_round_trip(PaletteFormat::Json, true);
+        assert_round_trip(PaletteFormat::Gpl, false);
+        assert_round_trip(PaletteFormat::Ase, false);
+    }
+
+    #[test]
+    fn guesses_format_from_file_name() {
+        assert_eq!(
+            PaletteFormat::from_file_name("Colors.GPL"),
+            Some(PaletteFormat::Gpl)
+        );
+        assert_eq!(
+            PaletteFormat::from_file_name("a.b.ase"),
+            Some(PaletteFormat::Ase)
+        );
+        assert_eq!(PaletteFormat::from_file_name("palette"), None);
+        assert_eq!(PaletteFormat::from_file_name("palette.txt"), None);
+    }
+
+    #[test]
+    fn parses_flags() {
+        assert_eq!(parse_flag(" Yes ").unwrap(), Some(true));
+        assert_eq!(parse_flag("x").unwrap(), Some(true));
+        assert_eq!(parse_flag("0").unwrap(), Some(false));
+        assert_eq!(parse_flag("").unwrap(), None);
+        assert!(parse_flag("maybe").is_err());
+    }
+
+    #[test]
+    fn csv_accepts_header_aliases_and_reports_bad_rows() {
+        let csv = "Name, Colour, Clear\nRed,#f00,yes\n,,\nBlue,#00f,perhaps\n";
+        let parsed = parse_palette(PaletteFormat::Csv, csv.as_bytes()).unwrap();
+        assert_eq!(parsed.len(), 2);
+
+        let (row, red) = &parsed[0];
+        let red = red.as_ref().unwrap();
+        assert_eq!(*row, 2);
+        assert_eq!((red.name.as_str(), red.code.as_str()), ("Red", ""));
+        assert_eq!(red.hex, "#f00"); // 👈 normalize ตอนบันทึก ไม่ใช่ตอนอ่าน
+        assert_eq!((red.is_clear, red.is_multi), (Some(true), None));
+
+        let (row, blue) = &parsed[1];
+        assert_eq!(*row, 4);
+        assert!(blue.as_ref().unwrap_err().contains("is_clear"));
+    }
+
+    #[test]
+    fn csv_requires_name_and_hex_columns() {
+        let err = parse_palette(PaletteFormat::Csv, b"code,hex\nC1,#fff\n").unwrap_err();
+        assert!(err.contains("name"));
+        let err = parse_palette(PaletteFormat::Csv, b"name,code\nRed,C1\n").unwrap_err();
+        assert!(err.contains("hex"));
+    }
+
+    #[test]
+    fn json_reports_invalid_entries_per_item() {
+        let json = br##"[{"name":"Red","hex":"#f00"},{"name":"No hex"}]"##;
+        let parsed = parse_palette(PaletteFormat::Json, json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        let red = parsed[0].1.as_ref().unwrap();
+        assert_eq!((red.name.as_str(), red.code.as_str()), ("Red", ""));
+        assert_eq!(parsed[1].0, 2);
+        assert!(parsed[1].1.as_ref().unwrap_err().contains("hex"));
+
+        assert!(parse_palette(PaletteFormat::Json, b"{}").is_err());
+    }
+
+    #[test]
+    fn gpl_skips_headers_and_names_untitled_swatches() {
+        let gpl = "\u{feff}GIMP Palette\nName: Test\nColumns: 4\n# comment\n\n255   0   0\tRed [R1]\n  0 128 255\tUntitled\n 12  34\n0 0 0 Jet Black\n";
+        let parsed = parse_palette(PaletteFormat::Gpl, gpl.as_bytes()).unwrap();
+        assert_eq!(parsed.len(), 4);
+
+        let red = parsed[0].1.as_ref().unwrap();
+        assert_eq!(parsed[0].0, 6);
+        assert_eq!(
+            (red.name.as_str(), red.code.as_str(), red.hex.as_str()),
+            ("Red", "R1", "#ff0000")
+        );
+        let untitled = parsed[1].1.as_ref().unwrap();
+        assert_eq!(untitled.name, "#0080ff");
+        assert!(parsed[2].1.as_ref().unwrap_err().contains("Invalid GPL"));
+        let black = parsed[3].1.as_ref().unwrap();
+        assert_eq!(
+            (black.name.as_str(), black.code.as_str()),
+            ("Jet Black", "")
+        );
+    }
+
+    #[test]
+    fn gpl_requires_the_header() {
+        assert!(parse_palette(PaletteFormat::Gpl, b"255 0 0 Red\n").is_err());
+    }
+
+    // สร้าง ASE block สีหนึ่งสีด้วยมือ (ทดสอบ model ที่ export ไม่ได้สร้าง)
+    fn ase_color_block(name: &str, model: &[u8; 4], values: &[f32]) -> Vec<u8> {
+        let name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut block = Vec::new();
+        block.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        for unit in &name {
+            block.extend_from_slice(&unit.to_be_bytes());
+        }
+        block.extend_from_slice(model);
+        for value in values {
+            block.extend_from_slice(&value.to_be_bytes());
+        }
+        block.extend_from_slice(&2u16.to_be_bytes());
+
+        let mut out = 0x0001u16.to_be_bytes().to_vec();
+        out.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        out.extend_from_slice(&block);
+        out
+    }
+
+    fn ase_file(blocks: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = b"ASEF".to_vec();
+        out.extend_from_slice(&1u16.to_be_bytes());
+        out.extend_from_slice(&0u16.to_be_bytes());
+        out.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+        for block in blocks {
+            out.extend_from_slice(block);
+        }
+        out
+    }
+
+    #[test]
+    fn ase_reads_gray_and_cmyk_and_skips_groups() {
+        let group_start = {
+            let mut block = 0xc001u16.to_be_bytes().to_vec();
+            block.extend_from_slice(&4u32.to_be_bytes());
+            block.extend_from_slice(&[0, 1, 0, 0]);
+            block
+        };
+        let group_end = {
+            let mut block = 0xc002u16.to_be_bytes().to_vec();
+            block.extend_from_slice(&0u32.to_be_bytes());
+            block
+        };
+        let bytes = ase_file(&[
+            group_start,
+            ase_color_block("Mid Gray", b"Gray", &[0.5]),
+            ase_color_block("Cyan [C]", b"CMYK", &[1.0, 0.0, 0.0, 0.0]),
+            ase_color_block("", b"RGB ", &[0.0, 0.0, 1.0]),
+            ase_color_block("Lab", b"LAB ", &[50.0, 0.0, 0.0]),
+            group_end,
+        ]);
+
+        let parsed = parse_palette(PaletteFormat::Ase, &bytes).unwrap();
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(
+            parsed.iter().map(|(row, _)| *row).collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+
+        let gray = parsed[0].1.as_ref().unwrap();
+        assert_eq!(
+            (gray.name.as_str(), gray.hex.as_str()),
+            ("Mid Gray", "#808080")
+        );
+        let cyan = parsed[1].1.as_ref().unwrap();
+        assert_eq!(
+            (cyan.name.as_str(), cyan.code.as_str(), cyan.hex.as_str()),
+            ("Cyan", "C", "#00ffff")
+        );
+        let unnamed = parsed[2].1.as_ref().unwrap();
+        assert_eq!(unnamed.name, "#0000ff");
+        assert!(parsed[3].1.as_ref().unwrap_err().contains("LAB"));
+    }
+
+    #[test]
+    fn ase_rejects_bad_signature_and_truncated_data() {
+        assert!(parse_palette(PaletteFormat::Ase, b"ASEX\0\x01\0\0\0\0\0\0").is_err());
+
+        let bytes = export_palette(PaletteFormat::Ase, &sample()).unwrap();
+        let err = parse_palette(PaletteFormat::Ase, &bytes[..bytes.len() - 3]).unwrap_err();
+        assert!(err.contains("Unexpected end"));
+    }
+}
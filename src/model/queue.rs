@@ -0,0 +1,163 @@
+// src/model/queue.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::model::kit::{Kit, KitGrade, KitStatus};
+
+// --- Main Model: รายการในคิวต่อ (ต่อ user) ---
+#[derive(Debug, Serialize, Clone)]
+pub struct KitQueueEntry {
+    pub id: i64,
+    pub kit_id: i64,
+    pub position: i64, // 👈 ลำดับปัจจุบัน เริ่มที่ 1 (คำนวณตอนอ่าน ไม่ได้เก็บใน DB)
+    pub rank: String,  // 👈 fractional index ใช้เรียงลำดับ
+    pub pinned: bool,
+    pub name: String,
+    pub grade: KitGrade,
+    pub status: KitStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// --- Response: GET /kits/next ---
+#[derive(Debug, Serialize)]
+pub struct NextKit {
+    #[serde(flatten)]
+    pub kit: Kit,
+    pub pinned: bool,
+    pub runner_count: i64,
+    pub part_count: i64,
+}
+
+// --- Payload Structs ---
+
+// ใช้สำหรับเพิ่ม kit เข้าคิว (POST /queue) — เข้าท้ายคิว หรือบนสุดถ้า pinned
+#[derive(Debug, Deserialize)]
+pub struct AddQueueEntryPayload {
+    pub kit_id: i64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+// ใช้สำหรับลากย้าย (PATCH /queue/:kit_id/move) — position เริ่มที่ 1
+#[derive(Debug, Deserialize)]
+pub struct MoveQueueEntryPayload {
+    pub position: i64,
+}
+
+// --- Fractional ranking ---
+// rank เป็น string base-62 ที่เรียงตาม byte order ได้เสมอ และไม่มีตัวท้ายเป็น '0'
+// จึงหา rank ที่อยู่ระหว่างสองค่าได้เสมอโดยไม่ต้องเลื่อนรายการอื่น
+
+const RANK_DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+fn digit_index(c: u8) -> usize {
+    RANK_DIGITS.iter().position(|d| *d == c).unwrap_or(0)
+}
+
+// a < b เสมอ, a = "" แทนจุดเริ่ม, b = None แทนจุดสิ้นสุด
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> Vec<u8> {
+    let zero = RANK_DIGITS[0];
+
+    if let Some(b) = b {
+        // ตัด prefix ที่เหมือนกันออกก่อน (a ที่สั้นกว่าเติม '0' ทางขวา)
+        let mut n = 0;
+        while n < b.len() && a.get(n).copied().unwrap_or(zero) == b[n] {
+            n += 1;
+        }
+        if n > 0 {
+            let mut out = b[..n].to_vec();
+            out.extend(midpoint(a.get(n..).unwrap_or(&[]), Some(&b[n..])));
+            return out;
+        }
+    }
+
+    let digit_a = a.first().map(|c| digit_index(*c)).unwrap_or(0);
+    let digit_b = b
+        .and_then(|b| b.first())
+        .map(|c| digit_index(*c))
+        .unwrap_or(RANK_DIGITS.len());
+
+    if digit_b.saturating_sub(digit_a) > 1 {
+        let mid = (digit_a + digit_b).div_ceil(2);
+        vec![RANK_DIGITS[mid]]
+    } else if let Some(b) = b.filter(|b| b.len() > 1) {
+        vec![b[0]]
+    } else {
+        let mut out = vec![RANK_DIGITS[digit_a]];
+        out.extend(midpoint(a.get(1..).unwrap_or(&[]), None));
+        out
+    }
+}
+
+// คืน rank ที่อยู่ระหว่าง prev กับ next (None = ไม่มีเพื่อนบ้านด้านนั้น)
+pub fn rank_between(prev: Option<&str>, next: Option<&str>) -> String {
+    let a = prev.unwrap_or("").as_bytes();
+    let b = next.map(str::as_bytes);
+    String::from_utf8(midpoint(a, b)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // rank ใหม่ต้องอยู่ระหว่างเพื่อนบ้านเสมอ และไม่ลงท้ายด้วย '0'
+    fn assert_between(prev: Option<&str>, next: Option<&str>) -> String {
+        let rank = rank_between(prev, next);
+        assert!(!rank.is_empty());
+        assert!(!rank.ends_with('0'), "{rank} ends with '0'");
+        if let Some(prev) = prev {
+            assert!(prev < rank.as_str(), "{prev} < {rank}");
+        }
+        if let Some(next) = next {
+            assert!(rank.as_str() < next, "{rank} < {next}");
+        }
+        rank
+    }
+
+    #[test]
+    fn first_rank_is_the_middle_digit() {
+        assert_eq!(rank_between(None, None), "V");
+    }
+
+    #[test]
+    fn ranks_between_neighbours() {
+        assert_eq!(assert_between(Some("A"), Some("C")), "B");
+        assert_eq!(assert_between(Some("A"), Some("B")), "AV");
+        assert_eq!(assert_between(Some("A"), Some("A1")), "A0V");
+        assert_between(Some("z"), None);
+        assert_between(None, Some("1"));
+        assert_between(Some("Az"), Some("B"));
+        assert_between(Some("A"), Some("B01"));
+    }
+
+    #[test]
+    fn appends_and_prepends_stay_ordered() {
+        let mut ranks = vec![rank_between(None, None)];
+        for _ in 0..200 {
+            let last = ranks.last().cloned();
+            ranks.push(assert_between(last.as_deref(), None));
+            let first = ranks.first().cloned();
+            ranks.insert(0, assert_between(None, first.as_deref()));
+        }
+        assert!(ranks.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn repeated_inserts_between_the_same_neighbours_stay_ordered() {
+        // แทรกชิดขอบล่างซ้ำ ๆ (rank ยาวขึ้นเรื่อย ๆ แต่ยังเรียงถูก)
+        let low = rank_between(None, None);
+        let mut next = rank_between(Some(&low), None);
+        for _ in 0..200 {
+            next = assert_between(Some(&low), Some(&next));
+        }
+
+        // แทรกชิดขอบบนซ้ำ ๆ
+        let high = rank_between(Some(&low), None);
+        let mut prev = low;
+        for _ in 0..200 {
+            prev = assert_between(Some(&prev), Some(&high));
+        }
+    }
+}
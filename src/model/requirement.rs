@@ -93,6 +93,7 @@ pub struct KitPartRequirementWithRunner {
     pub runner_id: i64,
     pub kit_part_id: i64,
     pub user_id: i64,
+    pub updated_by: Option<i64>,
     pub runner: Runner,
 }
 
@@ -105,5 +106,6 @@ pub struct KitPartRequirementWithRunnerColor {
     pub runner_id: i64,
     pub kit_part_id: i64,
     pub user_id: i64,
+    pub updated_by: Option<i64>,
     pub runner: RunnerWithColor,
 }
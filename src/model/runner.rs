@@ -4,7 +4,11 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-use crate::model::color::RunnerColor;
+use crate::model::{
+    color::RunnerColor,
+    gate::{full_gate_name, parse_gate, Gate},
+    runner_color::RunnerGateColor,
+};
 
 // --- Main Model: Runner ---
 #[derive(Debug, Serialize, Clone, FromRow)]
@@ -16,6 +20,7 @@ pub struct Runner {
     pub amount: i32,
     pub user_id: i64,
     pub is_used: bool,
+    pub updated_by: Option<i64>, // 👈 ผู้แก้ไขล่าสุด
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -55,5 +60,174 @@ pub struct RunnerWithColor {
     pub is_used: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
-    pub color: RunnerColor, // 👈 nested color object
+    pub color: RunnerColor,                // 👈 nested color object (สีหลัก)
+    pub colors: Vec<RunnerColor>,          // 👈 สีทั้งหมดเรียงตามลำดับ ตัวแรก = สีหลัก
+    pub gate_colors: Vec<RunnerGateColor>, // 👈 สีเฉพาะ gate
+    pub remaining_gates: Vec<String>,      // 👈 gate ที่ requirement ยังไม่ได้ตัด (ทุก kit part)
+}
+
+// --- Gate inventory (GET/PUT /runners/:id/gates) ---
+// runner กำหนดได้สองแบบ: gate_count (มี gate 1..N) หรือ gates (เก็บแบบไม่มี prefix เช่น "1", "2", "5a")
+#[derive(Debug, Serialize, Clone)]
+pub struct RunnerGates {
+    pub runner_id: i64,
+    pub runner_name: String,
+    pub gate_count: Option<i32>,
+    pub gates: Option<Vec<String>>,
+    pub available_gates: Vec<String>, // 👈 ชื่อเต็มทั้งหมด เช่น ["A1", "A2", ...] (ว่าง = ยังไม่กำหนด)
+}
+
+impl RunnerGates {
+    pub fn new(
+        runner_id: i64,
+        runner_name: String,
+        gate_count: Option<i32>,
+        gates: Option<Vec<String>>,
+    ) -> Self {
+        let available_gates = match (&gates, gate_count) {
+            (Some(gates), _) => gates
+                .iter()
+                .map(|g| full_gate_name(&runner_name, g))
+                .collect(),
+            (None, Some(count)) => (1..=count)
+                .map(|n| full_gate_name(&runner_name, n))
+                .collect(),
+            (None, None) => Vec::new(),
+        };
+        RunnerGates {
+            runner_id,
+            runner_name,
+            gate_count,
+            gates,
+            available_gates,
+        }
+    }
+
+    // ยังไม่ได้กำหนด inventory = ไม่ตรวจ gate ของ runner นี้
+    pub fn is_defined(&self) -> bool {
+        self.gate_count.is_some() || self.gates.is_some()
+    }
+
+    // gate ทั้งหมดที่ runner มี (ว่าง = ยังไม่กำหนด)
+    pub fn gate_list(&self) -> Vec<Gate> {
+        match (&self.gates, self.gate_count) {
+            (Some(gates), _) => gates
+                .iter()
+                .filter_map(|g| parse_gate(g, None).ok().map(|p| p.gate))
+                .collect(),
+            (None, Some(count)) => (1..=count as u32)
+                .map(|number| Gate {
+                    number,
+                    suffix: None,
+                })
+                .collect(),
+            (None, None) => Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, gate: &Gate) -> bool {
+        match (&self.gates, self.gate_count) {
+            (Some(gates), _) => gates
+                .iter()
+                .any(|g| g.eq_ignore_ascii_case(&gate.to_string())),
+            (None, Some(count)) => gate.suffix.is_none() && gate.number <= count as u32,
+            (None, None) => true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetRunnerGatesPayload {
+    pub gate_count: Option<i32>,
+    pub gates: Option<Vec<String>>, // 👈 ใช้ gate notation ได้ เช่น ["1-20", "21a", "21b"] (ส่งทั้งคู่เป็น null = ล้าง)
+}
+
+pub const MAX_RUNNER_GATES: i32 = 999;
+
+// --- Payload for Bulk Creating Runners (POST /runners/bulk) ---
+// ใช้ spec ("A-H", "B1-B4", "A, C, E1-E3") หรือ items แบบระบุทีละตัว หรือทั้งสองอย่าง
+#[derive(Debug, Deserialize)]
+pub struct BulkCreateRunnersPayload {
+    pub kit_id: i64,
+    pub spec: Option<String>,
+    pub color_id: Option<i64>, // 👈 ค่า default สำหรับ runner ที่ไม่ได้ระบุสีเอง
+    pub amount: Option<i32>,   // 👈 ค่า default (ไม่ส่ง = 1)
+    #[serde(default)]
+    pub items: Vec<BulkRunnerItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkRunnerItem {
+    pub name: String,
+    pub color_id: Option<i64>,
+    pub amount: Option<i32>,
+}
+
+// จำนวน runner สูงสุดที่ spec หนึ่งชุดขยายได้
+pub const MAX_BULK_RUNNERS: usize = 200;
+
+// ขยาย spec เป็นรายชื่อ runner ตามลำดับ
+// - "A-H"   => A, B, ..., H
+// - "B1-B4" => B1, B2, B3, B4 (หรือ "B1-4")
+// - คั่นหลายชุดด้วย ',' เช่น "A, B1-B4, C"
+pub fn expand_runner_spec(spec: &str) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                names.extend(expand_runner_range(start.trim(), end.trim())?);
+            }
+            None => names.push(part.to_string()),
+        }
+        if names.len() > MAX_BULK_RUNNERS {
+            return Err(format!(
+                "Spec expands to more than {} runners",
+                MAX_BULK_RUNNERS
+            ));
+        }
+    }
+
+    if names.is_empty() {
+        return Err("Spec is empty".to_string());
+    }
+    Ok(names)
+}
+
+fn split_numeric_suffix(value: &str) -> (&str, Option<u32>) {
+    let idx = value
+        .char_indices()
+        .find(|(_, c)| c.is_ascii_digit())
+        .map(|(i, _)| i)
+        .unwrap_or(value.len());
+    let (prefix, digits) = value.split_at(idx);
+    (prefix, digits.parse().ok())
+}
+
+fn expand_runner_range(start: &str, end: &str) -> Result<Vec<String>, String> {
+    let invalid = || format!("Invalid runner range: {}-{}", start, end);
+
+    // ตัวอักษรเดี่ยว: A-H
+    let single_letter = |s: &str| s.len() == 1 && s.chars().all(|c| c.is_ascii_alphabetic());
+    if single_letter(start) && single_letter(end) {
+        let (from, to) = (start.as_bytes()[0], end.as_bytes()[0]);
+        if from > to || from.is_ascii_uppercase() != to.is_ascii_uppercase() {
+            return Err(invalid());
+        }
+        return Ok((from..=to).map(|c| (c as char).to_string()).collect());
+    }
+
+    // prefix + เลข: B1-B4 หรือ B1-4
+    let (prefix, from) = split_numeric_suffix(start);
+    let (end_prefix, to) = split_numeric_suffix(end);
+    let (Some(from), Some(to)) = (from, to) else {
+        return Err(invalid());
+    };
+    if (!end_prefix.is_empty() && end_prefix != prefix) || from > to {
+        return Err(invalid());
+    }
+    if (to - from) as usize >= MAX_BULK_RUNNERS {
+        return Err(invalid());
+    }
+    Ok((from..=to).map(|n| format!("{}{}", prefix, n)).collect())
 }
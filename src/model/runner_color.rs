@@ -0,0 +1,29 @@
+// src/model/runner_color.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::color::RunnerColor;
+
+// สีเฉพาะ gate (เช่น runner PC ที่ gate 5-8 เป็นสีเทา)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunnerGateColor {
+    pub gate: String, // 👈 ชื่อเต็ม เช่น "A12"
+    pub color_id: i64,
+}
+
+// --- Response: GET/PUT /runners/:id/colors ---
+#[derive(Debug, Serialize)]
+pub struct RunnerColorSet {
+    pub runner_id: i64,
+    pub runner_name: String,
+    pub colors: Vec<RunnerColor>, // 👈 เรียงตามลำดับ ตัวแรก = สีหลัก (runners.color_id)
+    pub gate_colors: Vec<RunnerGateColor>,
+}
+
+// --- Payload: PUT /runners/:id/colors (แทนที่ทั้งชุด) ---
+#[derive(Debug, Deserialize)]
+pub struct SetRunnerColorsPayload {
+    pub color_ids: Vec<i64>,
+    #[serde(default)]
+    pub gate_colors: Vec<RunnerGateColor>, // 👈 gate รับได้ทั้ง "A12" และ "12"
+}
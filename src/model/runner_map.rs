@@ -0,0 +1,81 @@
+// src/model/runner_map.rs
+
+use serde::{Deserialize, Serialize};
+
+// รูปที่รับอัปโหลดได้ และขนาดสูงสุด
+pub const MAX_RUNNER_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+// ตรวจชนิดไฟล์จาก magic bytes (ไม่เชื่อ content-type ที่ client ส่งมา) แล้วคืนนามสกุล
+pub fn detect_image_extension(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        None
+    }
+}
+
+// content-type ตอนส่งรูปกลับ (อิงนามสกุลที่ detect_image_extension ตั้งไว้)
+pub fn image_content_type(path: &str) -> &'static str {
+    match path.rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("jpg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+// --- Hotspot ---
+// ตำแหน่ง gate บนรูป เป็นสัดส่วนของขนาดรูป (0.0 - 1.0)
+#[derive(Debug, Clone)]
+pub struct RunnerGateHotspot {
+    pub gate: String, // 👈 ไม่มี prefix เช่น "12", "3a"
+    pub x: f64,
+    pub y: f64,
+    pub polygon: Option<Vec<[f64; 2]>>,
+}
+
+// สถานะการตัดของ gate จาก kit_part_requirements
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HotspotCutState {
+    Cut,    // 👈 ทุก requirement ที่ใช้ gate นี้ตัดแล้ว
+    Uncut,  // 👈 ยังมี requirement ที่ไม่ได้ตัด
+    Unused, // 👈 ไม่มี requirement ใช้ (spare)
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunnerMapHotspot {
+    pub gate: String, // 👈 ชื่อเต็ม เช่น "A12"
+    pub x: f64,
+    pub y: f64,
+    pub polygon: Option<Vec<[f64; 2]>>,
+    pub state: HotspotCutState,
+    pub requirement_count: usize,
+}
+
+// --- Response: GET /runners/:id/map ---
+#[derive(Debug, Serialize)]
+pub struct RunnerMap {
+    pub runner_id: i64,
+    pub runner_name: String,
+    pub image_url: Option<String>,
+    pub hotspots: Vec<RunnerMapHotspot>,
+}
+
+// --- Payload: PUT /runners/:id/hotspots (แทนที่ทั้งชุด) ---
+#[derive(Debug, Deserialize)]
+pub struct SetHotspotsPayload {
+    pub hotspots: Vec<HotspotItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HotspotItem {
+    pub gate: String, // 👈 "A12" หรือ "12"
+    pub x: f64,
+    pub y: f64,
+    pub polygon: Option<Vec<[f64; 2]>>,
+}
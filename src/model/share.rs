@@ -0,0 +1,77 @@
+// src/model/share.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::model::kit::{KitGrade, KitStatus};
+
+// --- Main Model: KitShareLink ---
+#[derive(Debug, Serialize, Clone)]
+pub struct KitShareLink {
+    pub id: i64,
+    pub token: String,
+    pub kit_id: i64,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+// ใช้สำหรับสร้าง share link ใหม่ (POST /kits/:id/share_links)
+// ไม่ส่ง expires_in_hours = ลิงก์ไม่มีวันหมดอายุ (จนกว่าจะ revoke)
+#[derive(Debug, Deserialize)]
+pub struct CreateShareLinkPayload {
+    pub expires_in_hours: Option<i64>,
+}
+
+// อายุลิงก์สูงสุด 10 ปี (ค่าที่ใหญ่กว่านี้ทำให้ timestamp ใน DB ล้น)
+pub const MAX_SHARE_LINK_HOURS: i64 = 24 * 365 * 10;
+
+// --- Public Response ---
+// 🚨 ห้ามมี user_id หรือ id ภายในใดๆ: ใช้ชื่อ runner อ้างอิงแทน runner_id
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublicKit {
+    pub name: String,
+    pub grade: KitGrade,
+    pub status: KitStatus,
+    pub runners: Vec<PublicRunner>,
+    pub sub_assemblies: Vec<PublicSubAssembly>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublicColor {
+    pub name: String,
+    pub code: String,
+    pub hex: String,
+    pub is_clear: bool,
+    pub is_multi: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublicRunner {
+    pub name: String,
+    pub amount: i32,
+    pub is_used: bool,
+    pub color: PublicColor,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublicSubAssembly {
+    pub name: String,
+    pub parts: Vec<PublicKitPart>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublicKitPart {
+    pub code: Option<String>,
+    pub is_cut: bool,
+    pub requirements: Vec<PublicRequirement>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublicRequirement {
+    pub runner_name: String,
+    pub gate: Vec<String>,
+    pub qty: i64,
+    pub is_cut: bool,
+}
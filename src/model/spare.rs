@@ -0,0 +1,51 @@
+// src/model/spare.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{color::RunnerColor, kit::KitGrade};
+
+// --- Main Model: Spare part ---
+// gate ที่ runner มี (ตาม gate inventory) แต่ไม่มี requirement ไหนใช้ = ชิ้นส่วนเหลือ
+#[derive(Debug, Serialize, Clone)]
+pub struct SparePart {
+    pub runner_id: i64,
+    pub runner_name: String,
+    pub runner_amount: i32, // 👈 จำนวนแผงของ runner นี้ (มีหลายแผง = gate เหลือหลายชิ้น)
+    pub gate: String,       // 👈 ชื่อเต็ม เช่น "A12"
+    pub kit_id: i64,
+    pub kit_name: String,
+    pub kit_grade: KitGrade,
+    pub color: RunnerColor,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub updated_at: Option<NaiveDateTime>, // 👈 เวลาที่แก้ tag/note ล่าสุด (ยังไม่เคยแก้ = null)
+}
+
+// ข้อมูลที่ user เขียนไว้ของ spare หนึ่งชิ้น (ตาราง spare_parts)
+#[derive(Debug, Clone)]
+pub struct SpareNote {
+    pub runner_id: i64,
+    pub gate: String, // 👈 ไม่มี prefix เช่น "12", "3a"
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+// --- Query: GET /spares?grade=hg&color=gray&tag=armor ---
+#[derive(Debug, Deserialize)]
+pub struct SpareQuery {
+    pub kit_id: Option<i64>,
+    pub grade: Option<String>,
+    pub color_id: Option<i64>,
+    pub color: Option<String>, // 👈 ค้นจากชื่อหรือ code ของสี (ไม่สนตัวพิมพ์เล็ก/ใหญ่)
+    pub tag: Option<String>,
+}
+
+// --- Payload: PUT /spares/:runner_id/:gate ---
+#[derive(Debug, Deserialize)]
+pub struct UpdateSparePayload {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
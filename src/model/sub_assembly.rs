@@ -11,6 +11,7 @@ pub struct SubAssembly {
     pub name: String,
     pub kit_id: i64,
     pub user_id: i64,
+    pub updated_by: Option<i64>,   // 👈 ผู้แก้ไขล่าสุด
     pub created_at: NaiveDateTime, // 👈 เพิ่ม Type
     pub updated_at: NaiveDateTime, // 👈 เพิ่ม Type
 }
@@ -0,0 +1,53 @@
+// src/model/trash.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+// ชนิดของรายการที่อยู่ในถังขยะ (ใช้ทั้งใน JSON และใน Path เช่น /trash/kit_part/:id)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashItemType {
+    Kit,
+    Runner,
+    SubAssembly,
+    KitPart,
+}
+
+impl TrashItemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrashItemType::Kit => "kit",
+            TrashItemType::Runner => "runner",
+            TrashItemType::SubAssembly => "sub_assembly",
+            TrashItemType::KitPart => "kit_part",
+        }
+    }
+}
+
+impl FromStr for TrashItemType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kit" => Ok(TrashItemType::Kit),
+            "runner" => Ok(TrashItemType::Runner),
+            "sub_assembly" => Ok(TrashItemType::SubAssembly),
+            "kit_part" => Ok(TrashItemType::KitPart),
+            _ => Err(format!("Invalid trash item type: {}", s)),
+        }
+    }
+}
+
+// รายการในถังขยะ: แสดงเฉพาะรายการที่ผู้ใช้ลบเอง
+// (ลูกที่ถูกลบพร้อม parent จะไม่แสดงแยก เพราะ restore parent จะคืนมาด้วย)
+#[derive(Debug, Serialize, Clone)]
+pub struct TrashItem {
+    pub item_type: TrashItemType,
+    pub id: i64,
+    pub name: String,
+    pub kit_id: i64,
+    pub kit_name: String,
+    pub deleted_at: NaiveDateTime,
+    pub purge_at: NaiveDateTime, // 👈 เวลาที่ background task จะลบถาวร
+}
@@ -0,0 +1,112 @@
+// src/model/workflow.rs
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// ขั้นตอนเริ่มต้นของ user ที่ยังไม่เคยตั้งค่า (ต่อจาก is_cut)
+pub const DEFAULT_STAGES: [&str; 6] = [
+    "Cut",
+    "Nub sanded",
+    "Panel lined",
+    "Painted",
+    "Decaled",
+    "Top coated",
+];
+
+// --- Main Model: WorkflowStage ---
+#[derive(Debug, Serialize, Clone)]
+pub struct WorkflowStage {
+    pub id: i64,
+    pub name: String,
+    pub position: i32, // 👈 0 = ขั้นแรก
+    pub user_id: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStagePayload {
+    pub name: String,
+    pub position: Option<i32>, // 👈 ไม่ส่ง = ต่อท้าย
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateStagePayload {
+    pub name: Option<String>,
+    pub position: Option<i32>,
+}
+
+// --- Payload: PUT /workflow_stages/order (ต้องส่ง stage ครบทุกตัว) ---
+#[derive(Debug, Deserialize)]
+pub struct ReorderStagesPayload {
+    pub stage_ids: Vec<i64>,
+}
+
+// ขั้นปัจจุบันของ kit part (ใส่ใน KitPartWithSubAssemblyAndRequirements ด้วย)
+#[derive(Debug, Serialize, Clone)]
+pub struct KitPartStage {
+    pub stage_id: i64,
+    pub name: String,
+    pub position: i32,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+// --- Payload: PATCH /kit_parts/:id/stage (stage_id = null → ยังไม่เริ่ม) ---
+#[derive(Debug, Deserialize)]
+pub struct SetStagePayload {
+    pub stage_id: Option<i64>,
+}
+
+// --- Payload: PATCH /sub_assemblies/:id/stage ---
+#[derive(Debug, Deserialize)]
+pub struct BulkSetStagePayload {
+    pub stage_id: Option<i64>,
+    #[serde(default)]
+    pub advance_only: bool, // 👈 true = ไม่ถอย part ที่ไปไกลกว่าแล้ว
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkStageResult {
+    pub sub_assembly_id: i64,
+    pub stage_id: Option<i64>,
+    pub updated: u64, // 👈 part ที่เปลี่ยนขั้นจริง
+}
+
+// --- Response: GET /kit_parts/:id/stage_history ---
+#[derive(Debug, Serialize, Clone)]
+pub struct StageTransition {
+    pub id: i64,
+    pub kit_part_id: i64,
+    pub from_stage_id: Option<i64>,
+    pub from_stage_name: Option<String>,
+    pub to_stage_id: Option<i64>,
+    pub to_stage_name: Option<String>,
+    pub changed_by: Option<i64>,
+    pub changed_at: NaiveDateTime,
+}
+
+// --- Response: GET /kits/:id/stage_progress?sub_assembly_id= ---
+#[derive(Debug, Deserialize)]
+pub struct StageProgressQuery {
+    pub sub_assembly_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StageProgress {
+    pub stage_id: i64,
+    pub name: String,
+    pub position: i32,
+    pub current_count: i64, // 👈 part ที่อยู่ขั้นนี้ตอนนี้
+    pub reached_count: i64, // 👈 part ที่ถึงขั้นนี้แล้ว (ขั้นนี้หรือเลยไปแล้ว)
+    pub percent: f64,       // 👈 reached_count / total_parts
+}
+
+#[derive(Debug, Serialize)]
+pub struct KitStageProgress {
+    pub kit_id: i64,
+    pub sub_assembly_id: Option<i64>,
+    pub total_parts: i64,
+    pub unstaged_count: i64,    // 👈 ยังไม่เริ่มขั้นไหนเลย
+    pub other_stage_count: i64, // 👈 อยู่ที่ stage ของคนอื่น (เช่น หลังโอน kit) — ไม่อยู่ใน stages
+    pub stages: Vec<StageProgress>,
+}
@@ -1,6 +1,12 @@
-use crate::model::color::{Color, UpdateColorPayload};
-use sqlx::{Error, PgPool};
+use crate::model::{
+    color::{Color, ColorMatch, ColorMatchKind, ColorUsage, ColorUsageRunner, UpdateColorPayload},
+    lab::Lab,
+    palette::PaletteEntry,
+};
+use crate::repository::reference::{ensure_colors_for_kit, ReferenceError};
+use sqlx::{Error, PgConnection, PgExecutor, PgPool};
 
+// สีส่วนตัว + preset กลาง (สีส่วนตัวขึ้นก่อน)
 pub async fn get_colors(pool: &PgPool, user_id: i64) -> Result<Vec<Color>, Error> {
     sqlx::query_as!(
         Color,
@@ -13,11 +19,12 @@ pub async fn get_colors(pool: &PgPool, user_id: i64) -> Result<Vec<Color>, Error
             is_clear,
             is_multi,
             user_id,
+            user_id IS NULL as "is_preset!: bool",
             (created_at AT TIME ZONE 'UTC') as "created_at?: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at?: chrono::NaiveDateTime"
         FROM colors
-        WHERE user_id = $1
-        ORDER BY is_multi DESC, is_clear DESC, name
+        WHERE user_id = $1 OR user_id IS NULL
+        ORDER BY user_id IS NULL, is_multi DESC, is_clear DESC, name
         "#,
         user_id
     )
@@ -25,7 +32,10 @@ pub async fn get_colors(pool: &PgPool, user_id: i64) -> Result<Vec<Color>, Error
     .await
 }
 
-pub async fn get_color_by_id(pool: &PgPool, color_id: i64) -> Result<Color, Error> {
+pub async fn get_color_by_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    color_id: i64,
+) -> Result<Color, Error> {
     sqlx::query_as!(
         Color,
         r#"
@@ -37,6 +47,7 @@ pub async fn get_color_by_id(pool: &PgPool, color_id: i64) -> Result<Color, Erro
             is_clear,
             is_multi,
             user_id,
+            user_id IS NULL as "is_preset!: bool",
             (created_at AT TIME ZONE 'UTC') as "created_at?: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at?: chrono::NaiveDateTime"
         FROM colors
@@ -44,16 +55,18 @@ pub async fn get_color_by_id(pool: &PgPool, color_id: i64) -> Result<Color, Erro
         "#,
         color_id
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await
 }
 
-pub async fn create_color(pool: &PgPool, color: Color) -> Result<Color, Error> {
+// lab คำนวณจาก hex ที่ normalize แล้ว (handler เป็นคนตรวจ)
+// color.user_id = None → สร้างเป็น preset กลาง
+pub async fn create_color(pool: &PgPool, color: Color, lab: Lab) -> Result<Color, Error> {
     sqlx::query_as!(
         Color,
         r#"
-        INSERT INTO colors (name, code, hex, is_clear, is_multi, user_id)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO colors (name, code, hex, is_clear, is_multi, user_id, lab_l, lab_a, lab_b)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
         RETURNING
             id as "id?: i64",
             name,
@@ -62,6 +75,7 @@ pub async fn create_color(pool: &PgPool, color: Color) -> Result<Color, Error> {
             is_clear,
             is_multi,
             user_id,
+            user_id IS NULL as "is_preset!: bool",
             (created_at AT TIME ZONE 'UTC') as "created_at?: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at?: chrono::NaiveDateTime"
         "#,
@@ -70,17 +84,22 @@ pub async fn create_color(pool: &PgPool, color: Color) -> Result<Color, Error> {
         color.hex,
         color.is_clear,
         color.is_multi,
-        color.user_id
+        color.user_id,
+        lab.l,
+        lab.a,
+        lab.b
     )
     .fetch_one(pool)
     .await
 }
 
-pub async fn update_color(
-    pool: &PgPool,
+// owner_id = None → แก้ preset กลาง (handler ของ admin เท่านั้น)
+pub async fn update_color<'e, E: PgExecutor<'e>>(
+    executor: E,
     color_id: i64,
-    user_id: i64,
+    owner_id: Option<i64>,
     payload: UpdateColorPayload,
+    lab: Option<Lab>, // 👈 มีเมื่อส่ง hex มาเท่านั้น
 ) -> Result<Color, Error> {
     sqlx::query_as!(
         Color,
@@ -92,8 +111,11 @@ pub async fn update_color(
             hex = COALESCE($3, hex),
             is_clear = COALESCE($4, is_clear),
             is_multi = COALESCE($5, is_multi),
+            lab_l = COALESCE($8, lab_l),
+            lab_a = COALESCE($9, lab_a),
+            lab_b = COALESCE($10, lab_b),
             updated_at = NOW()
-        WHERE id = $6 AND user_id = $7
+        WHERE id = $6 AND user_id IS NOT DISTINCT FROM $7
         RETURNING
             id as "id?: i64",
             name,
@@ -102,6 +124,7 @@ pub async fn update_color(
             is_clear,
             is_multi,
             user_id,
+            user_id IS NULL as "is_preset!: bool",
             (created_at AT TIME ZONE 'UTC') as "created_at?: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at?: chrono::NaiveDateTime"
         "#,
@@ -111,27 +134,621 @@ pub async fn update_color(
         payload.is_clear,
         payload.is_multi,
         color_id,
-        user_id
+        owner_id,
+        lab.map(|lab| lab.l),
+        lab.map(|lab| lab.a),
+        lab.map(|lab| lab.b)
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await
 }
 
-pub async fn delete_color(pool: &PgPool, color_id: i64, user_id: i64) -> Result<(), Error> {
-    let result = sqlx::query!(
+pub enum DeleteColorError {
+    NotFound,
+    InUse(ColorUsage),
+    InvalidTarget(String), // 👈 reassign_to ใช้ไม่ได้ (ไม่มี/ตัวเดียวกัน/ใช้ใน kit นั้นไม่ได้)
+    Db(Error),
+}
+
+impl From<Error> for DeleteColorError {
+    fn from(e: Error) -> Self {
+        DeleteColorError::Db(e)
+    }
+}
+
+// ลบสีส่วนตัว: ถ้ายังมี runner ใช้อยู่ต้องส่ง reassign_to (ทำทั้งหมดใน transaction เดียว)
+pub async fn delete_color(
+    pool: &PgPool,
+    color_id: i64,
+    user_id: i64,
+    reassign_to: Option<i64>,
+) -> Result<(), DeleteColorError> {
+    let mut tx = pool.begin().await?;
+
+    let exists = sqlx::query_scalar!(
+        "SELECT id FROM colors WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        color_id,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    if exists.is_none() {
+        return Err(DeleteColorError::NotFound);
+    }
+
+    let usage = get_color_usage(&mut tx, color_id, user_id).await?;
+    if usage.is_used() {
+        let Some(target_id) = reassign_to else {
+            return Err(DeleteColorError::InUse(usage));
+        };
+        if target_id == color_id {
+            return Err(DeleteColorError::InvalidTarget(
+                "reassign_to must be a different color".to_string(),
+            ));
+        }
+        // ปลายทางต้องเป็นสีของเราหรือ preset
+        let target = sqlx::query_scalar!(
+            "SELECT id FROM colors WHERE id = $1 AND (user_id = $2 OR user_id IS NULL)",
+            target_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if target.is_none() {
+            return Err(DeleteColorError::InvalidTarget(format!(
+                "Color {} not found",
+                target_id
+            )));
+        }
+
+        if let Some(msg) = check_target_for_usage(&mut tx, &usage, target_id).await? {
+            return Err(DeleteColorError::InvalidTarget(msg));
+        }
+
+        reassign_color(&mut tx, color_id, target_id, user_id).await?;
+    }
+
+    sqlx::query!("DELETE FROM colors WHERE id = $1", color_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// สีปลายทางต้องใช้ได้ในทุก kit ที่ usage อ้างถึง (Some = ข้อความบอกว่าใช้ไม่ได้ที่ kit ไหน)
+// kit ที่เราไม่ได้เป็นสมาชิกก็ต้องตรวจ แต่ไม่บอก id ออกไป
+async fn check_target_for_usage(
+    conn: &mut PgConnection,
+    usage: &ColorUsage,
+    target_id: i64,
+) -> Result<Option<String>, Error> {
+    let mut kit_ids: Vec<(i64, bool)> = usage
+        .runners
+        .iter()
+        .map(|r| (r.kit_id, true))
+        .chain(usage.other_runners.iter().map(|r| (r.kit_id, false)))
+        .collect();
+    kit_ids.sort_unstable();
+    kit_ids.dedup();
+    for (kit_id, visible) in kit_ids {
+        match ensure_colors_for_kit(conn, kit_id, &[target_id]).await {
+            Ok(_) => {}
+            Err(ReferenceError::Db(e)) => return Err(e),
+            Err(_) if visible => {
+                return Ok(Some(format!(
+                    "Color {} cannot be used in kit {}",
+                    target_id, kit_id
+                )))
+            }
+            Err(_) => {
+                return Ok(Some(format!(
+                    "Color {} cannot be used in a kit you are not a member of",
+                    target_id
+                )))
+            }
+        }
+    }
+    Ok(None)
+}
+
+// runner ทั้งหมดที่ใช้สีนี้ (รวมที่อยู่ในถังขยะ เพราะยังอ้างถึงสีอยู่)
+// แสดงรายละเอียดเฉพาะ kit ที่ viewer เป็นสมาชิก ที่เหลือนับรวมไว้ใน other_* (preset/สีที่เราออกจาก kit ไปแล้ว)
+pub async fn get_color_usage(
+    conn: &mut PgConnection,
+    color_id: i64,
+    viewer_id: i64,
+) -> Result<ColorUsage, Error> {
+    let rows = sqlx::query!(
         r#"
-        DELETE FROM colors
-        WHERE id = $1 AND user_id = $2
+        SELECT
+            r.id as "runner_id!: i64",
+            r.name as "runner_name!: String",
+            r.kit_id as "kit_id!: i64",
+            k.name as "kit_name!: String",
+            (r.color_id = $1) as "is_primary!: bool",
+            (
+                SELECT COUNT(*) FROM runner_gate_colors gc
+                WHERE gc.runner_id = r.id AND gc.color_id = $1
+            ) as "gate_count!: i64",
+            (r.deleted_at IS NOT NULL OR k.deleted_at IS NOT NULL) as "in_trash!: bool",
+            EXISTS (
+                SELECT 1 FROM kit_members m
+                WHERE m.kit_id = r.kit_id AND m.user_id = $2
+            ) as "is_member!: bool"
+        FROM runners r
+        JOIN kits k ON k.id = r.kit_id
+        WHERE r.color_id = $1
+           OR EXISTS (
+               SELECT 1 FROM runner_colors rc
+               WHERE rc.runner_id = r.id AND rc.color_id = $1
+           )
+        ORDER BY k.name, k.id, r.name
         "#,
         color_id,
+        viewer_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let (runners, other_runners): (Vec<_>, Vec<_>) = rows
+        .into_iter()
+        .map(|row| {
+            let runner = ColorUsageRunner {
+                runner_id: row.runner_id,
+                runner_name: row.runner_name,
+                kit_id: row.kit_id,
+                kit_name: row.kit_name,
+                is_primary: row.is_primary,
+                gate_count: row.gate_count,
+                in_trash: row.in_trash,
+            };
+            (row.is_member, runner)
+        })
+        .partition(|(is_member, _)| *is_member);
+    let runners: Vec<ColorUsageRunner> = runners.into_iter().map(|(_, r)| r).collect();
+    let other_runners: Vec<ColorUsageRunner> = other_runners.into_iter().map(|(_, r)| r).collect();
+
+    Ok(ColorUsage {
+        color_id,
+        kit_count: count_kits(&runners),
+        runner_count: runners.len() as i64,
+        other_kit_count: count_kits(&other_runners),
+        other_runner_count: other_runners.len() as i64,
+        runners,
+        other_runners,
+    })
+}
+
+fn count_kits(runners: &[ColorUsageRunner]) -> i64 {
+    let mut kit_ids: Vec<i64> = runners.iter().map(|r| r.kit_id).collect();
+    kit_ids.dedup(); // 👈 เรียงตาม kit แล้ว
+    kit_ids.len() as i64
+}
+
+// GET /colors/:id/usage — สีของเรา/preset ที่เราเห็นได้ (รายละเอียดเฉพาะ kit ของเรา)
+pub async fn get_color_usage_for_user(
+    pool: &PgPool,
+    color_id: i64,
+    user_id: i64,
+) -> Result<ColorUsage, Error> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query_scalar!(
+        "SELECT id FROM colors WHERE id = $1 AND (user_id = $2 OR user_id IS NULL)",
+        color_id,
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    get_color_usage(&mut conn, color_id, user_id).await
+}
+
+// ย้ายทุกที่ที่ใช้สี from → to (runners.color_id, สีรอง, สีเฉพาะ gate)
+// ผู้เรียกต้องตรวจแล้วว่า to ใช้ได้ในทุก kit ที่เกี่ยวข้อง
+pub async fn reassign_color(
+    conn: &mut PgConnection,
+    from_id: i64,
+    to_id: i64,
+    user_id: i64,
+) -> Result<(), Error> {
+    // runner ที่มีทั้งสองสีอยู่แล้ว: ย้าย gate ไปสีปลายทาง แล้วรวมเป็นแถวเดียว (ลำดับที่มาก่อนชนะ)
+    sqlx::query!(
+        r#"
+        UPDATE runner_gate_colors gc
+        SET color_id = $2, updated_by = $3, updated_at = NOW()
+        WHERE gc.color_id = $1
+          AND EXISTS (
+              SELECT 1 FROM runner_colors rc
+              WHERE rc.runner_id = gc.runner_id AND rc.color_id = $2
+          )
+        "#,
+        from_id,
+        to_id,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE runner_colors target
+        SET position = LEAST(target.position, source.position)
+        FROM runner_colors source
+        WHERE source.runner_id = target.runner_id
+          AND source.color_id = $1
+          AND target.color_id = $2
+        "#,
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM runner_colors source
+        WHERE source.color_id = $1
+          AND EXISTS (
+              SELECT 1 FROM runner_colors target
+              WHERE target.runner_id = source.runner_id AND target.color_id = $2
+          )
+        "#,
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // ที่เหลือเปลี่ยนตรงๆ (สีเฉพาะ gate ตามไปเองจาก ON UPDATE CASCADE)
+    sqlx::query!(
+        "UPDATE runner_colors SET color_id = $2 WHERE color_id = $1",
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE runners
+        SET color_id = $2, updated_by = $3, updated_at = NOW()
+        WHERE color_id = $1
+        "#,
+        from_id,
+        to_id,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// --- PALETTE IMPORT ---
+// upsert ตาม UNIQUE(user_id, name, code) ใน transaction เดียว คืน id ตามลำดับ entries
+// is_clear/is_multi = None → สีใหม่ใช้ false, สีเดิมคงค่าเดิม
+pub async fn upsert_palette_colors(
+    pool: &PgPool,
+    user_id: i64,
+    entries: &[(PaletteEntry, Lab)],
+) -> Result<Vec<i64>, Error> {
+    let mut tx = pool.begin().await?;
+    let mut ids = Vec::with_capacity(entries.len());
+    for (entry, lab) in entries {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO colors (name, code, hex, is_clear, is_multi, user_id, lab_l, lab_a, lab_b)
+            VALUES ($1, $2, $3, COALESCE($4, false), COALESCE($5, false), $6, $7, $8, $9)
+            ON CONFLICT (user_id, name, code) DO UPDATE
+            SET
+                hex = EXCLUDED.hex,
+                is_clear = COALESCE($4, colors.is_clear),
+                is_multi = COALESCE($5, colors.is_multi),
+                lab_l = EXCLUDED.lab_l,
+                lab_a = EXCLUDED.lab_a,
+                lab_b = EXCLUDED.lab_b,
+                updated_at = NOW()
+            RETURNING id
+            "#,
+            entry.name,
+            entry.code,
+            entry.hex,
+            entry.is_clear,
+            entry.is_multi,
+            user_id,
+            lab.l,
+            lab.a,
+            lab.b
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        ids.push(id);
+    }
+    tx.commit().await?;
+    Ok(ids)
+}
+
+// --- MERGE ---
+pub enum MergeColorsError {
+    NotFound(String),
+    Invalid(String),  // 👈 ใช้สีปลายทางใน kit นั้นไม่ได้ / แก้ preset ไม่ได้
+    Conflict(String), // 👈 ชื่อ+code ใหม่ซ้ำกับสีที่มีอยู่ (UNIQUE(user_id, name, code))
+    Db(Error),
+}
+
+impl From<Error> for MergeColorsError {
+    fn from(e: Error) -> Self {
+        MergeColorsError::Db(e)
+    }
+}
+
+// รวมสีซ้ำ: ย้าย runner ของ source ทั้งหมดไป target แล้วลบ source
+// changes (ถ้ามี) แก้ target หลังลบ source แล้ว → ตั้งชื่อ/code ซ้ำกับ source เดิมได้
+pub async fn merge_colors(
+    pool: &PgPool,
+    user_id: i64,
+    target_id: i64,
+    source_ids: &[i64],
+    changes: Option<(UpdateColorPayload, Option<Lab>)>,
+) -> Result<(Color, i64), MergeColorsError> {
+    let mut tx = pool.begin().await?;
+
+    let target_owner = sqlx::query_scalar!(
+        r#"
+        SELECT user_id FROM colors
+        WHERE id = $1 AND (user_id = $2 OR user_id IS NULL)
+        FOR UPDATE
+        "#,
+        target_id,
         user_id
     )
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| MergeColorsError::NotFound(format!("Color {} not found", target_id)))?;
+    if target_owner.is_none() && changes.is_some() {
+        return Err(MergeColorsError::Invalid(
+            "Preset colors cannot be changed; merge without name/code/hex changes".to_string(),
+        ));
+    }
+
+    // source ต้องเป็นสีของเราเท่านั้น (preset ลบไม่ได้)
+    let mut found = sqlx::query_scalar!(
+        "SELECT id FROM colors WHERE id = ANY($1) AND user_id = $2 ORDER BY id FOR UPDATE",
+        source_ids,
+        user_id
+    )
+    .fetch_all(&mut *tx)
     .await?;
+    found.sort_unstable();
+    let mut missing: Vec<i64> = source_ids
+        .iter()
+        .copied()
+        .filter(|id| found.binary_search(id).is_err())
+        .collect();
+    missing.sort_unstable();
+    if !missing.is_empty() {
+        let ids: Vec<String> = missing.iter().map(|id| id.to_string()).collect();
+        return Err(MergeColorsError::NotFound(format!(
+            "Colors not found: {}",
+            ids.join(", ")
+        )));
+    }
 
-    if result.rows_affected() == 0 {
-        return Err(Error::RowNotFound);
+    let mut runner_ids: Vec<i64> = Vec::new();
+    for source_id in source_ids {
+        let usage = get_color_usage(&mut tx, *source_id, user_id).await?;
+        if let Some(msg) = check_target_for_usage(&mut tx, &usage, target_id).await? {
+            return Err(MergeColorsError::Invalid(msg));
+        }
+        runner_ids.extend(
+            usage
+                .runners
+                .iter()
+                .chain(usage.other_runners.iter())
+                .map(|r| r.runner_id),
+        );
+
+        reassign_color(&mut tx, *source_id, target_id, user_id).await?;
+        sqlx::query!("DELETE FROM colors WHERE id = $1", source_id)
+            .execute(&mut *tx)
+            .await?;
     }
+    runner_ids.sort_unstable();
+    runner_ids.dedup();
+
+    let color = match changes {
+        Some((payload, lab)) => update_color(&mut *tx, target_id, target_owner, payload, lab)
+            .await
+            .map_err(|e| match e.as_database_error() {
+                Some(db_err) if db_err.is_unique_violation() => MergeColorsError::Conflict(
+                    "A color with this name and code already exists".to_string(),
+                ),
+                _ => MergeColorsError::Db(e),
+            })?,
+        None => get_color_by_id(&mut *tx, target_id).await?,
+    };
+
+    tx.commit().await?;
+    Ok((color, runner_ids.len() as i64))
+}
+
+// --- PRESETS ---
+pub enum DeletePresetError {
+    NotFound,
+    InUse(ColorUsage),
+    Db(Error),
+}
 
+impl From<Error> for DeletePresetError {
+    fn from(e: Error) -> Self {
+        DeletePresetError::Db(e)
+    }
+}
+
+pub async fn get_preset_colors(pool: &PgPool) -> Result<Vec<Color>, Error> {
+    sqlx::query_as!(
+        Color,
+        r#"
+        SELECT
+            id as "id?: i64",
+            name,
+            code,
+            hex,
+            is_clear,
+            is_multi,
+            user_id,
+            user_id IS NULL as "is_preset!: bool",
+            (created_at AT TIME ZONE 'UTC') as "created_at?: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at?: chrono::NaiveDateTime"
+        FROM colors
+        WHERE user_id IS NULL
+        ORDER BY is_multi DESC, is_clear DESC, name
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// preset ถูกใช้โดย runner ของหลาย user → ห้ามลบถ้ายังมีคนใช้ (รายละเอียดเฉพาะ kit ของ admin เอง ที่เหลือเป็นตัวเลข)
+pub async fn delete_preset_color(
+    pool: &PgPool,
+    color_id: i64,
+    user_id: i64,
+) -> Result<(), DeletePresetError> {
+    let mut tx = pool.begin().await?;
+
+    let exists = sqlx::query_scalar!(
+        "SELECT id FROM colors WHERE id = $1 AND user_id IS NULL FOR UPDATE",
+        color_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    if exists.is_none() {
+        return Err(DeletePresetError::NotFound);
+    }
+
+    let usage = get_color_usage(&mut tx, color_id, user_id).await?;
+    if usage.is_used() {
+        return Err(DeletePresetError::InUse(usage));
+    }
+
+    sqlx::query!("DELETE FROM colors WHERE id = $1", color_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
     Ok(())
 }
+
+// คัดลอก preset มาเป็นสีส่วนตัว (แก้ไขต่อได้โดยไม่กระทบ preset)
+pub async fn copy_preset_color(
+    pool: &PgPool,
+    preset_id: i64,
+    user_id: i64,
+) -> Result<Color, Error> {
+    sqlx::query_as!(
+        Color,
+        r#"
+        INSERT INTO colors (name, code, hex, is_clear, is_multi, user_id, lab_l, lab_a, lab_b)
+        SELECT name, code, hex, is_clear, is_multi, $2, lab_l, lab_a, lab_b
+        FROM colors
+        WHERE id = $1 AND user_id IS NULL
+        RETURNING
+            id as "id?: i64",
+            name,
+            code,
+            hex,
+            is_clear,
+            is_multi,
+            user_id,
+            user_id IS NULL as "is_preset!: bool",
+            (created_at AT TIME ZONE 'UTC') as "created_at?: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at?: chrono::NaiveDateTime"
+        "#,
+        preset_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// --- SEARCH ---
+// สีของ user + preset กลาง ที่มีค่า Lab แล้ว (distance ยังเป็น 0 ให้ handler คำนวณ)
+pub async fn get_color_candidates(pool: &PgPool, user_id: i64) -> Result<Vec<ColorMatch>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            name,
+            code,
+            hex,
+            lab_l as "lab_l!: f64",
+            lab_a as "lab_a!: f64",
+            lab_b as "lab_b!: f64"
+        FROM colors
+        WHERE (user_id = $1 OR user_id IS NULL) AND lab_l IS NOT NULL
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ColorMatch {
+            kind: ColorMatchKind::Color,
+            id: row.id,
+            name: row.name,
+            code: Some(row.code),
+            brand: None,
+            hex: row.hex,
+            lab: Lab {
+                l: row.lab_l,
+                a: row.lab_a,
+                b: row.lab_b,
+            },
+            distance: 0.0,
+        })
+        .collect())
+}
+
+// paint ของ user ที่มี hex (ไม่มี hex = จับคู่สีไม่ได้)
+pub async fn get_paint_candidates(pool: &PgPool, user_id: i64) -> Result<Vec<ColorMatch>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            name,
+            code,
+            brand,
+            hex as "hex!: String",
+            lab_l as "lab_l!: f64",
+            lab_a as "lab_a!: f64",
+            lab_b as "lab_b!: f64"
+        FROM paints
+        WHERE user_id = $1 AND hex IS NOT NULL AND lab_l IS NOT NULL
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ColorMatch {
+            kind: ColorMatchKind::Paint,
+            id: row.id,
+            name: row.name,
+            code: row.code,
+            brand: row.brand,
+            hex: row.hex,
+            lab: Lab {
+                l: row.lab_l,
+                a: row.lab_a,
+                b: row.lab_b,
+            },
+            distance: 0.0,
+        })
+        .collect())
+}
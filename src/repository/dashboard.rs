@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use sqlx::{Error, PgPool};
+
+use crate::model::{
+    dashboard::{
+        CutProgress, Dashboard, KitGradeCount, KitStatusCount, RecentKit, RunnerColorCount,
+    },
+    kit::{KitGrade, KitStatus},
+};
+
+const RECENTLY_TOUCHED_LIMIT: i64 = 10;
+
+// สถิติทั้งหมดนับจาก kit ที่ user เป็นสมาชิก (ทุก role) และไม่อยู่ในถังขยะ
+// from / to เป็นวันที่แบบ UTC (รวมทั้งสองวัน) ใช้กับกราฟการตัดและ "recently touched"
+pub async fn get_dashboard(
+    pool: &PgPool,
+    user_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Dashboard, Error> {
+    // 1. จำนวน kit ตาม status
+    let kits_by_status: Vec<KitStatusCount> = sqlx::query!(
+        r#"
+        SELECT
+            k.status as "status!: KitStatus",
+            COUNT(*) as "count!: i64"
+        FROM kits k
+        JOIN kit_members m ON m.kit_id = k.id AND m.user_id = $1
+        WHERE k.deleted_at IS NULL
+        GROUP BY k.status
+        ORDER BY k.status
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| KitStatusCount {
+        status: row.status,
+        count: row.count,
+    })
+    .collect();
+
+    // 2. จำนวน kit ตาม grade
+    let kits_by_grade: Vec<KitGradeCount> = sqlx::query!(
+        r#"
+        SELECT
+            k.grade as "grade!: KitGrade",
+            COUNT(*) as "count!: i64"
+        FROM kits k
+        JOIN kit_members m ON m.kit_id = k.id AND m.user_id = $1
+        WHERE k.deleted_at IS NULL
+        GROUP BY k.grade
+        ORDER BY k.grade
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| KitGradeCount {
+        grade: row.grade,
+        count: row.count,
+    })
+    .collect();
+
+    // 3. Part / gate ที่ตัดต่อวัน (gate นับจากจำนวนสมาชิกใน array ของ requirement)
+    let cut_per_day: Vec<CutProgress> = sqlx::query!(
+        r#"
+        WITH cuts AS (
+            SELECT
+                (kp.cut_at AT TIME ZONE 'UTC')::DATE AS day,
+                1::BIGINT AS parts,
+                0::BIGINT AS gates
+            FROM kit_parts kp
+            JOIN kits k ON k.id = kp.kit_id
+            JOIN kit_members m ON m.kit_id = kp.kit_id AND m.user_id = $1
+            WHERE kp.deleted_at IS NULL
+              AND k.deleted_at IS NULL
+              AND kp.cut_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+              AND kp.cut_at < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+
+            UNION ALL
+
+            SELECT
+                (kpr.cut_at AT TIME ZONE 'UTC')::DATE,
+                0::BIGINT,
+                CASE
+                    WHEN jsonb_typeof(kpr.gate) = 'array' THEN jsonb_array_length(kpr.gate)
+                    ELSE 1
+                END::BIGINT
+            FROM kit_part_requirements kpr
+            JOIN kit_parts kp ON kp.id = kpr.kit_part_id
+            JOIN kits k ON k.id = kp.kit_id
+            JOIN kit_members m ON m.kit_id = kp.kit_id AND m.user_id = $1
+            WHERE kpr.deleted_at IS NULL
+              AND k.deleted_at IS NULL
+              AND kpr.cut_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+              AND kpr.cut_at < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+        )
+        SELECT
+            day as "day!: NaiveDate",
+            SUM(parts)::BIGINT as "parts!: i64",
+            SUM(gates)::BIGINT as "gates!: i64"
+        FROM cuts
+        GROUP BY day
+        ORDER BY day
+        "#,
+        user_id,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| CutProgress {
+        period: row.day,
+        parts: row.parts,
+        gates: row.gates,
+    })
+    .collect();
+
+    // 4. รวมรายวันเป็นรายสัปดาห์ (เริ่มวันจันทร์ เหมือน date_trunc('week'))
+    let mut weeks: BTreeMap<NaiveDate, (i64, i64)> = BTreeMap::new();
+    for day in &cut_per_day {
+        let week_start =
+            day.period - Duration::days(day.period.weekday().num_days_from_monday() as i64);
+        let entry = weeks.entry(week_start).or_default();
+        entry.0 += day.parts;
+        entry.1 += day.gates;
+    }
+    let cut_per_week = weeks
+        .into_iter()
+        .map(|(period, (parts, gates))| CutProgress {
+            period,
+            parts,
+            gates,
+        })
+        .collect();
+
+    // 5. การกระจายสีของ runner
+    let runner_colors = sqlx::query!(
+        r#"
+        SELECT
+            c.id as "color_id!: i64",
+            c.name,
+            c.code,
+            c.hex,
+            COUNT(r.id) as "runners!: i64",
+            COALESCE(SUM(r.amount), 0)::BIGINT as "amount!: i64"
+        FROM runners r
+        JOIN kits k ON k.id = r.kit_id
+        JOIN kit_members m ON m.kit_id = r.kit_id AND m.user_id = $1
+        JOIN colors c ON c.id = r.color_id
+        WHERE r.deleted_at IS NULL AND k.deleted_at IS NULL
+        GROUP BY c.id
+        ORDER BY COUNT(r.id) DESC, c.name
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| RunnerColorCount {
+        color_id: row.color_id,
+        name: row.name,
+        code: row.code,
+        hex: row.hex,
+        runners: row.runners,
+        amount: row.amount,
+    })
+    .collect();
+
+    // 6. Steam wishlist = เกมที่ยังไม่ได้ซื้อ
+    let steam_wishlist_total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM steam_app_games
+        WHERE user_id = $1 AND is_buy = false
+        "#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    // 7. Kit ที่มีการแก้ไขล่าสุด (นับรวมการแก้ไขของลูกด้วย)
+    let recently_touched = sqlx::query!(
+        r#"
+        SELECT
+            k.id as "id!: i64",
+            k.name,
+            k.grade as "grade!: KitGrade",
+            k.status as "status!: KitStatus",
+            (t.touched_at AT TIME ZONE 'UTC') as "touched_at!: chrono::NaiveDateTime"
+        FROM kits k
+        JOIN kit_members m ON m.kit_id = k.id AND m.user_id = $1
+        CROSS JOIN LATERAL (
+            SELECT GREATEST(
+                k.updated_at,
+                (SELECT MAX(r.updated_at) FROM runners r
+                 WHERE r.kit_id = k.id AND r.deleted_at IS NULL),
+                (SELECT MAX(sa.updated_at) FROM sub_assemblies sa
+                 WHERE sa.kit_id = k.id AND sa.deleted_at IS NULL),
+                (SELECT MAX(kp.updated_at) FROM kit_parts kp
+                 WHERE kp.kit_id = k.id AND kp.deleted_at IS NULL)
+            ) AS touched_at
+        ) t
+        WHERE k.deleted_at IS NULL
+          AND t.touched_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+          AND t.touched_at < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+        ORDER BY t.touched_at DESC
+        LIMIT $4
+        "#,
+        user_id,
+        from,
+        to,
+        RECENTLY_TOUCHED_LIMIT
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| RecentKit {
+        id: row.id,
+        name: row.name,
+        grade: row.grade,
+        status: row.status,
+        touched_at: row.touched_at,
+    })
+    .collect();
+
+    let total_kits = kits_by_status.iter().map(|s| s.count).sum();
+
+    Ok(Dashboard {
+        from,
+        to,
+        total_kits,
+        kits_by_status,
+        kits_by_grade,
+        cut_per_day,
+        cut_per_week,
+        runner_colors,
+        steam_wishlist_total,
+        recently_touched,
+    })
+}
@@ -25,20 +25,40 @@ pub async fn create(
     };
     let status_str = "pending";
 
+    let mut tx = pool.begin().await?;
+
     let rec = sqlx::query!(
         r#"
-        INSERT INTO kits (name, grade, status, user_id, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, NOW(), NOW())
+        INSERT INTO kits (
+            name, grade, status, tags, purchase_price, user_id, updated_by, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $6, NOW(), NOW())
         RETURNING id as "id!: i64"
         "#,
         payload.name,
         grade_str,
         status_str,
+        &payload.tags,
+        payload.purchase_price,
         user_id
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    // ผู้สร้าง kit เป็น owner เสมอ
+    sqlx::query!(
+        r#"
+        INSERT INTO kit_members (kit_id, user_id, role)
+        VALUES ($1, $2, 'owner')
+        "#,
+        rec.id,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
     let new_kit_id = rec.id;
     get_by_id(pool, new_kit_id, user_id).await
 }
@@ -64,11 +84,19 @@ pub async fn get_all(
             name,
             grade as "grade: KitGrade",
             status as "status: KitStatus",
+            tags,
+            purchase_price,
             user_id as "user_id!",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!"
         FROM kits
-        WHERE user_id = $1 AND ($2::TEXT IS NULL OR status = $2)
+        WHERE deleted_at IS NULL
+          AND ($2::TEXT IS NULL OR status = $2)
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kits.id AND m.user_id = $1
+          )
         "#,
         user_id,
         status_str
@@ -90,11 +118,19 @@ pub async fn get_by_id(pool: &PgPool, kit_id: i64, user_id: i64) -> Result<KitWi
             name,
             grade as "grade: KitGrade",
             status as "status: KitStatus",
+            tags,
+            purchase_price,
             user_id as "user_id!",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!"
         FROM kits
-        WHERE id = $1 AND user_id = $2
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kits.id AND m.user_id = $2
+          )
         "#,
         kit_id,
         user_id
@@ -102,7 +138,7 @@ pub async fn get_by_id(pool: &PgPool, kit_id: i64, user_id: i64) -> Result<KitWi
     .fetch_one(pool)
     .await?;
 
-    // 2. ดึง Runners ทั้งหมดที่เกี่ยวข้องกับ Kit นี้
+    // 2. ดึง Runners ทั้งหมดที่เกี่ยวข้องกับ Kit นี้ (สิทธิ์อ่านเช็คไปแล้วในข้อ 1)
     let runners = sqlx::query_as!(
         Runner,
         r#"
@@ -114,13 +150,13 @@ pub async fn get_by_id(pool: &PgPool, kit_id: i64, user_id: i64) -> Result<KitWi
             amount as "amount!: i32",
             user_id as "user_id!",
             is_used,
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!"
         FROM runners
-        WHERE kit_id = $1 AND user_id = $2
+        WHERE kit_id = $1 AND deleted_at IS NULL
         "#,
-        kit_id,
-        user_id
+        kit.id
     )
     .fetch_all(pool)
     .await?;
@@ -152,13 +188,23 @@ pub async fn update(
         SET
             name = COALESCE($1, name),
             grade = COALESCE($2, grade),
+            tags = COALESCE($5, tags),
+            purchase_price = COALESCE($6, purchase_price),
+            updated_by = $4,
             updated_at = NOW()
-        WHERE id = $3 AND user_id = $4
+        WHERE id = $3
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kits.id AND m.user_id = $4 AND m.role IN ('owner', 'editor')
+          )
         "#,
         payload.name,
         grade_str,
         kit_id,
-        user_id
+        user_id,
+        payload.tags.as_deref(),
+        payload.purchase_price
     )
     .execute(pool)
     .await?;
@@ -185,8 +231,13 @@ pub async fn update_status(
     let result = sqlx::query!(
         r#"
         UPDATE kits
-        SET status = $1, updated_at = NOW()
-        WHERE id = $2 AND user_id = $3
+        SET status = $1, updated_by = $3, updated_at = NOW()
+        WHERE id = $2
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kits.id AND m.user_id = $3 AND m.role IN ('owner', 'editor')
+          )
         "#,
         status_str,
         kit_id,
@@ -201,20 +252,70 @@ pub async fn update_status(
     get_by_id(pool, kit_id, user_id).await
 }
 
-// --- DELETE ---
+// --- DELETE (ย้ายลงถังขยะ) ---
+// ไม่ลบจริง: ตั้ง deleted_at ให้ kit และลูกทั้งหมดด้วย timestamp เดียวกัน
+// เพื่อให้ restore คืนเฉพาะแถวที่ถูกลบไปพร้อมกับ kit นี้
+// เฉพาะ owner (kits.user_id) เท่านั้นที่ลบ kit ได้
 pub async fn delete_kit(pool: &PgPool, kit_id: i64, user_id: i64) -> Result<(), Error> {
-    let result = sqlx::query!(
+    let mut tx = pool.begin().await?;
+
+    let rec = sqlx::query!(
         r#"
-        DELETE FROM kits WHERE id = $1 AND user_id = $2
+        UPDATE kits
+        SET deleted_at = NOW(), updated_by = $2
+        WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+        RETURNING deleted_at as "deleted_at!"
         "#,
         kit_id,
         user_id
     )
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(Error::RowNotFound)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE kit_part_requirements
+        SET deleted_at = $2
+        WHERE deleted_at IS NULL
+          AND kit_part_id IN (SELECT id FROM kit_parts WHERE kit_id = $1)
+        "#,
+        kit_id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
     .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(Error::RowNotFound);
-    }
+    sqlx::query!(
+        r#"
+        UPDATE kit_parts SET deleted_at = $2 WHERE kit_id = $1 AND deleted_at IS NULL
+        "#,
+        kit_id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE sub_assemblies SET deleted_at = $2 WHERE kit_id = $1 AND deleted_at IS NULL
+        "#,
+        kit_id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE runners SET deleted_at = $2 WHERE kit_id = $1 AND deleted_at IS NULL
+        "#,
+        kit_id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
     Ok(())
 }
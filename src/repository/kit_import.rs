@@ -0,0 +1,74 @@
+use sqlx::{Error, PgPool};
+
+use crate::model::{kit::KitGrade, kit_import::NewKitRow};
+
+// (id, ชื่อ lowercase, grade) ของ kit ที่ user เป็นเจ้าของอยู่แล้ว ใช้เช็คแถวซ้ำตอน import
+pub async fn get_existing_kit_keys(
+    pool: &PgPool,
+    user_id: i64,
+) -> Result<Vec<(i64, String, KitGrade)>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            LOWER(name) as "name!: String",
+            grade as "grade: KitGrade"
+        FROM kits
+        WHERE user_id = $1 AND deleted_at IS NULL
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.id, row.name, row.grade))
+        .collect())
+}
+
+// สร้างทุกแถวใน transaction เดียว (ผู้ import เป็น owner) คืน id ตามลำดับของ rows
+pub async fn create_kits(
+    pool: &PgPool,
+    user_id: i64,
+    rows: &[NewKitRow],
+) -> Result<Vec<i64>, Error> {
+    let mut tx = pool.begin().await?;
+    let mut ids = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let rec = sqlx::query!(
+            r#"
+            INSERT INTO kits (
+                name, grade, status, tags, purchase_price, user_id, updated_by, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $6, NOW(), NOW())
+            RETURNING id as "id!: i64"
+            "#,
+            row.name,
+            row.grade.as_str(),
+            row.status.as_str(),
+            &row.tags,
+            row.purchase_price,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO kit_members (kit_id, user_id, role)
+            VALUES ($1, $2, 'owner')
+            "#,
+            rec.id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        ids.push(rec.id);
+    }
+
+    tx.commit().await?;
+    Ok(ids)
+}
@@ -0,0 +1,168 @@
+use sqlx::{Error, PgPool};
+
+use crate::model::kit_member::{AddKitMemberPayload, KitMember, KitRole, UpdateKitMemberPayload};
+
+async fn get_member(pool: &PgPool, kit_id: i64, member_user_id: i64) -> Result<KitMember, Error> {
+    sqlx::query_as!(
+        KitMember,
+        r#"
+        SELECT
+            m.kit_id as "kit_id!: i64",
+            m.user_id as "user_id!: i64",
+            u.username,
+            u.full_name,
+            m.role as "role: KitRole",
+            (m.created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (m.updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM kit_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.kit_id = $1 AND m.user_id = $2
+        "#,
+        kit_id,
+        member_user_id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// --- READ ---
+// สมาชิกทุก role ดูรายชื่อสมาชิกได้ (ไม่ใช่สมาชิก = RowNotFound)
+pub async fn get_members(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+) -> Result<Vec<KitMember>, Error> {
+    let members = sqlx::query_as!(
+        KitMember,
+        r#"
+        SELECT
+            m.kit_id as "kit_id!: i64",
+            m.user_id as "user_id!: i64",
+            u.username,
+            u.full_name,
+            m.role as "role: KitRole",
+            (m.created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (m.updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM kit_members m
+        JOIN users u ON u.id = m.user_id
+        JOIN kits k ON k.id = m.kit_id
+        WHERE m.kit_id = $1
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members me
+              WHERE me.kit_id = $1 AND me.user_id = $2
+          )
+        ORDER BY
+            CASE m.role WHEN 'owner' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END,
+            u.username
+        "#,
+        kit_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // kit ที่มีอยู่จริงต้องมี owner เสมอ: ว่าง = ไม่เจอ kit หรือไม่ใช่สมาชิก
+    if members.is_empty() {
+        return Err(Error::RowNotFound);
+    }
+    Ok(members)
+}
+
+// --- CREATE ---
+// เฉพาะ owner เท่านั้นที่เพิ่มสมาชิกได้
+pub async fn add_member(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+    payload: AddKitMemberPayload,
+) -> Result<KitMember, Error> {
+    let rec = sqlx::query!(
+        r#"
+        INSERT INTO kit_members (kit_id, user_id, role)
+        SELECT $1, u.id, $3
+        FROM users u
+        WHERE u.username = $4
+          AND EXISTS (
+              SELECT 1 FROM kits k
+              WHERE k.id = $1 AND k.user_id = $2 AND k.deleted_at IS NULL
+          )
+        RETURNING user_id as "user_id!: i64"
+        "#,
+        kit_id,
+        user_id,
+        payload.role.as_str(),
+        payload.username
+    )
+    .fetch_one(pool)
+    .await?;
+
+    get_member(pool, kit_id, rec.user_id).await
+}
+
+// --- UPDATE ---
+// เปลี่ยน role ของ owner ไม่ได้ (kit ต้องมี owner คนเดียวเสมอ)
+pub async fn update_member_role(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+    member_user_id: i64,
+    payload: UpdateKitMemberPayload,
+) -> Result<KitMember, Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE kit_members
+        SET role = $4, updated_at = NOW()
+        WHERE kit_id = $1
+          AND user_id = $3
+          AND role <> 'owner'
+          AND EXISTS (
+              SELECT 1 FROM kits k
+              WHERE k.id = $1 AND k.user_id = $2 AND k.deleted_at IS NULL
+          )
+        "#,
+        kit_id,
+        user_id,
+        member_user_id,
+        payload.role.as_str()
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+    get_member(pool, kit_id, member_user_id).await
+}
+
+// --- DELETE ---
+// owner ลบสมาชิกคนอื่นได้ และสมาชิกออกจาก kit เองได้ (ยกเว้น owner)
+pub async fn remove_member(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+    member_user_id: i64,
+) -> Result<(), Error> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM kit_members
+        WHERE kit_id = $1
+          AND user_id = $3
+          AND role <> 'owner'
+          AND (
+              $3 = $2
+              OR EXISTS (SELECT 1 FROM kits k WHERE k.id = $1 AND k.user_id = $2)
+          )
+        "#,
+        kit_id,
+        user_id,
+        member_user_id
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+    Ok(())
+}
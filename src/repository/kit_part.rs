@@ -5,6 +5,13 @@ use crate::model::{
     },
     requirement::KitPartWithRequirements,
 };
+use crate::repository::kit_part_paint::{get_kit_part_paints, get_kit_part_recipes};
+use crate::repository::reference::{
+    ensure_kit_writable, ensure_sub_assembly_in_kit, ReferenceError,
+};
+use crate::repository::runner::refresh_runners_is_used;
+use crate::repository::runner_color::get_runner_color_sets;
+use crate::repository::workflow::{apply_stage, get_kit_part_stages};
 use sqlx::{Error, PgPool};
 
 // --- KitPart Functions ---
@@ -13,12 +20,24 @@ pub async fn create_kit_part(
     pool: &PgPool,
     user_id: i64,
     payload: CreateKitPartPayload,
-) -> Result<KitPart, Error> {
-    sqlx::query_as!(
+) -> Result<KitPart, ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    ensure_kit_writable(&mut conn, user_id, payload.kit_id).await?;
+    ensure_sub_assembly_in_kit(&mut conn, user_id, payload.kit_id, payload.sub_assembly_id).await?;
+
+    let part = sqlx::query_as!(
         KitPart,
         r#"
-        INSERT INTO kit_parts (code, is_cut, kit_id, sub_assembly_id, user_id)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO kit_parts (code, is_cut, kit_id, sub_assembly_id, user_id, updated_by)
+        SELECT $1, $2, $3, $4, $5, $5
+        WHERE EXISTS (
+            SELECT 1 FROM kit_members m
+            JOIN kits k ON k.id = m.kit_id
+            WHERE m.kit_id = $3
+              AND m.user_id = $5
+              AND m.role IN ('owner', 'editor')
+              AND k.deleted_at IS NULL
+        )
         RETURNING
             id as "id!: i64",
             code,
@@ -26,6 +45,7 @@ pub async fn create_kit_part(
             kit_id as "kit_id!: i64",
             sub_assembly_id as "sub_assembly_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         "#,
@@ -35,8 +55,10 @@ pub async fn create_kit_part(
         payload.sub_assembly_id,
         user_id
     )
-    .fetch_one(pool)
-    .await
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(part)
 }
 
 pub async fn get_all_kit_parts_for_sub_assembly(
@@ -54,10 +76,16 @@ pub async fn get_all_kit_parts_for_sub_assembly(
             kit_id as "kit_id!: i64",
             sub_assembly_id as "sub_assembly_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         FROM kit_parts
-        WHERE sub_assembly_id = $1 AND user_id = $2
+        WHERE sub_assembly_id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kit_parts.kit_id AND m.user_id = $2
+          )
         "#,
         sub_assembly_id,
         user_id
@@ -136,10 +164,16 @@ pub async fn get_kit_part_by_id(pool: &PgPool, id: i64, user_id: i64) -> Result<
             kit_id as "kit_id!: i64",
             sub_assembly_id as "sub_assembly_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         FROM kit_parts
-        WHERE id = $1 AND user_id = $2
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kit_parts.kit_id AND m.user_id = $2
+          )
         "#,
         id,
         user_id
@@ -195,18 +229,31 @@ pub async fn get_kit_part_by_id_with_requirements(
 //     .await
 // }
 
+// ยังไม่ตัด = ยังไม่เริ่มขั้นไหน → ล้าง stage ด้วย (บันทึกประวัติเหมือนย้ายขั้น)
 pub async fn update_kit_part_is_cut(
     pool: &PgPool,
     id: i64,
     user_id: i64,
     is_cut: bool,
 ) -> Result<KitPart, Error> {
-    sqlx::query_as!(
+    let mut tx = pool.begin().await?;
+    let kit_part = sqlx::query_as!(
         KitPart,
         r#"
         UPDATE kit_parts
-        SET is_cut = $1, updated_at = NOW()
-        WHERE id = $2 AND user_id = $3
+        SET
+            is_cut = $1,
+            cut_at = CASE WHEN $1 THEN COALESCE(cut_at, NOW()) ELSE NULL END,
+            updated_by = $3,
+            updated_at = NOW()
+        WHERE id = $2
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kit_parts.kit_id
+                AND m.user_id = $3
+                AND m.role IN ('owner', 'editor')
+          )
         RETURNING
             id as "id!: i64",
             code,
@@ -214,6 +261,7 @@ pub async fn update_kit_part_is_cut(
             kit_id as "kit_id!: i64",
             sub_assembly_id as "sub_assembly_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         "#,
@@ -221,25 +269,63 @@ pub async fn update_kit_part_is_cut(
         id,
         user_id
     )
-    .fetch_one(pool)
-    .await
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if !is_cut {
+        apply_stage(&mut tx, &[id], None, user_id, false).await?;
+    }
+    tx.commit().await?;
+    Ok(kit_part)
 }
 
+// Soft delete: the kit part and its requirements go to the trash together
 pub async fn delete_kit_part(pool: &PgPool, id: i64, user_id: i64) -> Result<(), Error> {
-    let result = sqlx::query!(
+    let mut tx = pool.begin().await?;
+
+    let rec = sqlx::query!(
         r#"
-        DELETE FROM kit_parts
-        WHERE id = $1 AND user_id = $2
+        UPDATE kit_parts
+        SET deleted_at = NOW(), updated_by = $2
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kit_parts.kit_id
+                AND m.user_id = $2
+                AND m.role IN ('owner', 'editor')
+          )
+        RETURNING deleted_at as "deleted_at!"
         "#,
         id,
         user_id
     )
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(Error::RowNotFound)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE kit_part_requirements
+        SET deleted_at = $2
+        WHERE kit_part_id = $1 AND deleted_at IS NULL
+        "#,
+        id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
     .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(Error::RowNotFound);
-    }
+    // requirement ที่ยังไม่ตัดของ part นี้ไม่นับแล้ว runner อาจกลายเป็น used
+    let runner_ids: Vec<i64> = sqlx::query_scalar!(
+        r#"SELECT DISTINCT runner_id as "runner_id!: i64" FROM kit_part_requirements WHERE kit_part_id = $1"#,
+        id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
+    tx.commit().await?;
     Ok(())
 }
 
@@ -259,9 +345,16 @@ pub async fn get_all_requirements_for_kit_part(
             is_cut,
             runner_id as "runner_id!: i64",
             kit_part_id as "kit_part_id!: i64",
-            user_id as "user_id!: i64"
+            user_id as "user_id!: i64",
+            updated_by
         FROM kit_part_requirements
-        WHERE kit_part_id = $1 AND user_id = $2
+        WHERE kit_part_id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_parts kp
+              JOIN kit_members m ON m.kit_id = kp.kit_id
+              WHERE kp.id = kit_part_requirements.kit_part_id AND m.user_id = $2
+          )
         "#,
         kit_part_id,
         user_id
@@ -279,6 +372,7 @@ pub async fn get_all_requirements_for_kit_part(
             runner_id: row.runner_id,
             kit_part_id: row.kit_part_id,
             user_id: row.user_id,
+            updated_by: row.updated_by,
         })
         .collect())
 }
@@ -298,6 +392,7 @@ pub async fn get_all_requirements_with_join_runner_for_kit_part(
             kpr.runner_id as "runner_id!: i64",
             kpr.kit_part_id as "kit_part_id!: i64",
             kpr.user_id as "user_id!: i64",
+            kpr.updated_by,
             runner.id as "r_id!: i64",
             (runner.name)::TEXT as "r_name!: String",
             runner.kit_id as "r_kit_id!: i64",
@@ -305,11 +400,18 @@ pub async fn get_all_requirements_with_join_runner_for_kit_part(
             (runner.amount)::INT as "r_amount!: i32",
             runner.user_id as "r_user_id!: i64",
             (runner.is_used)::BOOLEAN as "r_is_used!: bool",
+            runner.updated_by as r_updated_by,
             (runner.created_at AT TIME ZONE 'UTC') as "r_created_at!: chrono::NaiveDateTime",
             (runner.updated_at AT TIME ZONE 'UTC') as "r_updated_at!: chrono::NaiveDateTime"
         FROM kit_part_requirements kpr
         JOIN runners runner ON runner.id = kpr.runner_id
-        WHERE kpr.kit_part_id = $1 AND kpr.user_id = $2
+        WHERE kpr.kit_part_id = $1
+          AND kpr.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_parts kp
+              JOIN kit_members m ON m.kit_id = kp.kit_id
+              WHERE kp.id = kpr.kit_part_id AND m.user_id = $2
+          )
         "#,
         kit_part_id,
         user_id
@@ -327,6 +429,7 @@ pub async fn get_all_requirements_with_join_runner_for_kit_part(
             runner_id: row.runner_id,
             kit_part_id: row.kit_part_id,
             user_id: row.user_id,
+            updated_by: row.updated_by,
             runner: crate::model::runner::Runner {
                 id: row.r_id,
                 name: row.r_name,
@@ -335,6 +438,7 @@ pub async fn get_all_requirements_with_join_runner_for_kit_part(
                 amount: row.r_amount,
                 user_id: row.r_user_id,
                 is_used: row.r_is_used,
+                updated_by: row.r_updated_by,
                 created_at: row.r_created_at,
                 updated_at: row.r_updated_at,
             },
@@ -357,6 +461,7 @@ pub async fn get_all_requirements_with_join_runner_and_color_for_kit_part(
             kpr.runner_id as "runner_id!: i64",
             kpr.kit_part_id as "kit_part_id!: i64",
             kpr.user_id as "user_id!: i64",
+            kpr.updated_by,
             r.id as "r_id!: i64",
             (r.name)::TEXT as "r_name!: String",
             r.kit_id as "r_kit_id!: i64",
@@ -364,6 +469,7 @@ pub async fn get_all_requirements_with_join_runner_and_color_for_kit_part(
             (r.amount)::INT as "r_amount!: i32",
             r.user_id as "r_user_id!: i64",
             (r.is_used)::BOOLEAN as "r_is_used!: bool",
+            r.updated_by as r_updated_by,
             (r.created_at AT TIME ZONE 'UTC') as "r_created_at!: chrono::NaiveDateTime",
             (r.updated_at AT TIME ZONE 'UTC') as "r_updated_at!: chrono::NaiveDateTime",
             c.id as "c_id!: i64",
@@ -371,11 +477,25 @@ pub async fn get_all_requirements_with_join_runner_and_color_for_kit_part(
             c.code as c_code,
             c.hex as c_hex,
             c.is_clear as c_is_clear,
-            c.is_multi as c_is_multi
+            c.is_multi as c_is_multi,
+            COALESCE((
+                SELECT ARRAY_AGG(DISTINCT g.gate)
+                FROM kit_part_requirements kpr2
+                CROSS JOIN LATERAL jsonb_array_elements_text(kpr2.gate) AS g(gate)
+                WHERE kpr2.runner_id = r.id
+                  AND kpr2.is_cut = false
+                  AND kpr2.deleted_at IS NULL
+            ), '{}') as "remaining_gates!: Vec<String>"
         FROM kit_part_requirements kpr
         JOIN runners r ON r.id = kpr.runner_id
         JOIN colors c ON c.id = r.color_id
-        WHERE kpr.kit_part_id = $1 AND kpr.user_id = $2
+        WHERE kpr.kit_part_id = $1
+          AND kpr.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_parts kp
+              JOIN kit_members m ON m.kit_id = kp.kit_id
+              WHERE kp.id = kpr.kit_part_id AND m.user_id = $2
+          )
         "#,
         kit_part_id,
         user_id
@@ -383,6 +503,9 @@ pub async fn get_all_requirements_with_join_runner_and_color_for_kit_part(
     .fetch_all(pool)
     .await?;
 
+    let runner_ids: Vec<i64> = rows.iter().map(|row| row.r_id).collect();
+    let color_sets = get_runner_color_sets(pool, &runner_ids).await?;
+
     Ok(rows
         .into_iter()
         .map(
@@ -394,6 +517,7 @@ pub async fn get_all_requirements_with_join_runner_and_color_for_kit_part(
                 runner_id: row.runner_id,
                 kit_part_id: row.kit_part_id,
                 user_id: row.user_id,
+                updated_by: row.updated_by,
                 runner: crate::model::runner::RunnerWithColor {
                     id: row.r_id,
                     name: row.r_name,
@@ -411,6 +535,19 @@ pub async fn get_all_requirements_with_join_runner_and_color_for_kit_part(
                         is_clear: row.c_is_clear,
                         is_multi: row.c_is_multi,
                     },
+                    colors: color_sets
+                        .get(&row.r_id)
+                        .map(|(colors, _)| colors.clone())
+                        .unwrap_or_default(),
+                    gate_colors: color_sets
+                        .get(&row.r_id)
+                        .map(|(_, gate_colors)| gate_colors.clone())
+                        .unwrap_or_default(),
+                    remaining_gates: {
+                        let mut gates = row.remaining_gates;
+                        crate::model::gate::sort_gates(&mut gates);
+                        gates
+                    },
                 },
             },
         )
@@ -431,12 +568,14 @@ pub async fn get_all_kit_parts_for_kit_with_requirements(
             kp.kit_id as "kp_kit_id!: i64",
             kp.sub_assembly_id as "kp_sub_assembly_id!: i64",
             kp.user_id as "kp_user_id!: i64",
+            kp.updated_by as kp_updated_by,
             (kp.created_at AT TIME ZONE 'UTC') as "kp_created_at!: chrono::NaiveDateTime",
             (kp.updated_at AT TIME ZONE 'UTC') as "kp_updated_at!: chrono::NaiveDateTime",
             sa.id as "sa_id!: i64",
             sa.name as sa_name,
             sa.kit_id as "sa_kit_id!: i64",
             sa.user_id as "sa_user_id!: i64",
+            sa.updated_by as sa_updated_by,
             (sa.created_at AT TIME ZONE 'UTC') as "sa_created_at!: chrono::NaiveDateTime",
             (sa.updated_at AT TIME ZONE 'UTC') as "sa_updated_at!: chrono::NaiveDateTime",
             COALESCE(
@@ -448,7 +587,8 @@ pub async fn get_all_kit_parts_for_kit_with_requirements(
                         'is_cut', kpr.is_cut,
                         'runner_id', kpr.runner_id,
                         'kit_part_id', kpr.kit_part_id,
-                        'user_id', kpr.user_id
+                        'user_id', kpr.user_id,
+                        'updated_by', kpr.updated_by
                     )
                     ORDER BY kpr.id
                 ) FILTER (WHERE kpr.id IS NOT NULL),
@@ -457,8 +597,13 @@ pub async fn get_all_kit_parts_for_kit_with_requirements(
         FROM kit_parts kp
         JOIN sub_assemblies sa ON sa.id = kp.sub_assembly_id
         LEFT JOIN kit_part_requirements kpr
-            ON kpr.kit_part_id = kp.id AND kpr.user_id = kp.user_id
-        WHERE kp.kit_id = $1 AND kp.user_id = $2
+            ON kpr.kit_part_id = kp.id AND kpr.deleted_at IS NULL
+        WHERE kp.kit_id = $1
+          AND kp.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kp.kit_id AND m.user_id = $2
+          )
         GROUP BY kp.id, sa.id
         "#,
         kit_id,
@@ -467,9 +612,17 @@ pub async fn get_all_kit_parts_for_kit_with_requirements(
     .fetch_all(pool)
     .await?;
 
+    let kit_part_ids: Vec<i64> = rows.iter().map(|row| row.kp_id).collect();
+    let mut paints = get_kit_part_paints(pool, &kit_part_ids).await?;
+    let mut recipes = get_kit_part_recipes(pool, &kit_part_ids).await?;
+    let mut stages = get_kit_part_stages(pool, &kit_part_ids).await?;
+
     let out = rows
         .into_iter()
         .map(|row| KitPartWithSubAssemblyAndRequirements {
+            paints: paints.remove(&row.kp_id).unwrap_or_default(),
+            recipes: recipes.remove(&row.kp_id).unwrap_or_default(),
+            stage: stages.remove(&row.kp_id),
             kit_part: KitPart {
                 id: row.kp_id,
                 code: row.kp_code,
@@ -477,6 +630,7 @@ pub async fn get_all_kit_parts_for_kit_with_requirements(
                 kit_id: row.kp_kit_id,
                 sub_assembly_id: row.kp_sub_assembly_id,
                 user_id: row.kp_user_id,
+                updated_by: row.kp_updated_by,
                 created_at: row.kp_created_at,
                 updated_at: row.kp_updated_at,
             },
@@ -485,13 +639,11 @@ pub async fn get_all_kit_parts_for_kit_with_requirements(
                 name: row.sa_name,
                 kit_id: row.sa_kit_id,
                 user_id: row.sa_user_id,
+                updated_by: row.sa_updated_by,
                 created_at: row.sa_created_at,
                 updated_at: row.sa_updated_at,
             },
-            requirements: match serde_json::from_value(row.reqs) {
-                Ok(v) => v,
-                Err(_) => Vec::new(),
-            },
+            requirements: serde_json::from_value(row.reqs).unwrap_or_default(),
         })
         .collect();
 
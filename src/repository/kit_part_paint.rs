@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use sqlx::{Error, PgPool};
+
+use crate::model::{
+    kit_part_paint::{
+        AttachPaintPayload, AttachRecipePayload, KitPaintPlan, KitPartPaint, KitPartRecipe,
+        PaintPlanEntry, PaintPlanPart, PaintRole, RecipePlanEntry,
+    },
+    paint::{PaintFinish, PaintType},
+};
+use crate::repository::reference::{
+    ensure_kit_writable, ensure_paint_for_kit, ensure_recipe_for_kit, get_kit_part_kit_id,
+    ReferenceError,
+};
+
+// paint ของหลาย kit part พร้อมกัน (ใช้เติม KitPartWithSubAssemblyAndRequirements)
+pub async fn get_kit_part_paints(
+    pool: &PgPool,
+    kit_part_ids: &[i64],
+) -> Result<HashMap<i64, Vec<KitPartPaint>>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            kpp.kit_part_id,
+            p.id as paint_id,
+            p.name,
+            p.brand,
+            p.code,
+            p.paint_type as "paint_type: PaintType",
+            p.finish as "finish: PaintFinish",
+            p.hex,
+            kpp.role as "role!: PaintRole",
+            kpp.position
+        FROM kit_part_paints kpp
+        JOIN paints p ON p.id = kpp.paint_id
+        WHERE kpp.kit_part_id = ANY($1)
+        ORDER BY kpp.kit_part_id, kpp.position, kpp.created_at, p.id
+        "#,
+        kit_part_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut paints: HashMap<i64, Vec<KitPartPaint>> = HashMap::new();
+    for row in rows {
+        paints
+            .entry(row.kit_part_id)
+            .or_default()
+            .push(KitPartPaint {
+                paint_id: row.paint_id,
+                name: row.name,
+                brand: row.brand,
+                code: row.code,
+                paint_type: row.paint_type,
+                finish: row.finish,
+                hex: row.hex,
+                role: row.role,
+                position: row.position,
+            });
+    }
+    Ok(paints)
+}
+
+// GET /kit_parts/:id/paints (สมาชิก kit อ่านได้)
+pub async fn get_paints_for_kit_part(
+    pool: &PgPool,
+    kit_part_id: i64,
+    user_id: i64,
+) -> Result<Vec<KitPartPaint>, ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    get_kit_part_kit_id(&mut conn, user_id, kit_part_id).await?;
+    drop(conn);
+
+    Ok(get_kit_part_paints(pool, &[kit_part_id])
+        .await?
+        .remove(&kit_part_id)
+        .unwrap_or_default())
+}
+
+pub async fn attach_paint(
+    pool: &PgPool,
+    kit_part_id: i64,
+    user_id: i64,
+    payload: &AttachPaintPayload,
+) -> Result<(), ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    let kit_id = get_kit_part_kit_id(&mut conn, user_id, kit_part_id).await?;
+    ensure_kit_writable(&mut conn, user_id, kit_id).await?;
+    ensure_paint_for_kit(&mut conn, kit_id, payload.paint_id, user_id).await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO kit_part_paints (kit_part_id, paint_id, role, position, user_id)
+        VALUES (
+            $1, $2, $3,
+            COALESCE(
+                $4,
+                (SELECT COALESCE(MAX(position) + 1, 0) FROM kit_part_paints WHERE kit_part_id = $1)
+            ),
+            $5
+        )
+        ON CONFLICT (kit_part_id, paint_id, role)
+        DO UPDATE SET position = COALESCE($4, kit_part_paints.position)
+        "#,
+        kit_part_id,
+        payload.paint_id,
+        payload.role.as_str(),
+        payload.position,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// role = None → ถอด paint นี้ออกจาก part ทุก role
+pub async fn detach_paint(
+    pool: &PgPool,
+    kit_part_id: i64,
+    paint_id: i64,
+    role: Option<PaintRole>,
+    user_id: i64,
+) -> Result<(), ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    let kit_id = get_kit_part_kit_id(&mut conn, user_id, kit_part_id).await?;
+    ensure_kit_writable(&mut conn, user_id, kit_id).await?;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM kit_part_paints
+        WHERE kit_part_id = $1
+          AND paint_id = $2
+          AND ($3::TEXT IS NULL OR role = $3)
+        "#,
+        kit_part_id,
+        paint_id,
+        role.map(|r| r.as_str())
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ReferenceError::NotFound(format!(
+            "Paint {} is not attached to this kit part",
+            paint_id
+        )));
+    }
+    Ok(())
+}
+
+// --- RECIPES --- สูตรผสมสีผูกกับ part แบบเดียวกับ paint
+pub async fn get_kit_part_recipes(
+    pool: &PgPool,
+    kit_part_ids: &[i64],
+) -> Result<HashMap<i64, Vec<KitPartRecipe>>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            kpr.kit_part_id,
+            r.id as recipe_id,
+            r.name,
+            r.hex,
+            kpr.role as "role!: PaintRole",
+            kpr.position
+        FROM kit_part_recipes kpr
+        JOIN paint_recipes r ON r.id = kpr.recipe_id
+        WHERE kpr.kit_part_id = ANY($1)
+        ORDER BY kpr.kit_part_id, kpr.position, kpr.created_at, r.id
+        "#,
+        kit_part_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut recipes: HashMap<i64, Vec<KitPartRecipe>> = HashMap::new();
+    for row in rows {
+        recipes
+            .entry(row.kit_part_id)
+            .or_default()
+            .push(KitPartRecipe {
+                recipe_id: row.recipe_id,
+                name: row.name,
+                hex: row.hex,
+                role: row.role,
+                position: row.position,
+            });
+    }
+    Ok(recipes)
+}
+
+// GET /kit_parts/:id/recipes (สมาชิก kit อ่านได้)
+pub async fn get_recipes_for_kit_part(
+    pool: &PgPool,
+    kit_part_id: i64,
+    user_id: i64,
+) -> Result<Vec<KitPartRecipe>, ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    get_kit_part_kit_id(&mut conn, user_id, kit_part_id).await?;
+    drop(conn);
+
+    Ok(get_kit_part_recipes(pool, &[kit_part_id])
+        .await?
+        .remove(&kit_part_id)
+        .unwrap_or_default())
+}
+
+pub async fn attach_recipe(
+    pool: &PgPool,
+    kit_part_id: i64,
+    user_id: i64,
+    payload: &AttachRecipePayload,
+) -> Result<(), ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    let kit_id = get_kit_part_kit_id(&mut conn, user_id, kit_part_id).await?;
+    ensure_kit_writable(&mut conn, user_id, kit_id).await?;
+    ensure_recipe_for_kit(&mut conn, kit_id, payload.recipe_id).await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO kit_part_recipes (kit_part_id, recipe_id, role, position, user_id)
+        VALUES (
+            $1, $2, $3,
+            COALESCE(
+                $4,
+                (SELECT COALESCE(MAX(position) + 1, 0) FROM kit_part_recipes WHERE kit_part_id = $1)
+            ),
+            $5
+        )
+        ON CONFLICT (kit_part_id, recipe_id, role)
+        DO UPDATE SET position = COALESCE($4, kit_part_recipes.position)
+        "#,
+        kit_part_id,
+        payload.recipe_id,
+        payload.role.as_str(),
+        payload.position,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// role = None → ถอดสูตรนี้ออกจาก part ทุก role
+pub async fn detach_recipe(
+    pool: &PgPool,
+    kit_part_id: i64,
+    recipe_id: i64,
+    role: Option<PaintRole>,
+    user_id: i64,
+) -> Result<(), ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    let kit_id = get_kit_part_kit_id(&mut conn, user_id, kit_part_id).await?;
+    ensure_kit_writable(&mut conn, user_id, kit_id).await?;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM kit_part_recipes
+        WHERE kit_part_id = $1
+          AND recipe_id = $2
+          AND ($3::TEXT IS NULL OR role = $3)
+        "#,
+        kit_part_id,
+        recipe_id,
+        role.map(|r| r.as_str())
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ReferenceError::NotFound(format!(
+            "Recipe {} is not attached to this kit part",
+            recipe_id
+        )));
+    }
+    Ok(())
+}
+
+// part เดียวหลาย role นับครั้งเดียว (parts เรียงตาม part แล้ว)
+fn distinct_part_count(parts: &[PaintPlanPart]) -> i64 {
+    let mut part_ids: Vec<i64> = parts.iter().map(|p| p.kit_part_id).collect();
+    part_ids.dedup();
+    part_ids.len() as i64
+}
+
+// GET /kits/:id/paint_plan — จัดกลุ่ม part (ที่ไม่อยู่ในถังขยะ) ตาม paint และสูตรผสม
+pub async fn get_paint_plan(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+) -> Result<KitPaintPlan, Error> {
+    // ไม่ใช่สมาชิก / kit อยู่ในถังขยะ → RowNotFound
+    sqlx::query_scalar!(
+        r#"
+        SELECT k.id FROM kits k
+        WHERE k.id = $1
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = k.id AND m.user_id = $2
+          )
+        "#,
+        kit_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            p.id as paint_id,
+            p.name as paint_name,
+            p.brand,
+            p.code as paint_code,
+            p.paint_type as "paint_type: PaintType",
+            p.finish as "finish: PaintFinish",
+            p.hex,
+            kp.id as kit_part_id,
+            kp.code as part_code,
+            kp.is_cut,
+            sa.id as sub_assembly_id,
+            sa.name as sub_assembly_name,
+            kpp.role as "role!: PaintRole",
+            kpp.position
+        FROM kit_part_paints kpp
+        JOIN kit_parts kp ON kp.id = kpp.kit_part_id
+        JOIN sub_assemblies sa ON sa.id = kp.sub_assembly_id
+        JOIN paints p ON p.id = kpp.paint_id
+        WHERE kp.kit_id = $1
+          AND kp.deleted_at IS NULL
+        ORDER BY p.brand NULLS LAST, p.code NULLS LAST, p.name, p.id,
+                 sa.name, kp.code NULLS LAST, kp.id, kpp.position
+        "#,
+        kit_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut paints: Vec<PaintPlanEntry> = Vec::new();
+    for row in rows {
+        let part = PaintPlanPart {
+            kit_part_id: row.kit_part_id,
+            code: row.part_code,
+            sub_assembly_id: row.sub_assembly_id,
+            sub_assembly_name: row.sub_assembly_name,
+            is_cut: row.is_cut,
+            role: row.role,
+            position: row.position,
+        };
+        match paints.last_mut() {
+            Some(entry) if entry.paint_id == row.paint_id => entry.parts.push(part),
+            _ => paints.push(PaintPlanEntry {
+                paint_id: row.paint_id,
+                name: row.paint_name,
+                brand: row.brand,
+                code: row.paint_code,
+                paint_type: row.paint_type,
+                finish: row.finish,
+                hex: row.hex,
+                part_count: 0,
+                parts: vec![part],
+            }),
+        }
+    }
+    for entry in &mut paints {
+        entry.part_count = distinct_part_count(&entry.parts);
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            r.id as recipe_id,
+            r.name as recipe_name,
+            r.hex,
+            kp.id as kit_part_id,
+            kp.code as part_code,
+            kp.is_cut,
+            sa.id as sub_assembly_id,
+            sa.name as sub_assembly_name,
+            kpr.role as "role!: PaintRole",
+            kpr.position
+        FROM kit_part_recipes kpr
+        JOIN kit_parts kp ON kp.id = kpr.kit_part_id
+        JOIN sub_assemblies sa ON sa.id = kp.sub_assembly_id
+        JOIN paint_recipes r ON r.id = kpr.recipe_id
+        WHERE kp.kit_id = $1
+          AND kp.deleted_at IS NULL
+        ORDER BY r.name, r.id, sa.name, kp.code NULLS LAST, kp.id, kpr.position
+        "#,
+        kit_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut recipes: Vec<RecipePlanEntry> = Vec::new();
+    for row in rows {
+        let part = PaintPlanPart {
+            kit_part_id: row.kit_part_id,
+            code: row.part_code,
+            sub_assembly_id: row.sub_assembly_id,
+            sub_assembly_name: row.sub_assembly_name,
+            is_cut: row.is_cut,
+            role: row.role,
+            position: row.position,
+        };
+        match recipes.last_mut() {
+            Some(entry) if entry.recipe_id == row.recipe_id => entry.parts.push(part),
+            _ => recipes.push(RecipePlanEntry {
+                recipe_id: row.recipe_id,
+                name: row.recipe_name,
+                hex: row.hex,
+                part_count: 0,
+                parts: vec![part],
+            }),
+        }
+    }
+    for entry in &mut recipes {
+        entry.part_count = distinct_part_count(&entry.parts);
+    }
+
+    let unpainted_part_count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM kit_parts kp
+        WHERE kp.kit_id = $1
+          AND kp.deleted_at IS NULL
+          AND NOT EXISTS (SELECT 1 FROM kit_part_paints kpp WHERE kpp.kit_part_id = kp.id)
+          AND NOT EXISTS (SELECT 1 FROM kit_part_recipes kpr WHERE kpr.kit_part_id = kp.id)
+        "#,
+        kit_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(KitPaintPlan {
+        kit_id,
+        paints,
+        recipes,
+        unpainted_part_count,
+    })
+}
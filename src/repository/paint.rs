@@ -0,0 +1,571 @@
+use sqlx::{Error, PgConnection, PgPool};
+
+use crate::model::{
+    lab::Lab,
+    paint::{
+        CreatePaintPayload, Paint, PaintFinish, PaintQuery, PaintType, PaintUsage, PaintUsagePart,
+        PaintUsageRecipe, ShoppingList, ShoppingListItem, ShoppingListKit, StockStatus,
+        UpdatePaintPayload,
+    },
+};
+use crate::repository::paint_recipe::refresh_recipes_for_paint;
+
+// paint ของ user (แยกตาม user เหมือน colors)
+pub async fn get_paints(
+    pool: &PgPool,
+    user_id: i64,
+    query: &PaintQuery,
+) -> Result<Vec<Paint>, Error> {
+    sqlx::query_as!(
+        Paint,
+        r#"
+        SELECT
+            id,
+            name,
+            brand,
+            code,
+            paint_type as "paint_type: PaintType",
+            finish as "finish: PaintFinish",
+            hex,
+            quantity,
+            fill_level,
+            low_stock_threshold,
+            stock_status as "stock_status!: StockStatus",
+            catalog_id,
+            user_id,
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM paints
+        WHERE user_id = $1
+          AND ($2::TEXT IS NULL OR LOWER(brand) = LOWER($2))
+          AND ($3::TEXT IS NULL OR paint_type = $3)
+          AND ($4::TEXT IS NULL OR finish = $4)
+          AND ($5::TEXT IS NULL OR stock_status = $5)
+        ORDER BY brand NULLS LAST, code NULLS LAST, name
+        "#,
+        user_id,
+        query.brand,
+        query.paint_type.map(|t| t.as_str()),
+        query.finish.map(|f| f.as_str()),
+        query.stock_status.map(|s| s.as_str())
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_paint_by_id(pool: &PgPool, paint_id: i64, user_id: i64) -> Result<Paint, Error> {
+    sqlx::query_as!(
+        Paint,
+        r#"
+        SELECT
+            id,
+            name,
+            brand,
+            code,
+            paint_type as "paint_type: PaintType",
+            finish as "finish: PaintFinish",
+            hex,
+            quantity,
+            fill_level,
+            low_stock_threshold,
+            stock_status as "stock_status!: StockStatus",
+            catalog_id,
+            user_id,
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM paints
+        WHERE id = $1 AND user_id = $2
+        "#,
+        paint_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// hex ใน payload normalize แล้ว, lab คำนวณจาก hex นั้น (ไม่มี hex = ไม่มี lab)
+pub async fn create_paint(
+    pool: &PgPool,
+    user_id: i64,
+    payload: CreatePaintPayload,
+    lab: Option<Lab>,
+) -> Result<Paint, Error> {
+    sqlx::query_as!(
+        Paint,
+        r#"
+        INSERT INTO paints (
+            name, brand, code, paint_type, finish, hex, user_id, lab_l, lab_a, lab_b,
+            quantity, fill_level, low_stock_threshold
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE($11, 0), $12, COALESCE($13, 25))
+        RETURNING
+            id,
+            name,
+            brand,
+            code,
+            paint_type as "paint_type: PaintType",
+            finish as "finish: PaintFinish",
+            hex,
+            quantity,
+            fill_level,
+            low_stock_threshold,
+            stock_status as "stock_status!: StockStatus",
+            catalog_id,
+            user_id,
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        "#,
+        payload.name,
+        payload.brand,
+        payload.code,
+        payload.paint_type.map(|t| t.as_str()),
+        payload.finish.map(|f| f.as_str()),
+        payload.hex,
+        user_id,
+        lab.map(|lab| lab.l),
+        lab.map(|lab| lab.a),
+        lab.map(|lab| lab.b),
+        payload.quantity,
+        payload.fill_level,
+        payload.low_stock_threshold
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn update_paint(
+    pool: &PgPool,
+    paint_id: i64,
+    user_id: i64,
+    payload: UpdatePaintPayload,
+    lab: Option<Lab>, // 👈 มีเมื่อส่ง hex มาเท่านั้น
+) -> Result<Paint, Error> {
+    let mut tx = pool.begin().await?;
+    let paint = sqlx::query_as!(
+        Paint,
+        r#"
+        UPDATE paints
+        SET
+            name = COALESCE($1, name),
+            brand = COALESCE($2, brand),
+            code = COALESCE($3, code),
+            paint_type = COALESCE($4, paint_type),
+            finish = COALESCE($5, finish),
+            hex = COALESCE($6, hex),
+            lab_l = COALESCE($9, lab_l),
+            lab_a = COALESCE($10, lab_a),
+            lab_b = COALESCE($11, lab_b),
+            quantity = COALESCE($12, quantity),
+            fill_level = COALESCE($13, fill_level),
+            low_stock_threshold = COALESCE($14, low_stock_threshold),
+            updated_at = NOW()
+        WHERE id = $7 AND user_id = $8
+        RETURNING
+            id,
+            name,
+            brand,
+            code,
+            paint_type as "paint_type: PaintType",
+            finish as "finish: PaintFinish",
+            hex,
+            quantity,
+            fill_level,
+            low_stock_threshold,
+            stock_status as "stock_status!: StockStatus",
+            catalog_id,
+            user_id,
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        "#,
+        payload.name,
+        payload.brand,
+        payload.code,
+        payload.paint_type.map(|t| t.as_str()),
+        payload.finish.map(|f| f.as_str()),
+        payload.hex,
+        paint_id,
+        user_id,
+        lab.map(|lab| lab.l),
+        lab.map(|lab| lab.a),
+        lab.map(|lab| lab.b),
+        payload.quantity,
+        payload.fill_level,
+        payload.low_stock_threshold
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // สูตรที่ใช้ paint นี้ต้องประมาณสีใหม่ (transaction เดียวกับการแก้ hex)
+    if lab.is_some() {
+        refresh_recipes_for_paint(&mut tx, paint.id).await?;
+    }
+    tx.commit().await?;
+    Ok(paint)
+}
+
+pub enum DeletePaintError {
+    NotFound,
+    InUse(PaintUsage),
+    InvalidTarget(String),
+    Db(Error),
+}
+
+impl From<Error> for DeletePaintError {
+    fn from(e: Error) -> Self {
+        DeletePaintError::Db(e)
+    }
+}
+
+// ห้ามลบ paint ที่ยังผูกกับ part หรืออยู่ในสูตรผสม → ตอบรายการที่ใช้อยู่กลับไป (เหมือน colors)
+// reassign_to = ย้ายทุกที่ที่ใช้อยู่ (รวม kit ที่เราออกไปแล้ว) ไป paint อื่นของเราก่อนลบ
+pub async fn delete_paint(
+    pool: &PgPool,
+    paint_id: i64,
+    user_id: i64,
+    reassign_to: Option<i64>,
+) -> Result<(), DeletePaintError> {
+    let mut tx = pool.begin().await?;
+
+    let exists = sqlx::query_scalar!(
+        "SELECT id FROM paints WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        paint_id,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    if exists.is_none() {
+        return Err(DeletePaintError::NotFound);
+    }
+
+    let usage = get_paint_usage(&mut tx, paint_id, user_id).await?;
+    if usage.is_used() {
+        let Some(target_id) = reassign_to else {
+            return Err(DeletePaintError::InUse(usage));
+        };
+        if target_id == paint_id {
+            return Err(DeletePaintError::InvalidTarget(
+                "reassign_to must be a different paint".to_string(),
+            ));
+        }
+        // ปลายทางต้องเป็น paint ของเราเอง (เจ้าของเดิมของทุกแถวที่ย้าย ไม่ต้องเช็คสมาชิก kit ซ้ำ)
+        let target = sqlx::query_scalar!(
+            "SELECT id FROM paints WHERE id = $1 AND user_id = $2",
+            target_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if target.is_none() {
+            return Err(DeletePaintError::InvalidTarget(format!(
+                "Paint {} not found",
+                target_id
+            )));
+        }
+
+        reassign_paint(&mut tx, paint_id, target_id).await?;
+    }
+
+    sqlx::query!("DELETE FROM paints WHERE id = $1", paint_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// ย้าย kit_part_paints + ส่วนผสมในสูตรจาก paint หนึ่งไปอีก paint
+// part / สูตรที่มีปลายทางอยู่แล้ว: รวมเป็นแถวเดียว (part: ลำดับที่มาก่อนชนะ, สูตร: รวม ratio)
+async fn reassign_paint(conn: &mut PgConnection, from_id: i64, to_id: i64) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+        UPDATE kit_part_paints target
+        SET position = LEAST(target.position, source.position)
+        FROM kit_part_paints source
+        WHERE source.kit_part_id = target.kit_part_id
+          AND source.role = target.role
+          AND source.paint_id = $1
+          AND target.paint_id = $2
+        "#,
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM kit_part_paints source
+        WHERE source.paint_id = $1
+          AND EXISTS (
+              SELECT 1 FROM kit_part_paints target
+              WHERE target.kit_part_id = source.kit_part_id
+                AND target.role = source.role
+                AND target.paint_id = $2
+          )
+        "#,
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE kit_part_paints SET paint_id = $2 WHERE paint_id = $1",
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE paint_recipe_components target
+        SET ratio = target.ratio + source.ratio,
+            position = LEAST(target.position, source.position)
+        FROM paint_recipe_components source
+        WHERE source.recipe_id = target.recipe_id
+          AND source.paint_id = $1
+          AND target.paint_id = $2
+        "#,
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM paint_recipe_components source
+        WHERE source.paint_id = $1
+          AND EXISTS (
+              SELECT 1 FROM paint_recipe_components target
+              WHERE target.recipe_id = source.recipe_id AND target.paint_id = $2
+          )
+        "#,
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE paint_recipe_components SET paint_id = $2 WHERE paint_id = $1",
+        from_id,
+        to_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // ส่วนผสมเปลี่ยน → สีของสูตรต้องประมาณใหม่
+    refresh_recipes_for_paint(conn, to_id).await
+}
+
+// part ที่ใช้ paint นี้ (รวมที่อยู่ในถังขยะ) + สูตรผสมที่มี paint นี้เป็นส่วนผสม
+// แสดงรายละเอียดเฉพาะ kit ที่ viewer เป็นสมาชิก ที่เหลือนับรวมไว้ใน other_*
+pub async fn get_paint_usage(
+    conn: &mut PgConnection,
+    paint_id: i64,
+    viewer_id: i64,
+) -> Result<PaintUsage, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            kp.id as "kit_part_id!: i64",
+            kp.code,
+            k.id as "kit_id!: i64",
+            k.name as "kit_name!: String",
+            ARRAY_AGG(kpp.role ORDER BY kpp.position, kpp.role) as "roles!: Vec<String>",
+            (kp.deleted_at IS NOT NULL OR k.deleted_at IS NOT NULL) as "in_trash!: bool",
+            EXISTS (
+                SELECT 1 FROM kit_members m
+                WHERE m.kit_id = k.id AND m.user_id = $2
+            ) as "is_member!: bool"
+        FROM kit_part_paints kpp
+        JOIN kit_parts kp ON kp.id = kpp.kit_part_id
+        JOIN kits k ON k.id = kp.kit_id
+        WHERE kpp.paint_id = $1
+        GROUP BY kp.id, k.id
+        ORDER BY k.name, k.id, kp.code, kp.id
+        "#,
+        paint_id,
+        viewer_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let recipes = sqlx::query_as!(
+        PaintUsageRecipe,
+        r#"
+        SELECT r.id as "recipe_id!: i64", r.name
+        FROM paint_recipe_components rc
+        JOIN paint_recipes r ON r.id = rc.recipe_id
+        WHERE rc.paint_id = $1
+        ORDER BY r.name, r.id
+        "#,
+        paint_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut parts = Vec::new();
+    let mut other_kit_ids = Vec::new();
+    let mut other_part_count = 0;
+    for row in rows {
+        if row.is_member {
+            parts.push(PaintUsagePart {
+                kit_part_id: row.kit_part_id,
+                code: row.code,
+                kit_id: row.kit_id,
+                kit_name: row.kit_name,
+                roles: row.roles,
+                in_trash: row.in_trash,
+            });
+        } else {
+            other_kit_ids.push(row.kit_id);
+            other_part_count += 1;
+        }
+    }
+
+    let mut kit_ids: Vec<i64> = parts.iter().map(|p| p.kit_id).collect();
+    kit_ids.dedup(); // 👈 เรียงตาม kit แล้ว
+    other_kit_ids.dedup();
+    Ok(PaintUsage {
+        paint_id,
+        kit_count: kit_ids.len() as i64,
+        part_count: parts.len() as i64,
+        parts,
+        other_kit_count: other_kit_ids.len() as i64,
+        other_part_count,
+        recipes,
+    })
+}
+
+// GET /paints/:id/usage
+pub async fn get_paint_usage_for_user(
+    pool: &PgPool,
+    paint_id: i64,
+    user_id: i64,
+) -> Result<PaintUsage, Error> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query_scalar!(
+        "SELECT id FROM paints WHERE id = $1 AND user_id = $2",
+        paint_id,
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    get_paint_usage(&mut conn, paint_id, user_id).await
+}
+
+// --- SHOPPING LIST ---
+// paint ของ user ที่ผูกกับ part ใน kit (ตรง ๆ หรือผ่านสูตรผสม) แล้วสต็อก low / out (inventory แยกตาม user เหมือน paint)
+// kit_id = None → ทุก kit ที่เป็นสมาชิกและยัง pending / in_progress
+pub async fn get_shopping_list(
+    pool: &PgPool,
+    user_id: i64,
+    kit_id: Option<i64>,
+) -> Result<ShoppingList, Error> {
+    if let Some(kit_id) = kit_id {
+        // ไม่ใช่สมาชิก / kit อยู่ในถังขยะ → RowNotFound
+        sqlx::query_scalar!(
+            r#"
+            SELECT k.id FROM kits k
+            WHERE k.id = $1
+              AND k.deleted_at IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM kit_members m
+                  WHERE m.kit_id = k.id AND m.user_id = $2
+              )
+            "#,
+            kit_id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            p.id as paint_id,
+            p.name,
+            p.brand,
+            p.code,
+            p.hex,
+            p.quantity,
+            p.fill_level,
+            p.low_stock_threshold,
+            p.stock_status as "stock_status!: StockStatus",
+            k.id as kit_id,
+            k.name as kit_name,
+            COUNT(DISTINCT kp.id) as "part_count!: i64"
+        FROM (
+            SELECT kit_part_id, paint_id FROM kit_part_paints
+            UNION
+            -- 👈 paint ที่ใช้ผ่านสูตรผสมสีก็ต้องซื้อเหมือนกัน
+            SELECT kpr.kit_part_id, rc.paint_id
+            FROM kit_part_recipes kpr
+            JOIN paint_recipe_components rc ON rc.recipe_id = kpr.recipe_id
+        ) kpp
+        JOIN kit_parts kp ON kp.id = kpp.kit_part_id
+        JOIN kits k ON k.id = kp.kit_id
+        JOIN paints p ON p.id = kpp.paint_id
+        WHERE p.user_id = $1
+          AND kp.deleted_at IS NULL
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = k.id AND m.user_id = $1
+          )
+          AND (
+              ($2::BIGINT IS NULL AND k.status IN ('pending', 'in_progress'))
+              OR k.id = $2
+          )
+        GROUP BY p.id, k.id
+        ORDER BY p.brand NULLS LAST, p.code NULLS LAST, p.name, p.id, k.name, k.id
+        "#,
+        user_id,
+        kit_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut paints: Vec<ShoppingListItem> = Vec::new();
+    for row in rows {
+        let kit = ShoppingListKit {
+            kit_id: row.kit_id,
+            kit_name: row.kit_name,
+            part_count: row.part_count,
+        };
+        match paints.last_mut() {
+            Some(item) if item.paint_id == row.paint_id => item.kits.push(kit),
+            _ => paints.push(ShoppingListItem {
+                paint_id: row.paint_id,
+                name: row.name,
+                brand: row.brand,
+                code: row.code,
+                hex: row.hex,
+                quantity: row.quantity,
+                fill_level: row.fill_level,
+                low_stock_threshold: row.low_stock_threshold,
+                stock_status: row.stock_status,
+                kits: vec![kit],
+            }),
+        }
+    }
+
+    let paint_count = paints.len();
+    // หมดก่อน แล้วค่อยใกล้หมด (ลำดับเดิมภายในกลุ่ม)
+    let mut items: Vec<ShoppingListItem> = paints
+        .into_iter()
+        .filter(|item| item.stock_status != StockStatus::Ok)
+        .collect();
+    items.sort_by_key(|item| item.stock_status != StockStatus::Out);
+
+    Ok(ShoppingList {
+        kit_id,
+        paint_count,
+        items,
+    })
+}
@@ -0,0 +1,333 @@
+use sqlx::{Error, PgPool};
+
+use crate::model::{
+    lab::{ciede2000, hex_to_lab, Lab},
+    paint::{Paint, PaintType, StockStatus},
+    paint_catalog::{
+        CatalogEntry, CatalogKey, CatalogPaint, CatalogQuery, Substitute, SubstituteSource,
+    },
+};
+use crate::repository::paint::get_paint_by_id;
+
+// GET /paint_catalog (ทุก user เห็นเหมือนกัน)
+pub async fn get_catalog_paints(
+    pool: &PgPool,
+    query: &CatalogQuery,
+) -> Result<Vec<CatalogPaint>, Error> {
+    sqlx::query_as!(
+        CatalogPaint,
+        r#"
+        SELECT
+            id,
+            brand,
+            line,
+            code,
+            name,
+            hex,
+            paint_type as "paint_type: PaintType",
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM paint_catalog
+        WHERE ($1::TEXT IS NULL OR LOWER(brand) = LOWER($1))
+          AND ($2::TEXT IS NULL OR LOWER(line) = LOWER($2))
+          AND ($3::TEXT IS NULL OR paint_type = $3)
+          AND ($4::TEXT IS NULL OR code ILIKE '%' || $4 || '%' OR name ILIKE '%' || $4 || '%')
+        ORDER BY brand, line NULLS FIRST, code
+        "#,
+        query.brand,
+        query.line,
+        query.paint_type.map(|t| t.as_str()),
+        query.q
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_catalog_paint_by_id(pool: &PgPool, id: i64) -> Result<CatalogPaint, Error> {
+    sqlx::query_as!(
+        CatalogPaint,
+        r#"
+        SELECT
+            id,
+            brand,
+            line,
+            code,
+            name,
+            hex,
+            paint_type as "paint_type: PaintType",
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM paint_catalog
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// import ทั้งชุดใน transaction เดียว: upsert ตาม (brand, code) แบบไม่สนตัวพิมพ์
+// (ตัวพิมพ์ใน DB เปลี่ยนตามไฟล์ล่าสุด) แล้วผูก equivalents ที่อ้างด้วย key lowercase
+// คืน id ตามลำดับของ entries
+pub async fn import_catalog_paints(
+    pool: &PgPool,
+    entries: &[(CatalogEntry, Option<PaintType>)],
+    equivalents: &[(CatalogKey, CatalogKey)],
+) -> Result<Vec<i64>, Error> {
+    let mut tx = pool.begin().await?;
+    let mut ids = Vec::with_capacity(entries.len());
+    for (entry, paint_type) in entries {
+        let lab = entry.hex.as_deref().and_then(hex_to_lab);
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO paint_catalog (brand, line, code, name, hex, paint_type, lab_l, lab_a, lab_b)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (LOWER(brand), LOWER(code)) DO UPDATE
+            SET
+                brand = EXCLUDED.brand,
+                line = EXCLUDED.line,
+                code = EXCLUDED.code,
+                name = EXCLUDED.name,
+                hex = EXCLUDED.hex,
+                paint_type = EXCLUDED.paint_type,
+                lab_l = EXCLUDED.lab_l,
+                lab_a = EXCLUDED.lab_a,
+                lab_b = EXCLUDED.lab_b,
+                updated_at = NOW()
+            RETURNING id
+            "#,
+            entry.brand,
+            entry.line,
+            entry.code,
+            entry.name,
+            entry.hex,
+            paint_type.map(|t| t.as_str()),
+            lab.map(|lab| lab.l),
+            lab.map(|lab| lab.a),
+            lab.map(|lab| lab.b)
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        ids.push(id);
+    }
+
+    if !equivalents.is_empty() {
+        let mut a_brands = Vec::with_capacity(equivalents.len());
+        let mut a_codes = Vec::with_capacity(equivalents.len());
+        let mut b_brands = Vec::with_capacity(equivalents.len());
+        let mut b_codes = Vec::with_capacity(equivalents.len());
+        for ((a_brand, a_code), (b_brand, b_code)) in equivalents {
+            a_brands.push(a_brand.clone());
+            a_codes.push(a_code.clone());
+            b_brands.push(b_brand.clone());
+            b_codes.push(b_code.clone());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO paint_catalog_equivalents (paint_a_id, paint_b_id)
+            SELECT DISTINCT LEAST(a.id, b.id), GREATEST(a.id, b.id)
+            FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::TEXT[])
+                AS pair(a_brand, a_code, b_brand, b_code)
+            JOIN paint_catalog a ON LOWER(a.brand) = pair.a_brand AND LOWER(a.code) = pair.a_code
+            JOIN paint_catalog b ON LOWER(b.brand) = pair.b_brand AND LOWER(b.code) = pair.b_code
+            WHERE a.id <> b.id
+            ON CONFLICT DO NOTHING
+            "#,
+            &a_brands,
+            &a_codes,
+            &b_brands,
+            &b_codes
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(ids)
+}
+
+// คู่ที่มีอยู่แล้วข้ามไป คืนจำนวนคู่ที่เพิ่มใหม่
+pub async fn link_equivalents(pool: &PgPool, pairs: &[(i64, i64)]) -> Result<u64, Error> {
+    let (a_ids, b_ids): (Vec<i64>, Vec<i64>) = pairs
+        .iter()
+        .filter(|(a, b)| a != b)
+        .map(|&(a, b)| (a.min(b), a.max(b)))
+        .unzip();
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO paint_catalog_equivalents (paint_a_id, paint_b_id)
+        SELECT a, b FROM UNNEST($1::BIGINT[], $2::BIGINT[]) AS pair(a, b)
+        ON CONFLICT DO NOTHING
+        "#,
+        &a_ids,
+        &b_ids
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn unlink_equivalent(pool: &PgPool, paint_id: i64, other_id: i64) -> Result<(), Error> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM paint_catalog_equivalents
+        WHERE paint_a_id = LEAST($1::BIGINT, $2::BIGINT)
+          AND paint_b_id = GREATEST($1::BIGINT, $2::BIGINT)
+        "#,
+        paint_id,
+        other_id
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+    Ok(())
+}
+
+// paint ของ user ที่เพิ่มไปแล้วยังอยู่ (catalog_id → NULL)
+pub async fn delete_catalog_paint(pool: &PgPool, id: i64) -> Result<(), Error> {
+    let result = sqlx::query!("DELETE FROM paint_catalog WHERE id = $1", id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+    Ok(())
+}
+
+// คัดลอกรายการ catalog เป็น paint ของ user (ซ้ำ = unique violation → 409)
+pub async fn add_catalog_paint_to_inventory(
+    pool: &PgPool,
+    catalog_id: i64,
+    user_id: i64,
+    quantity: i32,
+) -> Result<Paint, Error> {
+    let paint_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO paints (
+            name, brand, code, paint_type, hex, lab_l, lab_a, lab_b, user_id, catalog_id, quantity
+        )
+        SELECT name, brand, code, paint_type, hex, lab_l, lab_a, lab_b, $2, id, $3
+        FROM paint_catalog
+        WHERE id = $1
+        RETURNING id
+        "#,
+        catalog_id,
+        user_id,
+        quantity
+    )
+    .fetch_one(pool)
+    .await?;
+
+    get_paint_by_id(pool, paint_id, user_id).await
+}
+
+// สีทดแทน: คู่ที่ admin map ไว้ก่อน แล้วตามด้วยสียี่ห้ออื่นที่ ΔE00 ≤ max_distance (ใกล้สุดก่อน)
+// catalog_id = None (paint ที่ไม่ได้มาจาก catalog) → ใช้ hex อย่างเดียว
+pub async fn get_substitutes(
+    pool: &PgPool,
+    user_id: i64,
+    catalog_id: Option<i64>,
+    brand: Option<&str>,
+    lab: Option<Lab>,
+    max_distance: f64,
+    limit: usize,
+) -> Result<Vec<Substitute>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            c.id,
+            c.brand,
+            c.line,
+            c.code,
+            c.name,
+            c.hex,
+            c.paint_type as "paint_type: PaintType",
+            (c.created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (c.updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime",
+            c.lab_l,
+            c.lab_a,
+            c.lab_b,
+            EXISTS (
+                SELECT 1 FROM paint_catalog_equivalents e
+                WHERE (e.paint_a_id = $2 AND e.paint_b_id = c.id)
+                   OR (e.paint_b_id = $2 AND e.paint_a_id = c.id)
+            ) as "is_mapped!: bool",
+            p.id as "owned_paint_id?",
+            p.stock_status as "stock_status?: StockStatus"
+        FROM paint_catalog c
+        LEFT JOIN paints p ON p.catalog_id = c.id AND p.user_id = $1
+        WHERE ($2::BIGINT IS NULL OR c.id <> $2)
+          AND (
+              EXISTS (
+                  SELECT 1 FROM paint_catalog_equivalents e
+                  WHERE (e.paint_a_id = $2 AND e.paint_b_id = c.id)
+                     OR (e.paint_b_id = $2 AND e.paint_a_id = c.id)
+              )
+              OR (
+                  $3::BOOL
+                  AND c.lab_l IS NOT NULL
+                  AND ($4::TEXT IS NULL OR LOWER(c.brand) <> LOWER($4))
+              )
+          )
+        "#,
+        user_id,
+        catalog_id,
+        lab.is_some(),
+        brand
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut mapped = Vec::new();
+    let mut similar = Vec::new();
+    for row in rows {
+        let row_lab = match (row.lab_l, row.lab_a, row.lab_b) {
+            (Some(l), Some(a), Some(b)) => Some(Lab { l, a, b }),
+            _ => None,
+        };
+        let distance = lab.zip(row_lab).map(|(from, to)| ciede2000(from, to));
+        let substitute = Substitute {
+            paint: CatalogPaint {
+                id: row.id,
+                brand: row.brand,
+                line: row.line,
+                code: row.code,
+                name: row.name,
+                hex: row.hex,
+                paint_type: row.paint_type,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            },
+            source: if row.is_mapped {
+                SubstituteSource::Mapping
+            } else {
+                SubstituteSource::Color
+            },
+            distance,
+            owned_paint_id: row.owned_paint_id,
+            stock_status: row.stock_status,
+        };
+        if row.is_mapped {
+            mapped.push(substitute);
+        } else if distance.is_some_and(|d| d <= max_distance) {
+            similar.push(substitute);
+        }
+    }
+
+    // ไม่มี distance ไปท้ายสุด
+    let by_distance = |a: &Substitute, b: &Substitute| {
+        a.distance
+            .unwrap_or(f64::MAX)
+            .total_cmp(&b.distance.unwrap_or(f64::MAX))
+    };
+    mapped.sort_by(by_distance);
+    similar.sort_by(by_distance);
+    similar.truncate(limit);
+    mapped.extend(similar);
+    Ok(mapped)
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use sqlx::{Error, PgConnection, PgPool};
+
+use crate::model::{
+    color::{ColorMatch, ColorMatchKind},
+    lab::{hex_to_lab, mix_hex, Lab},
+    paint_recipe::{
+        CreateRecipePayload, PaintRecipe, RecipeComponent, RecipeComponentInput,
+        UpdateRecipePayload,
+    },
+};
+
+#[derive(Debug)]
+pub enum RecipeError {
+    NotFound,
+    UnknownPaint(i64), // 👈 paint ไม่มีอยู่ / ไม่ใช่ของ user → 422
+    Db(Error),
+}
+
+impl From<Error> for RecipeError {
+    fn from(e: Error) -> Self {
+        RecipeError::Db(e)
+    }
+}
+
+// ส่วนผสมของหลายสูตรพร้อมกัน เรียงตาม position
+async fn get_recipe_components(
+    pool: &PgPool,
+    recipe_ids: &[i64],
+) -> Result<HashMap<i64, Vec<RecipeComponent>>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            rc.recipe_id,
+            p.id as paint_id,
+            p.name,
+            p.brand,
+            p.code,
+            p.hex,
+            rc.ratio
+        FROM paint_recipe_components rc
+        JOIN paints p ON p.id = rc.paint_id
+        WHERE rc.recipe_id = ANY($1)
+        ORDER BY rc.recipe_id, rc.position, p.id
+        "#,
+        recipe_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut components: HashMap<i64, Vec<RecipeComponent>> = HashMap::new();
+    for row in rows {
+        components
+            .entry(row.recipe_id)
+            .or_default()
+            .push(RecipeComponent {
+                paint_id: row.paint_id,
+                name: row.name,
+                brand: row.brand,
+                code: row.code,
+                hex: row.hex,
+                ratio: row.ratio,
+                percent: 0.0,
+            });
+    }
+    for list in components.values_mut() {
+        let total: f64 = list.iter().map(|c| c.ratio).sum();
+        for c in list.iter_mut() {
+            c.percent = (c.ratio / total * 1000.0).round() / 10.0; // 👈 ทศนิยม 1 ตำแหน่ง
+        }
+    }
+    Ok(components)
+}
+
+// สูตรของ user (recipe_id = None → ทั้งหมด)
+async fn fetch_recipes(
+    pool: &PgPool,
+    user_id: i64,
+    recipe_id: Option<i64>,
+) -> Result<Vec<PaintRecipe>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            name,
+            notes,
+            thinner_ratio,
+            hex,
+            user_id,
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM paint_recipes
+        WHERE user_id = $1
+          AND ($2::BIGINT IS NULL OR id = $2)
+        ORDER BY name, id
+        "#,
+        user_id,
+        recipe_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let ids: Vec<i64> = rows.iter().map(|row| row.id).collect();
+    let mut components = get_recipe_components(pool, &ids).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PaintRecipe {
+            components: components.remove(&row.id).unwrap_or_default(),
+            id: row.id,
+            name: row.name,
+            notes: row.notes,
+            thinner_ratio: row.thinner_ratio,
+            hex: row.hex,
+            user_id: row.user_id,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+        .collect())
+}
+
+pub async fn get_recipes(pool: &PgPool, user_id: i64) -> Result<Vec<PaintRecipe>, Error> {
+    fetch_recipes(pool, user_id, None).await
+}
+
+pub async fn get_recipe_by_id(
+    pool: &PgPool,
+    recipe_id: i64,
+    user_id: i64,
+) -> Result<PaintRecipe, Error> {
+    fetch_recipes(pool, user_id, Some(recipe_id))
+        .await?
+        .pop()
+        .ok_or(Error::RowNotFound)
+}
+
+// แทนที่ส่วนผสมทั้งชุด (paint ต้องเป็นของเจ้าของสูตร)
+async fn replace_components(
+    conn: &mut PgConnection,
+    recipe_id: i64,
+    user_id: i64,
+    components: &[RecipeComponentInput],
+) -> Result<(), RecipeError> {
+    let paint_ids: Vec<i64> = components.iter().map(|c| c.paint_id).collect();
+    let owned = sqlx::query_scalar!(
+        "SELECT id FROM paints WHERE id = ANY($1) AND user_id = $2",
+        &paint_ids,
+        user_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    if let Some(missing) = paint_ids.iter().find(|id| !owned.contains(id)) {
+        return Err(RecipeError::UnknownPaint(*missing));
+    }
+
+    sqlx::query!(
+        "DELETE FROM paint_recipe_components WHERE recipe_id = $1",
+        recipe_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let ratios: Vec<f64> = components.iter().map(|c| c.ratio).collect();
+    let positions: Vec<i32> = (0..components.len() as i32).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO paint_recipe_components (recipe_id, paint_id, ratio, position)
+        SELECT $1, paint_id, ratio, position
+        FROM UNNEST($2::BIGINT[], $3::FLOAT8[], $4::INT4[]) AS c(paint_id, ratio, position)
+        "#,
+        recipe_id,
+        &paint_ids,
+        &ratios,
+        &positions
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// คำนวณ hex / Lab ของสูตรใหม่จาก hex ของส่วนผสม
+async fn refresh_recipe_color(conn: &mut PgConnection, recipe_id: i64) -> Result<(), Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT p.hex as "hex!: String", rc.ratio
+        FROM paint_recipe_components rc
+        JOIN paints p ON p.id = rc.paint_id
+        WHERE rc.recipe_id = $1 AND p.hex IS NOT NULL
+        "#,
+        recipe_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let parts: Vec<(&str, f64)> = rows.iter().map(|r| (r.hex.as_str(), r.ratio)).collect();
+    let hex = mix_hex(&parts);
+    let lab = hex.as_deref().and_then(hex_to_lab);
+
+    sqlx::query!(
+        r#"
+        UPDATE paint_recipes
+        SET hex = $2, lab_l = $3, lab_a = $4, lab_b = $5, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        recipe_id,
+        hex,
+        lab.map(|lab| lab.l),
+        lab.map(|lab| lab.a),
+        lab.map(|lab| lab.b)
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// เรียกหลังแก้ hex ของ paint: สูตรที่ใช้ paint นี้ต้องประมาณสีใหม่
+pub async fn refresh_recipes_for_paint(
+    conn: &mut PgConnection,
+    paint_id: i64,
+) -> Result<(), Error> {
+    let recipe_ids = sqlx::query_scalar!(
+        "SELECT DISTINCT recipe_id FROM paint_recipe_components WHERE paint_id = $1",
+        paint_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    for recipe_id in recipe_ids {
+        refresh_recipe_color(conn, recipe_id).await?;
+    }
+    Ok(())
+}
+
+pub async fn create_recipe(
+    pool: &PgPool,
+    user_id: i64,
+    payload: &CreateRecipePayload,
+) -> Result<PaintRecipe, RecipeError> {
+    let mut tx = pool.begin().await?;
+
+    let recipe_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO paint_recipes (name, notes, thinner_ratio, user_id)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+        payload.name,
+        payload.notes,
+        payload.thinner_ratio,
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    replace_components(&mut tx, recipe_id, user_id, &payload.components).await?;
+    refresh_recipe_color(&mut tx, recipe_id).await?;
+    tx.commit().await?;
+
+    Ok(get_recipe_by_id(pool, recipe_id, user_id).await?)
+}
+
+pub async fn update_recipe(
+    pool: &PgPool,
+    recipe_id: i64,
+    user_id: i64,
+    payload: &UpdateRecipePayload,
+) -> Result<PaintRecipe, RecipeError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query_scalar!(
+        r#"
+        UPDATE paint_recipes
+        SET
+            name = COALESCE($3, name),
+            notes = COALESCE($4, notes),
+            thinner_ratio = COALESCE($5, thinner_ratio),
+            updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING id
+        "#,
+        recipe_id,
+        user_id,
+        payload.name,
+        payload.notes,
+        payload.thinner_ratio
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(RecipeError::NotFound)?;
+
+    if let Some(components) = &payload.components {
+        replace_components(&mut tx, recipe_id, user_id, components).await?;
+        refresh_recipe_color(&mut tx, recipe_id).await?;
+    }
+    tx.commit().await?;
+
+    Ok(get_recipe_by_id(pool, recipe_id, user_id).await?)
+}
+
+// ลบสูตร = ถอดออกจาก kit part ทุกชิ้นด้วย (FK cascade)
+pub async fn delete_recipe(pool: &PgPool, recipe_id: i64, user_id: i64) -> Result<(), Error> {
+    let result = sqlx::query!(
+        "DELETE FROM paint_recipes WHERE id = $1 AND user_id = $2",
+        recipe_id,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+
+    Ok(())
+}
+
+// --- SEARCH --- สูตรที่ประมาณสีได้แล้ว (ขึ้นคู่กับ paint ใน /colors/search)
+pub async fn get_recipe_candidates(pool: &PgPool, user_id: i64) -> Result<Vec<ColorMatch>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            name,
+            hex as "hex!: String",
+            lab_l as "lab_l!: f64",
+            lab_a as "lab_a!: f64",
+            lab_b as "lab_b!: f64"
+        FROM paint_recipes
+        WHERE user_id = $1 AND hex IS NOT NULL AND lab_l IS NOT NULL
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ColorMatch {
+            kind: ColorMatchKind::Recipe,
+            id: row.id,
+            name: row.name,
+            code: None,
+            brand: None,
+            hex: row.hex,
+            lab: Lab {
+                l: row.lab_l,
+                a: row.lab_a,
+                b: row.lab_b,
+            },
+            distance: 0.0,
+        })
+        .collect())
+}
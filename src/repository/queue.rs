@@ -0,0 +1,303 @@
+use sqlx::{Error, PgPool, Postgres, Transaction};
+
+use crate::model::{
+    kit::{Kit, KitGrade, KitStatus},
+    queue::{rank_between, AddQueueEntryPayload, KitQueueEntry, NextKit},
+};
+
+// แถวในคิวแบบย่อ ใช้คำนวณ rank ใหม่
+struct QueueSlot {
+    kit_id: i64,
+    rank: String,
+    pinned: bool,
+}
+
+// อ่านคิวทั้งหมดของ user ตามลำดับ พร้อม lock แถวไว้จนจบ transaction
+// (กันสอง request คำนวณ rank จากข้อมูลชุดเดียวกัน)
+async fn lock_queue(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i64,
+) -> Result<Vec<QueueSlot>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT q.kit_id as "kit_id!: i64", q.rank, q.pinned
+        FROM kit_queue_entries q
+        JOIN kits k ON k.id = q.kit_id
+        WHERE q.user_id = $1
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = q.kit_id AND m.user_id = $1
+          )
+        ORDER BY q.pinned DESC, q.rank, q.id
+        FOR UPDATE OF q
+        "#,
+        user_id
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| QueueSlot {
+            kit_id: row.kit_id,
+            rank: row.rank,
+            pinned: row.pinned,
+        })
+        .collect())
+}
+
+// rank สำหรับวางบนสุดของกลุ่ม (pinned หรือไม่ pinned)
+fn top_of_group(slots: &[QueueSlot], pinned: bool) -> String {
+    let first = slots.iter().find(|s| s.pinned == pinned);
+    rank_between(None, first.map(|s| s.rank.as_str()))
+}
+
+// rank สำหรับวางล่างสุดของกลุ่ม
+fn bottom_of_group(slots: &[QueueSlot], pinned: bool) -> String {
+    let last = slots.iter().rev().find(|s| s.pinned == pinned);
+    rank_between(last.map(|s| s.rank.as_str()), None)
+}
+
+async fn get_queue_entry(pool: &PgPool, user_id: i64, kit_id: i64) -> Result<KitQueueEntry, Error> {
+    get_queue(pool, user_id)
+        .await?
+        .into_iter()
+        .find(|entry| entry.kit_id == kit_id)
+        .ok_or(Error::RowNotFound)
+}
+
+// --- READ ---
+// ซ่อน kit ที่อยู่ในถังขยะหรือ user ไม่ได้เป็นสมาชิกแล้ว
+pub async fn get_queue(pool: &PgPool, user_id: i64) -> Result<Vec<KitQueueEntry>, Error> {
+    sqlx::query_as!(
+        KitQueueEntry,
+        r#"
+        SELECT
+            q.id as "id!: i64",
+            q.kit_id as "kit_id!: i64",
+            ROW_NUMBER() OVER (ORDER BY q.pinned DESC, q.rank, q.id) as "position!: i64",
+            q.rank::TEXT as "rank!: String",
+            q.pinned,
+            k.name,
+            k.grade as "grade: KitGrade",
+            k.status as "status: KitStatus",
+            (q.created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (q.updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM kit_queue_entries q
+        JOIN kits k ON k.id = q.kit_id
+        WHERE q.user_id = $1
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = q.kit_id AND m.user_id = $1
+          )
+        ORDER BY q.pinned DESC, q.rank, q.id
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// --- CREATE ---
+// เข้าท้ายคิว หรือบนสุดของกลุ่ม pinned (kit ที่ไม่ได้เป็นสมาชิก = RowNotFound)
+pub async fn add_to_queue(
+    pool: &PgPool,
+    user_id: i64,
+    payload: AddQueueEntryPayload,
+) -> Result<KitQueueEntry, Error> {
+    let mut tx = pool.begin().await?;
+    let slots = lock_queue(&mut tx, user_id).await?;
+
+    let rank = if payload.pinned {
+        top_of_group(&slots, true)
+    } else {
+        bottom_of_group(&slots, false)
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO kit_queue_entries (user_id, kit_id, rank, pinned)
+        SELECT $1, k.id, $3, $4
+        FROM kits k
+        WHERE k.id = $2
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = k.id AND m.user_id = $1
+          )
+        RETURNING id
+        "#,
+        user_id,
+        payload.kit_id,
+        rank,
+        payload.pinned
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    get_queue_entry(pool, user_id, payload.kit_id).await
+}
+
+// --- MOVE (drag & drop) ---
+// แก้ rank ของแถวเดียว: หา rank ระหว่างเพื่อนบ้านที่ตำแหน่งปลายทาง
+// ถ้าวางระหว่างรายการ pinned จะกลายเป็น pinned (และกลับกัน)
+pub async fn move_queue_entry(
+    pool: &PgPool,
+    user_id: i64,
+    kit_id: i64,
+    position: i64,
+) -> Result<KitQueueEntry, Error> {
+    let mut tx = pool.begin().await?;
+    let mut slots = lock_queue(&mut tx, user_id).await?;
+
+    let current = slots
+        .iter()
+        .position(|s| s.kit_id == kit_id)
+        .ok_or(Error::RowNotFound)?;
+    let moving = slots.remove(current);
+
+    // position เริ่มที่ 1; เกินช่วงให้ชิดหัว/ท้าย
+    let index = (position.max(1) as usize - 1).min(slots.len());
+    let prev = index.checked_sub(1).and_then(|i| slots.get(i));
+    let next = slots.get(index);
+
+    let pinned = match (prev, next) {
+        (Some(p), Some(n)) if p.pinned == n.pinned => p.pinned,
+        (Some(p), None) => p.pinned,
+        (None, Some(n)) => n.pinned,
+        // อยู่ตรงรอยต่อ pinned/ไม่ pinned หรือคิวว่าง: คงสถานะเดิม
+        _ => moving.pinned,
+    };
+    let prev_rank = prev.filter(|s| s.pinned == pinned).map(|s| s.rank.as_str());
+    let next_rank = next.filter(|s| s.pinned == pinned).map(|s| s.rank.as_str());
+    let rank = rank_between(prev_rank, next_rank);
+
+    sqlx::query!(
+        r#"
+        UPDATE kit_queue_entries
+        SET rank = $3, pinned = $4, updated_at = NOW()
+        WHERE user_id = $1 AND kit_id = $2
+        "#,
+        user_id,
+        kit_id,
+        rank,
+        pinned
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    get_queue_entry(pool, user_id, kit_id).await
+}
+
+// --- PIN / UNPIN ---
+// pin = ขึ้นบนสุดของคิว, unpin = ไปอยู่บนสุดของกลุ่มที่ไม่ pinned
+pub async fn set_queue_entry_pinned(
+    pool: &PgPool,
+    user_id: i64,
+    kit_id: i64,
+    pinned: bool,
+) -> Result<KitQueueEntry, Error> {
+    let mut tx = pool.begin().await?;
+    let mut slots = lock_queue(&mut tx, user_id).await?;
+
+    let current = slots
+        .iter()
+        .position(|s| s.kit_id == kit_id)
+        .ok_or(Error::RowNotFound)?;
+    slots.remove(current);
+    let rank = top_of_group(&slots, pinned);
+
+    sqlx::query!(
+        r#"
+        UPDATE kit_queue_entries
+        SET rank = $3, pinned = $4, updated_at = NOW()
+        WHERE user_id = $1 AND kit_id = $2
+        "#,
+        user_id,
+        kit_id,
+        rank,
+        pinned
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    get_queue_entry(pool, user_id, kit_id).await
+}
+
+// --- DELETE ---
+pub async fn remove_from_queue(pool: &PgPool, user_id: i64, kit_id: i64) -> Result<(), Error> {
+    let result = sqlx::query!(
+        "DELETE FROM kit_queue_entries WHERE user_id = $1 AND kit_id = $2",
+        user_id,
+        kit_id
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+    Ok(())
+}
+
+// --- NEXT ---
+// kit แรกในคิวที่ยังเป็น pending พร้อมจำนวน runner และ part (ไม่มี = RowNotFound)
+pub async fn get_next_kit(pool: &PgPool, user_id: i64) -> Result<NextKit, Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            k.id as "id!: i64",
+            k.name,
+            k.grade as "grade: KitGrade",
+            k.status as "status: KitStatus",
+            k.tags,
+            k.purchase_price,
+            k.user_id as "user_id!: i64",
+            k.updated_by,
+            (k.created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (k.updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime",
+            q.pinned,
+            (SELECT COUNT(*) FROM runners r
+             WHERE r.kit_id = k.id AND r.deleted_at IS NULL) as "runner_count!: i64",
+            (SELECT COUNT(*) FROM kit_parts kp
+             WHERE kp.kit_id = k.id AND kp.deleted_at IS NULL) as "part_count!: i64"
+        FROM kit_queue_entries q
+        JOIN kits k ON k.id = q.kit_id
+        WHERE q.user_id = $1
+          AND k.status = 'pending'
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = q.kit_id AND m.user_id = $1
+          )
+        ORDER BY q.pinned DESC, q.rank, q.id
+        LIMIT 1
+        "#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(NextKit {
+        kit: Kit {
+            id: row.id,
+            name: row.name,
+            grade: row.grade,
+            status: row.status,
+            tags: row.tags,
+            purchase_price: row.purchase_price,
+            user_id: row.user_id,
+            updated_by: row.updated_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        },
+        pinned: row.pinned,
+        runner_count: row.runner_count,
+        part_count: row.part_count,
+    })
+}
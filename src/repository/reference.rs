@@ -0,0 +1,280 @@
+use sqlx::{Error, PgConnection};
+
+// error ของการอ้างอิงข้ามตาราง (color / sub-assembly / runner) ตอนเขียนข้อมูล
+// DB มี composite FK กันไว้อีกชั้น ส่วนนี้มีไว้ตอบ error ที่อ่านรู้เรื่อง
+#[derive(Debug)]
+pub enum ReferenceError {
+    NotFound(String), // 👈 ไม่มีอยู่ / อยู่ในถังขยะ / ไม่ใช่สมาชิก kit ของมัน → 404
+    WrongKit(String), // 👈 มีอยู่และเห็นได้ แต่อยู่คนละ kit → 422
+    Db(Error),
+}
+
+impl From<Error> for ReferenceError {
+    fn from(e: Error) -> Self {
+        ReferenceError::Db(e)
+    }
+}
+
+// เช็คก่อนตรวจ reference อื่น เพื่อให้ kit ที่แก้ไม่ได้ตอบ "Kit not found" เสมอ
+pub async fn ensure_kit_writable(
+    conn: &mut PgConnection,
+    user_id: i64,
+    kit_id: i64,
+) -> Result<(), ReferenceError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT k.id as "id!: i64"
+        FROM kits k
+        JOIN kit_members m ON m.kit_id = k.id
+        WHERE k.id = $1
+          AND k.deleted_at IS NULL
+          AND m.user_id = $2
+          AND m.role IN ('owner', 'editor')
+        "#,
+        kit_id,
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|_| ())
+    .ok_or_else(|| ReferenceError::NotFound("Kit not found".to_string()))
+}
+
+// สีเป็นของ user แต่ละคน: ใช้ได้เฉพาะสีของสมาชิก kit นั้น หรือ preset กลาง (ไม่ใช่สีของ user อื่นที่เดา id มา)
+pub async fn ensure_colors_for_kit(
+    conn: &mut PgConnection,
+    kit_id: i64,
+    color_ids: &[i64],
+) -> Result<(), ReferenceError> {
+    let found = sqlx::query_scalar!(
+        r#"
+        SELECT c.id as "id!: i64"
+        FROM colors c
+        WHERE c.id = ANY($2)
+          AND (
+              c.user_id IS NULL
+              OR EXISTS (
+                  SELECT 1 FROM kit_members m
+                  WHERE m.kit_id = $1 AND m.user_id = c.user_id
+              )
+          )
+        "#,
+        kit_id,
+        color_ids
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let mut missing: Vec<i64> = color_ids
+        .iter()
+        .copied()
+        .filter(|id| !found.contains(id))
+        .collect();
+    missing.sort_unstable();
+    missing.dedup();
+    match missing.as_slice() {
+        [] => Ok(()),
+        [id] => Err(ReferenceError::NotFound(format!("Color {} not found", id))),
+        ids => Err(ReferenceError::NotFound(format!(
+            "Colors not found: {}",
+            join_ids(ids)
+        ))),
+    }
+}
+
+// paint เป็นของ user แต่ละคน: ผูกกับ part ได้เฉพาะ paint ของคนที่กำลังแก้ไข (ต้องเป็นสมาชิก kit ด้วย)
+// ไม่งั้น editor คนอื่นผูก paint ของเราไว้ใน kit ที่เราไม่เห็น แล้วเราลบ paint นั้นไม่ได้
+pub async fn ensure_paint_for_kit(
+    conn: &mut PgConnection,
+    kit_id: i64,
+    paint_id: i64,
+    user_id: i64,
+) -> Result<(), ReferenceError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT p.id
+        FROM paints p
+        WHERE p.id = $2
+          AND p.user_id = $3
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = $1 AND m.user_id = p.user_id
+          )
+        "#,
+        kit_id,
+        paint_id,
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|_| ())
+    .ok_or_else(|| ReferenceError::NotFound(format!("Paint {} not found", paint_id)))
+}
+
+// สูตรผสมสีใช้กับ kit ได้ถ้าเจ้าของสูตรเป็นสมาชิก kit นั้น (แบบเดียวกับ paint)
+pub async fn ensure_recipe_for_kit(
+    conn: &mut PgConnection,
+    kit_id: i64,
+    recipe_id: i64,
+) -> Result<(), ReferenceError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT r.id
+        FROM paint_recipes r
+        WHERE r.id = $2
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = $1 AND m.user_id = r.user_id
+          )
+        "#,
+        kit_id,
+        recipe_id
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|_| ())
+    .ok_or_else(|| ReferenceError::NotFound(format!("Recipe {} not found", recipe_id)))
+}
+
+// kit ใช้ workflow stage ของ owner เท่านั้น
+pub async fn ensure_stage_for_kit(
+    conn: &mut PgConnection,
+    kit_id: i64,
+    stage_id: i64,
+) -> Result<(), ReferenceError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT s.id
+        FROM workflow_stages s
+        JOIN kit_members m ON m.user_id = s.user_id AND m.role = 'owner'
+        WHERE s.id = $2 AND m.kit_id = $1
+        "#,
+        kit_id,
+        stage_id
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|_| ())
+    .ok_or_else(|| ReferenceError::NotFound(format!("Stage {} not found", stage_id)))
+}
+
+pub async fn ensure_sub_assembly_in_kit(
+    conn: &mut PgConnection,
+    user_id: i64,
+    kit_id: i64,
+    sub_assembly_id: i64,
+) -> Result<(), ReferenceError> {
+    let owner_kit = sqlx::query_scalar!(
+        r#"
+        SELECT sa.kit_id as "kit_id!: i64"
+        FROM sub_assemblies sa
+        WHERE sa.id = $1
+          AND sa.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = sa.kit_id AND m.user_id = $2
+          )
+        "#,
+        sub_assembly_id,
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    match owner_kit {
+        None => Err(ReferenceError::NotFound(format!(
+            "Sub-assembly {} not found",
+            sub_assembly_id
+        ))),
+        Some(owner_kit) if owner_kit != kit_id => Err(ReferenceError::WrongKit(format!(
+            "Sub-assembly {} belongs to a different kit",
+            sub_assembly_id
+        ))),
+        Some(_) => Ok(()),
+    }
+}
+
+// runner ทุกตัวต้องอยู่ใน kit_id เดียวกัน (เช่น kit ของ kit part ที่ requirement ชี้ไป)
+pub async fn ensure_runners_in_kit(
+    conn: &mut PgConnection,
+    user_id: i64,
+    kit_id: i64,
+    runner_ids: &[i64],
+) -> Result<(), ReferenceError> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT r.id as "id!: i64", r.kit_id as "kit_id!: i64"
+        FROM runners r
+        WHERE r.id = ANY($1)
+          AND r.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id AND m.user_id = $2
+          )
+        "#,
+        runner_ids,
+        user_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let mut missing: Vec<i64> = Vec::new();
+    let mut wrong_kit: Vec<i64> = Vec::new();
+    for &id in runner_ids {
+        match rows.iter().find(|row| row.id == id) {
+            None => missing.push(id),
+            Some(row) if row.kit_id != kit_id => wrong_kit.push(id),
+            Some(_) => {}
+        }
+    }
+    for ids in [&mut missing, &mut wrong_kit] {
+        ids.sort_unstable();
+        ids.dedup();
+    }
+
+    if !missing.is_empty() {
+        return Err(ReferenceError::NotFound(match missing.as_slice() {
+            [id] => format!("Runner {} not found", id),
+            ids => format!("Runners not found: {}", join_ids(ids)),
+        }));
+    }
+    if !wrong_kit.is_empty() {
+        return Err(ReferenceError::WrongKit(match wrong_kit.as_slice() {
+            [id] => format!("Runner {} belongs to a different kit", id),
+            ids => format!("Runners belong to a different kit: {}", join_ids(ids)),
+        }));
+    }
+    Ok(())
+}
+
+// kit ของ kit part (ใช้เป็น kit ปลายทางของ requirement)
+pub async fn get_kit_part_kit_id(
+    conn: &mut PgConnection,
+    user_id: i64,
+    kit_part_id: i64,
+) -> Result<i64, ReferenceError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT kp.kit_id as "kit_id!: i64"
+        FROM kit_parts kp
+        WHERE kp.id = $1
+          AND kp.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kp.kit_id AND m.user_id = $2
+          )
+        "#,
+        kit_part_id,
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ReferenceError::NotFound("Kit part not found".to_string()))
+}
+
+fn join_ids(ids: &[i64]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
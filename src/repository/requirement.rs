@@ -1,12 +1,18 @@
-use sqlx::{Error, PgPool, Row};
+use std::collections::HashMap;
+
+use sqlx::{Error, PgConnection, PgPool, Row};
 
 use crate::model::{
+    gate::parse_gate_expressions,
     kit_part::KitPartRequirement,
     requirement::{
         BulkCreateRequirementsPayload, BulkSyncRequirementsPayload, BulkUpdateRequirementsPayload,
-        CompareSyncRequirementsPayload, CreateKitPartRequirementPayload,
+        CompareSyncRequirementsPayload, CreateKitPartRequirementPayload, UpdateRequirementItem,
     },
+    runner::RunnerGates,
 };
+use crate::repository::reference::{ensure_runners_in_kit, get_kit_part_kit_id, ReferenceError};
+use crate::repository::runner::refresh_runners_is_used;
 
 // pub async fn update_kit_part_requirement(
 //     pool: &PgPool,
@@ -56,15 +62,326 @@ use crate::model::{
 //     })
 // }
 
+// error ของการเขียน requirement ที่ handler ต้องแยกตอบ
+#[derive(Debug)]
+pub enum RequirementWriteError {
+    InvalidGates(Vec<String>), // 👈 ข้อความ error ราย item เช่น "items[2]: runner A has no gate A99"
+    NotFound(String),          // 👈 kit part / runner ที่ไม่มีหรือมองไม่เห็น
+    WrongKit(String),          // 👈 runner อยู่คนละ kit กับ kit part
+    Db(Error),
+}
+
+impl From<Error> for RequirementWriteError {
+    fn from(e: Error) -> Self {
+        RequirementWriteError::Db(e)
+    }
+}
+
+impl From<ReferenceError> for RequirementWriteError {
+    fn from(e: ReferenceError) -> Self {
+        match e {
+            ReferenceError::NotFound(msg) => RequirementWriteError::NotFound(msg),
+            ReferenceError::WrongKit(msg) => RequirementWriteError::WrongKit(msg),
+            ReferenceError::Db(e) => RequirementWriteError::Db(e),
+        }
+    }
+}
+
+// runner ทุกตัวต้องอยู่ใน kit เดียวกับ kit part (คืน kit_id ไว้ใส่ตอน insert)
+async fn ensure_kit_part_runners(
+    conn: &mut PgConnection,
+    user_id: i64,
+    kit_part_id: i64,
+    runner_ids: &[i64],
+) -> Result<i64, ReferenceError> {
+    let kit_id = get_kit_part_kit_id(conn, user_id, kit_part_id).await?;
+    ensure_runners_in_kit(conn, user_id, kit_id, runner_ids).await?;
+    Ok(kit_id)
+}
+
+// update ที่ย้าย runner: runner ใหม่ต้องอยู่ใน kit เดียวกับ requirement นั้น
+// (requirement ที่หาไม่เจอปล่อยให้ UPDATE ตอบ RowNotFound เอง)
+async fn ensure_update_runners(
+    conn: &mut PgConnection,
+    user_id: i64,
+    items: &[UpdateRequirementItem],
+) -> Result<(), ReferenceError> {
+    let ids: Vec<i64> = items
+        .iter()
+        .filter(|item| item.runner_id.is_some())
+        .map(|item| item.id)
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT id as "id!: i64", kit_id as "kit_id!: i64"
+        FROM kit_part_requirements
+        WHERE id = ANY($1) AND deleted_at IS NULL
+        "#,
+        &ids
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut by_kit: HashMap<i64, Vec<i64>> = HashMap::new();
+    for item in items {
+        let (Some(runner_id), Some(row)) = (item.runner_id, rows.iter().find(|r| r.id == item.id))
+        else {
+            continue;
+        };
+        by_kit.entry(row.kit_id).or_default().push(runner_id);
+    }
+    for (kit_id, runner_ids) in by_kit {
+        ensure_runners_in_kit(conn, user_id, kit_id, &runner_ids).await?;
+    }
+    Ok(())
+}
+
+// gate ของหนึ่ง item ที่จะเขียน (label ใช้ในข้อความ error เช่น "items[2]")
+struct GateWrite<'a> {
+    label: String,
+    runner_id: i64,
+    gate: &'a mut Vec<String>,
+    qty: Option<i32>, // 👈 None = ไม่รู้ qty (ไม่ตรวจจำนวน)
+}
+
+// qty ต้องเท่ากับจำนวน gate (requirement ที่ไม่ระบุ gate ไม่ตรวจ) แบบเดียวกับ POST /requirements/gates/parse
+fn check_gate_qty(label: &str, qty: i32, gate_count: usize) -> Option<String> {
+    (gate_count > 0 && usize::try_from(qty).ok() != Some(gate_count)).then(|| {
+        format!(
+            "{}: qty {} does not match {} gate(s)",
+            label, qty, gate_count
+        )
+    })
+}
+
+// แปลง gate เป็นรูปแบบมาตรฐาน (แก้ในที่) แล้วตรวจกับ inventory ของ runner และตรวจ qty
+// runner ที่ยังไม่กำหนด inventory หรือหาไม่เจอ จะไม่ถูกตรวจ inventory ที่นี่
+async fn prepare_gates(
+    conn: &mut PgConnection,
+    items: Vec<GateWrite<'_>>,
+) -> Result<Vec<String>, Error> {
+    let runner_ids: Vec<i64> = items.iter().map(|item| item.runner_id).collect();
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: i64",
+            name,
+            gate_count,
+            gates as "gates: sqlx::types::Json<Vec<String>>"
+        FROM runners
+        WHERE id = ANY($1) AND deleted_at IS NULL
+        "#,
+        &runner_ids
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let inventories: HashMap<i64, RunnerGates> = rows
+        .into_iter()
+        .map(|row| {
+            let gates = RunnerGates::new(row.id, row.name, row.gate_count, row.gates.map(|g| g.0));
+            (row.id, gates)
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    for item in items {
+        let inventory = inventories.get(&item.runner_id);
+        let runner_name = inventory.map(|i| i.runner_name.as_str());
+
+        let parsed = match parse_gate_expressions(item.gate, runner_name) {
+            Ok(parsed) => parsed,
+            Err(msg) => {
+                errors.push(format!("{}: {}", item.label, msg));
+                continue;
+            }
+        };
+
+        if let Some(inventory) = inventory.filter(|i| i.is_defined()) {
+            let unknown: Vec<String> = parsed
+                .iter()
+                .filter(|g| !inventory.contains(&g.gate))
+                .map(|g| g.to_string())
+                .collect();
+            if !unknown.is_empty() {
+                errors.push(format!(
+                    "{}: runner {} has no gate {}",
+                    item.label,
+                    inventory.runner_name,
+                    unknown.join(", ")
+                ));
+                continue;
+            }
+        }
+        if let Some(msg) = item
+            .qty
+            .and_then(|qty| check_gate_qty(&item.label, qty, parsed.len()))
+        {
+            errors.push(msg);
+            continue;
+        }
+
+        *item.gate = parsed.iter().map(|g| g.to_string()).collect();
+    }
+    Ok(errors)
+}
+
+// runner_id, qty และจำนวน gate ปัจจุบันของ requirement (ใช้ตอน update โดยไม่ได้ส่งค่ามาครบ)
+struct CurrentRequirement {
+    runner_id: i64,
+    qty: i32,
+    gate_count: usize,
+}
+
+async fn get_current_requirements(
+    conn: &mut PgConnection,
+    ids: &[i64],
+) -> Result<HashMap<i64, CurrentRequirement>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: i64",
+            runner_id as "runner_id!: i64",
+            qty as "qty!: i32",
+            COALESCE(jsonb_array_length(gate), 0) as "gate_count!: i32"
+        FROM kit_part_requirements
+        WHERE id = ANY($1) AND deleted_at IS NULL
+        "#,
+        ids
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let current = CurrentRequirement {
+                runner_id: row.runner_id,
+                qty: row.qty,
+                gate_count: row.gate_count as usize,
+            };
+            (row.id, current)
+        })
+        .collect())
+}
+
+// ใช้กับ update item: runner_id / qty ที่ไม่ได้ส่งมาใช้ค่าเดิมของ requirement
+// ส่งแค่ qty มา = เทียบกับจำนวน gate เดิม
+async fn prepare_update_gates(
+    conn: &mut PgConnection,
+    label: &str,
+    items: &mut [UpdateRequirementItem],
+) -> Result<Vec<String>, Error> {
+    let ids: Vec<i64> = items.iter().map(|item| item.id).collect();
+    let current = get_current_requirements(conn, &ids).await?;
+
+    let mut errors = Vec::new();
+    let mut writes: Vec<GateWrite> = Vec::new();
+    for (index, item) in items.iter_mut().enumerate() {
+        let label = format!("{}[{}]", label, index);
+        let existing = current.get(&item.id);
+        match item.gate.as_mut() {
+            Some(gate) => {
+                let Some(runner_id) = item.runner_id.or(existing.map(|c| c.runner_id)) else {
+                    continue;
+                };
+                writes.push(GateWrite {
+                    label,
+                    runner_id,
+                    gate,
+                    qty: item.qty.or(existing.map(|c| c.qty)),
+                });
+            }
+            None => {
+                if let (Some(qty), Some(existing)) = (item.qty, existing) {
+                    errors.extend(check_gate_qty(&label, qty, existing.gate_count));
+                }
+            }
+        }
+    }
+    errors.extend(prepare_gates(conn, writes).await?);
+    Ok(errors)
+}
+
+// runner ทั้งหมดที่ requirement ของ kit part นี้ใช้อยู่ (ใช้ refresh is_used หลัง sync)
+async fn get_kit_part_runner_ids(
+    conn: &mut PgConnection,
+    kit_part_id: i64,
+) -> Result<Vec<i64>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT DISTINCT runner_id as "runner_id!: i64"
+        FROM kit_part_requirements
+        WHERE kit_part_id = $1 AND deleted_at IS NULL
+        "#,
+        kit_part_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| row.runner_id).collect())
+}
+
+// เช็คว่า user เป็น owner/editor ของ kit ที่ kit_part นี้อยู่ (ไม่ใช่ = RowNotFound)
+async fn ensure_kit_part_writable(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    kit_part_id: i64,
+    user_id: i64,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        SELECT 1
+        FROM kit_parts kp
+        JOIN kit_members m ON m.kit_id = kp.kit_id
+        WHERE kp.id = $1
+          AND kp.deleted_at IS NULL
+          AND m.user_id = $2
+          AND m.role IN ('owner', 'editor')
+        "#,
+    )
+    .bind(kit_part_id)
+    .bind(user_id)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(())
+}
+
 pub async fn create_kit_part_requirement(
     pool: &PgPool,
     user_id: i64,
-    payload: CreateKitPartRequirementPayload,
-) -> Result<KitPartRequirement, Error> {
+    mut payload: CreateKitPartRequirementPayload,
+) -> Result<KitPartRequirement, RequirementWriteError> {
+    let mut tx = pool.begin().await?;
+    let kit_id =
+        ensure_kit_part_runners(&mut tx, user_id, payload.kit_part_id, &[payload.runner_id])
+            .await?;
+    let write = GateWrite {
+        label: "gate".to_string(),
+        runner_id: payload.runner_id,
+        gate: &mut payload.gate,
+        qty: Some(payload.qty),
+    };
+    let errors = prepare_gates(&mut tx, vec![write]).await?;
+    if !errors.is_empty() {
+        return Err(RequirementWriteError::InvalidGates(errors));
+    }
+
     let row = sqlx::query!(
         r#"
-        INSERT INTO kit_part_requirements (gate, qty, is_cut, runner_id, kit_part_id, user_id)
-        VALUES ($1::JSONB, $2, $3, $4, $5, $6)
+        INSERT INTO kit_part_requirements (gate, qty, is_cut, runner_id, kit_part_id, kit_id, user_id, updated_by)
+        SELECT $1::JSONB, $2, $3, $4, $5, $7, $6, $6
+        WHERE EXISTS (
+            SELECT 1 FROM kit_parts kp
+            JOIN kit_members m ON m.kit_id = kp.kit_id
+            WHERE kp.id = $5
+              AND kp.deleted_at IS NULL
+              AND m.user_id = $6
+              AND m.role IN ('owner', 'editor')
+        )
         RETURNING
             id as "id!: i64",
             gate as "gate: sqlx::types::Json<Vec<String>>",
@@ -72,17 +389,21 @@ pub async fn create_kit_part_requirement(
             is_cut,
             runner_id as "runner_id!: i64",
             kit_part_id as "kit_part_id!: i64",
-            user_id as "user_id!: i64"
+            user_id as "user_id!: i64",
+            updated_by
         "#,
         serde_json::json!(payload.gate),
         payload.qty,
         false,
         payload.runner_id,
         payload.kit_part_id,
-        user_id
+        user_id,
+        kit_id
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await?;
+    refresh_runners_is_used(&mut tx, &[row.runner_id]).await?;
+    tx.commit().await?;
 
     Ok(KitPartRequirement {
         id: row.id,
@@ -92,22 +413,44 @@ pub async fn create_kit_part_requirement(
         runner_id: row.runner_id,
         kit_part_id: row.kit_part_id,
         user_id: row.user_id,
+        updated_by: row.updated_by,
     })
 }
 
 pub async fn bulk_create_requirements(
     pool: &PgPool,
     user_id: i64,
-    payload: BulkCreateRequirementsPayload,
-) -> Result<Vec<KitPartRequirement>, Error> {
+    mut payload: BulkCreateRequirementsPayload,
+) -> Result<Vec<KitPartRequirement>, RequirementWriteError> {
     let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = pool.begin().await?;
+    ensure_kit_part_writable(&mut tx, payload.kit_part_id, user_id).await?;
+    let item_runner_ids: Vec<i64> = payload.items.iter().map(|item| item.runner_id).collect();
+    let kit_id =
+        ensure_kit_part_runners(&mut tx, user_id, payload.kit_part_id, &item_runner_ids).await?;
+
+    let writes: Vec<GateWrite> = payload
+        .items
+        .iter_mut()
+        .enumerate()
+        .map(|(index, item)| GateWrite {
+            label: format!("items[{}]", index),
+            runner_id: item.runner_id,
+            gate: &mut item.gate,
+            qty: Some(item.qty),
+        })
+        .collect();
+    let errors = prepare_gates(&mut tx, writes).await?;
+    if !errors.is_empty() {
+        return Err(RequirementWriteError::InvalidGates(errors));
+    }
+
     let mut out = Vec::with_capacity(payload.items.len());
 
     for item in payload.items {
         let row = sqlx::query(
             r#"
-            INSERT INTO kit_part_requirements (gate, qty, is_cut, runner_id, kit_part_id, user_id)
-            VALUES ($1::JSONB, $2, $3, $4, $5, $6)
+            INSERT INTO kit_part_requirements (gate, qty, is_cut, cut_at, runner_id, kit_part_id, kit_id, user_id, updated_by)
+            VALUES ($1::JSONB, $2, $3, CASE WHEN $3 THEN NOW() END, $4, $5, $7, $6, $6)
             RETURNING
                 id,
                 (gate)::TEXT AS gate_text,
@@ -115,7 +458,8 @@ pub async fn bulk_create_requirements(
                 is_cut,
                 runner_id,
                 kit_part_id,
-                user_id
+                user_id,
+                updated_by
             "#,
         )
         .bind(serde_json::json!(item.gate))
@@ -124,6 +468,7 @@ pub async fn bulk_create_requirements(
         .bind(item.runner_id)
         .bind(payload.kit_part_id)
         .bind(user_id)
+        .bind(kit_id)
         .fetch_one(&mut *tx)
         .await?;
 
@@ -134,6 +479,7 @@ pub async fn bulk_create_requirements(
         let runner_id_val: i64 = row.try_get("runner_id")?;
         let kit_part_id_val: i64 = row.try_get("kit_part_id")?;
         let user_id_val: i64 = row.try_get("user_id")?;
+        let updated_by_val: Option<i64> = row.try_get("updated_by")?;
         let gate_vec: Vec<String> = serde_json::from_str(&gate_text).unwrap_or_default();
 
         out.push(KitPartRequirement {
@@ -144,9 +490,13 @@ pub async fn bulk_create_requirements(
             runner_id: runner_id_val,
             kit_part_id: kit_part_id_val,
             user_id: user_id_val,
+            updated_by: updated_by_val,
         });
     }
 
+    let runner_ids: Vec<i64> = out.iter().map(|req| req.runner_id).collect();
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
     tx.commit().await?;
     Ok(out)
 }
@@ -154,9 +504,24 @@ pub async fn bulk_create_requirements(
 pub async fn bulk_update_requirements(
     pool: &PgPool,
     user_id: i64,
-    payload: BulkUpdateRequirementsPayload,
-) -> Result<Vec<KitPartRequirement>, Error> {
+    mut payload: BulkUpdateRequirementsPayload,
+) -> Result<Vec<KitPartRequirement>, RequirementWriteError> {
     let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = pool.begin().await?;
+    ensure_update_runners(&mut tx, user_id, &payload.items).await?;
+
+    let errors = prepare_update_gates(&mut tx, "items", &mut payload.items).await?;
+    if !errors.is_empty() {
+        return Err(RequirementWriteError::InvalidGates(errors));
+    }
+
+    // runner เดิมของแต่ละ requirement (กรณีย้ายไป runner อื่น ต้อง refresh ทั้งสองฝั่ง)
+    let ids: Vec<i64> = payload.items.iter().map(|item| item.id).collect();
+    let mut runner_ids: Vec<i64> = get_current_requirements(&mut tx, &ids)
+        .await?
+        .into_values()
+        .map(|current| current.runner_id)
+        .collect();
+
     let mut out = Vec::with_capacity(payload.items.len());
 
     for item in payload.items {
@@ -169,8 +534,21 @@ pub async fn bulk_update_requirements(
                 gate = COALESCE($1::JSONB, gate),
                 qty = COALESCE($2, qty),
                 is_cut = COALESCE($3, is_cut),
-                runner_id = COALESCE($4, runner_id)
-            WHERE id = $5 AND user_id = $6
+                cut_at = CASE
+                    WHEN COALESCE($3, is_cut) THEN COALESCE(cut_at, NOW())
+                    ELSE NULL
+                END,
+                runner_id = COALESCE($4, runner_id),
+                updated_by = $6
+            WHERE id = $5
+              AND deleted_at IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM kit_parts kp
+                  JOIN kit_members m ON m.kit_id = kp.kit_id
+                  WHERE kp.id = kit_part_requirements.kit_part_id
+                    AND m.user_id = $6
+                    AND m.role IN ('owner', 'editor')
+              )
             RETURNING
                 id,
                 (gate)::TEXT AS gate_text,
@@ -178,7 +556,8 @@ pub async fn bulk_update_requirements(
                 is_cut,
                 runner_id,
                 kit_part_id,
-                user_id
+                user_id,
+                updated_by
             "#,
         )
         .bind(gate_json_opt)
@@ -197,6 +576,7 @@ pub async fn bulk_update_requirements(
         let runner_id_val: i64 = row.try_get("runner_id")?;
         let kit_part_id_val: i64 = row.try_get("kit_part_id")?;
         let user_id_val: i64 = row.try_get("user_id")?;
+        let updated_by_val: Option<i64> = row.try_get("updated_by")?;
         let gate_vec: Vec<String> = serde_json::from_str(&gate_text).unwrap_or_default();
 
         out.push(KitPartRequirement {
@@ -207,9 +587,13 @@ pub async fn bulk_update_requirements(
             runner_id: runner_id_val,
             kit_part_id: kit_part_id_val,
             user_id: user_id_val,
+            updated_by: updated_by_val,
         });
     }
 
+    runner_ids.extend(out.iter().map(|req| req.runner_id));
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
     tx.commit().await?;
     Ok(out)
 }
@@ -217,16 +601,48 @@ pub async fn bulk_update_requirements(
 pub async fn bulk_sync_requirements(
     pool: &PgPool,
     user_id: i64,
-    payload: BulkSyncRequirementsPayload,
-) -> Result<Vec<KitPartRequirement>, Error> {
+    mut payload: BulkSyncRequirementsPayload,
+) -> Result<Vec<KitPartRequirement>, RequirementWriteError> {
     let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = pool.begin().await?;
+    ensure_kit_part_writable(&mut tx, payload.kit_part_id, user_id).await?;
+    let item_runner_ids: Vec<i64> = payload.create.iter().map(|item| item.runner_id).collect();
+    let kit_id =
+        ensure_kit_part_runners(&mut tx, user_id, payload.kit_part_id, &item_runner_ids).await?;
+    ensure_update_runners(&mut tx, user_id, &payload.update).await?;
 
-    // 1) Delete
+    let mut errors = prepare_update_gates(&mut tx, "update", &mut payload.update).await?;
+    let writes: Vec<GateWrite> = payload
+        .create
+        .iter_mut()
+        .enumerate()
+        .map(|(index, item)| GateWrite {
+            label: format!("create[{}]", index),
+            runner_id: item.runner_id,
+            gate: &mut item.gate,
+            qty: Some(item.qty),
+        })
+        .collect();
+    errors.extend(prepare_gates(&mut tx, writes).await?);
+    if !errors.is_empty() {
+        return Err(RequirementWriteError::InvalidGates(errors));
+    }
+
+    let mut runner_ids = get_kit_part_runner_ids(&mut tx, payload.kit_part_id).await?;
+
+    // 1) Delete (ลบถาวร: requirement ไม่มีรายการในถังขยะของตัวเอง เข้าถังได้พร้อม parent เท่านั้น)
     for del_id in &payload.delete_ids {
         sqlx::query(
             r#"
             DELETE FROM kit_part_requirements
-            WHERE id = $1 AND user_id = $2
+            WHERE id = $1
+              AND deleted_at IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM kit_parts kp
+                  JOIN kit_members m ON m.kit_id = kp.kit_id
+                  WHERE kp.id = kit_part_requirements.kit_part_id
+                    AND m.user_id = $2
+                    AND m.role IN ('owner', 'editor')
+              )
             "#,
         )
         .bind(del_id)
@@ -245,8 +661,21 @@ pub async fn bulk_sync_requirements(
                 gate = COALESCE($1::JSONB, gate),
                 qty = COALESCE($2, qty),
                 is_cut = COALESCE($3, is_cut),
-                runner_id = COALESCE($4, runner_id)
-            WHERE id = $5 AND user_id = $6
+                cut_at = CASE
+                    WHEN COALESCE($3, is_cut) THEN COALESCE(cut_at, NOW())
+                    ELSE NULL
+                END,
+                runner_id = COALESCE($4, runner_id),
+                updated_by = $6
+            WHERE id = $5
+              AND deleted_at IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM kit_parts kp
+                  JOIN kit_members m ON m.kit_id = kp.kit_id
+                  WHERE kp.id = kit_part_requirements.kit_part_id
+                    AND m.user_id = $6
+                    AND m.role IN ('owner', 'editor')
+              )
             "#,
         )
         .bind(gate_json_opt)
@@ -263,8 +692,11 @@ pub async fn bulk_sync_requirements(
     for item in &payload.create {
         sqlx::query(
             r#"
-            INSERT INTO kit_part_requirements (gate, qty, is_cut, runner_id, kit_part_id, user_id)
-            VALUES ($1::JSONB, $2, COALESCE($3, false), $4, $5, $6)
+            INSERT INTO kit_part_requirements (gate, qty, is_cut, cut_at, runner_id, kit_part_id, kit_id, user_id, updated_by)
+            VALUES (
+                $1::JSONB, $2, COALESCE($3, false), CASE WHEN $3 THEN NOW() END,
+                $4, $5, $7, $6, $6
+            )
             "#,
         )
         .bind(serde_json::json!(item.gate))
@@ -273,6 +705,7 @@ pub async fn bulk_sync_requirements(
         .bind(item.runner_id)
         .bind(payload.kit_part_id)
         .bind(user_id)
+        .bind(kit_id)
         .execute(&mut *tx)
         .await?;
     }
@@ -287,14 +720,14 @@ pub async fn bulk_sync_requirements(
             is_cut,
             runner_id,
             kit_part_id,
-            user_id
+            user_id,
+            updated_by
         FROM kit_part_requirements
-        WHERE kit_part_id = $1 AND user_id = $2
+        WHERE kit_part_id = $1 AND deleted_at IS NULL
         ORDER BY id
         "#,
     )
     .bind(payload.kit_part_id)
-    .bind(user_id)
     .fetch_all(&mut *tx)
     .await?;
 
@@ -307,6 +740,7 @@ pub async fn bulk_sync_requirements(
         let runner_id_val: i64 = row.try_get("runner_id")?;
         let kit_part_id_val: i64 = row.try_get("kit_part_id")?;
         let user_id_val: i64 = row.try_get("user_id")?;
+        let updated_by_val: Option<i64> = row.try_get("updated_by")?;
         let gate_vec: Vec<String> = serde_json::from_str(&gate_text).unwrap_or_default();
 
         out.push(KitPartRequirement {
@@ -317,9 +751,13 @@ pub async fn bulk_sync_requirements(
             runner_id: runner_id_val,
             kit_part_id: kit_part_id_val,
             user_id: user_id_val,
+            updated_by: updated_by_val,
         });
     }
 
+    runner_ids.extend(out.iter().map(|req| req.runner_id));
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
     tx.commit().await?;
     Ok(out)
 }
@@ -330,44 +768,78 @@ pub async fn bulk_delete_requirements(
     ids: Vec<i64>,
 ) -> Result<u64, Error> {
     let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = pool.begin().await?;
-    let mut total: u64 = 0;
+    let mut runner_ids: Vec<i64> = Vec::new();
 
     for id in ids {
-        let res = sqlx::query(
+        let deleted = sqlx::query(
             r#"
             DELETE FROM kit_part_requirements
-            WHERE id = $1 AND user_id = $2
+            WHERE id = $1
+              AND deleted_at IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM kit_parts kp
+                  JOIN kit_members m ON m.kit_id = kp.kit_id
+                  WHERE kp.id = kit_part_requirements.kit_part_id
+                    AND m.user_id = $2
+                    AND m.role IN ('owner', 'editor')
+              )
+            RETURNING runner_id
             "#,
         )
         .bind(id)
         .bind(user_id)
-        .execute(&mut *tx)
+        .fetch_optional(&mut *tx)
         .await?;
 
-        total += res.rows_affected();
+        if let Some(row) = deleted {
+            runner_ids.push(row.try_get("runner_id")?);
+        }
     }
 
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
     tx.commit().await?;
-    Ok(total)
+    Ok(runner_ids.len() as u64)
 }
 
 pub async fn compare_sync_requirements(
     pool: &PgPool,
     user_id: i64,
-    payload: CompareSyncRequirementsPayload,
-) -> Result<Vec<KitPartRequirement>, Error> {
+    mut payload: CompareSyncRequirementsPayload,
+) -> Result<Vec<KitPartRequirement>, RequirementWriteError> {
     let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = pool.begin().await?;
+    ensure_kit_part_writable(&mut tx, payload.kit_part_id, user_id).await?;
+    let item_runner_ids: Vec<i64> = payload.items.iter().map(|item| item.runner_id).collect();
+    let kit_id =
+        ensure_kit_part_runners(&mut tx, user_id, payload.kit_part_id, &item_runner_ids).await?;
+
+    let writes: Vec<GateWrite> = payload
+        .items
+        .iter_mut()
+        .enumerate()
+        .map(|(index, item)| GateWrite {
+            label: format!("items[{}]", index),
+            runner_id: item.runner_id,
+            gate: &mut item.gate,
+            qty: Some(item.qty),
+        })
+        .collect();
+    let errors = prepare_gates(&mut tx, writes).await?;
+    if !errors.is_empty() {
+        return Err(RequirementWriteError::InvalidGates(errors));
+    }
+
+    let mut runner_ids = get_kit_part_runner_ids(&mut tx, payload.kit_part_id).await?;
 
     // 1) Read existing IDs for this kit_part_id
     let existing_rows = sqlx::query(
         r#"
         SELECT id
         FROM kit_part_requirements
-        WHERE kit_part_id = $1 AND user_id = $2
+        WHERE kit_part_id = $1 AND deleted_at IS NULL
         "#,
     )
     .bind(payload.kit_part_id)
-    .bind(user_id)
     .fetch_all(&mut *tx)
     .await?;
 
@@ -378,6 +850,19 @@ pub async fn compare_sync_requirements(
     }
 
     let provided_ids: Vec<i64> = payload.items.iter().filter_map(|i| i.id).collect();
+    // id ที่ส่งมาต้องเป็น requirement ของ kit part นี้เท่านั้น
+    let unknown_ids: Vec<String> = provided_ids
+        .iter()
+        .filter(|id| !existing_ids.contains(id))
+        .map(|id| id.to_string())
+        .collect();
+    if !unknown_ids.is_empty() {
+        return Err(RequirementWriteError::NotFound(format!(
+            "Requirements not found on kit part {}: {}",
+            payload.kit_part_id,
+            unknown_ids.join(", ")
+        )));
+    }
 
     // 2) Delete records that are missing from provided list
     for id in existing_ids {
@@ -385,11 +870,11 @@ pub async fn compare_sync_requirements(
             sqlx::query(
                 r#"
                 DELETE FROM kit_part_requirements
-                WHERE id = $1 AND user_id = $2
+                WHERE id = $1 AND kit_part_id = $2 AND deleted_at IS NULL
                 "#,
             )
             .bind(id)
-            .bind(user_id)
+            .bind(payload.kit_part_id)
             .execute(&mut *tx)
             .await?;
         }
@@ -404,8 +889,22 @@ pub async fn compare_sync_requirements(
                 gate = $1::JSONB,
                 qty = $2,
                 is_cut = COALESCE($3, is_cut),
-                runner_id = $4
-            WHERE id = $5 AND user_id = $6
+                cut_at = CASE
+                    WHEN COALESCE($3, is_cut) THEN COALESCE(cut_at, NOW())
+                    ELSE NULL
+                END,
+                runner_id = $4,
+                updated_by = $6
+            WHERE id = $5
+              AND kit_part_id = $7
+              AND deleted_at IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM kit_parts kp
+                  JOIN kit_members m ON m.kit_id = kp.kit_id
+                  WHERE kp.id = kit_part_requirements.kit_part_id
+                    AND m.user_id = $6
+                    AND m.role IN ('owner', 'editor')
+              )
             "#,
         )
         .bind(serde_json::json!(&item.gate))
@@ -414,6 +913,7 @@ pub async fn compare_sync_requirements(
         .bind(item.runner_id)
         .bind(item.id.unwrap())
         .bind(user_id)
+        .bind(payload.kit_part_id)
         .execute(&mut *tx)
         .await?;
     }
@@ -422,8 +922,11 @@ pub async fn compare_sync_requirements(
     for item in payload.items.into_iter().filter(|i| i.id.is_none()) {
         sqlx::query(
             r#"
-            INSERT INTO kit_part_requirements (gate, qty, is_cut, runner_id, kit_part_id, user_id)
-            VALUES ($1::JSONB, $2, COALESCE($3, false), $4, $5, $6)
+            INSERT INTO kit_part_requirements (gate, qty, is_cut, cut_at, runner_id, kit_part_id, kit_id, user_id, updated_by)
+            VALUES (
+                $1::JSONB, $2, COALESCE($3, false), CASE WHEN $3 THEN NOW() END,
+                $4, $5, $7, $6, $6
+            )
             "#,
         )
         .bind(serde_json::json!(item.gate))
@@ -432,6 +935,7 @@ pub async fn compare_sync_requirements(
         .bind(item.runner_id)
         .bind(payload.kit_part_id)
         .bind(user_id)
+        .bind(kit_id)
         .execute(&mut *tx)
         .await?;
     }
@@ -446,14 +950,14 @@ pub async fn compare_sync_requirements(
             is_cut,
             runner_id,
             kit_part_id,
-            user_id
+            user_id,
+            updated_by
         FROM kit_part_requirements
-        WHERE kit_part_id = $1 AND user_id = $2
+        WHERE kit_part_id = $1 AND deleted_at IS NULL
         ORDER BY id
         "#,
     )
     .bind(payload.kit_part_id)
-    .bind(user_id)
     .fetch_all(&mut *tx)
     .await?;
 
@@ -466,6 +970,7 @@ pub async fn compare_sync_requirements(
         let runner_id_val: i64 = row.try_get("runner_id")?;
         let kit_part_id_val: i64 = row.try_get("kit_part_id")?;
         let user_id_val: i64 = row.try_get("user_id")?;
+        let updated_by_val: Option<i64> = row.try_get("updated_by")?;
         let gate_vec: Vec<String> = serde_json::from_str(&gate_text).unwrap_or_default();
 
         out.push(KitPartRequirement {
@@ -476,9 +981,13 @@ pub async fn compare_sync_requirements(
             runner_id: runner_id_val,
             kit_part_id: kit_part_id_val,
             user_id: user_id_val,
+            updated_by: updated_by_val,
         });
     }
 
+    runner_ids.extend(out.iter().map(|req| req.runner_id));
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
     tx.commit().await?;
     Ok(out)
 }
@@ -1,23 +1,66 @@
 use crate::model::{
     color::RunnerColor,
+    gate::sort_gates,
     runner::{
-        CreateRunnerPayload, Runner, RunnerWithColor, UpdateIsUsedPayload, UpdateRunnerPayload,
+        CreateRunnerPayload, Runner, RunnerGates, RunnerWithColor, UpdateIsUsedPayload,
+        UpdateRunnerPayload,
     },
 };
-use sqlx::{Error, PgPool};
+use crate::repository::reference::{ensure_colors_for_kit, ensure_kit_writable, ReferenceError};
+use crate::repository::runner_color::{get_runner_color_sets, set_primary_runner_color};
+use sqlx::{Error, PgConnection, PgPool};
+
+// ผลลัพธ์ error ของ bulk create ที่ handler ต้องแยกตอบ
+#[derive(Debug)]
+pub enum BulkCreateRunnersError {
+    KitNotFound,
+    DuplicateNames(Vec<String>), // 👈 ชื่อที่มีอยู่แล้วใน kit
+    ColorNotFound(String),
+    Db(Error),
+}
+
+impl From<Error> for BulkCreateRunnersError {
+    fn from(e: Error) -> Self {
+        BulkCreateRunnersError::Db(e)
+    }
+}
+
+impl From<ReferenceError> for BulkCreateRunnersError {
+    fn from(e: ReferenceError) -> Self {
+        match e {
+            ReferenceError::NotFound(msg) | ReferenceError::WrongKit(msg) => {
+                BulkCreateRunnersError::ColorNotFound(msg)
+            }
+            ReferenceError::Db(e) => BulkCreateRunnersError::Db(e),
+        }
+    }
+}
 
 // --- CREATE ---
 pub async fn create_runner(
     pool: &PgPool,
     user_id: i64,
     payload: CreateRunnerPayload,
-) -> Result<Runner, Error> {
+) -> Result<Runner, ReferenceError> {
+    let mut tx = pool.begin().await?;
+    // Only owners/editors of the kit can add runners, with a color of one of the kit's members
+    ensure_kit_writable(&mut tx, user_id, payload.kit_id).await?;
+    ensure_colors_for_kit(&mut tx, payload.kit_id, &[payload.color_id]).await?;
+
     // created_at/updated_at handled by DB defaults; return the inserted row
-    sqlx::query_as!(
+    let runner = sqlx::query_as!(
         Runner,
         r#"
-        INSERT INTO runners (name, kit_id, color_id, amount, user_id, is_used)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO runners (name, kit_id, color_id, amount, user_id, is_used, updated_by)
+        SELECT $1, $2, $3, $4, $5, $6, $5
+        WHERE EXISTS (
+            SELECT 1 FROM kit_members m
+            JOIN kits k ON k.id = m.kit_id
+            WHERE m.kit_id = $2
+              AND m.user_id = $5
+              AND m.role IN ('owner', 'editor')
+              AND k.deleted_at IS NULL
+        )
         RETURNING
             id as "id!: i64",
             name,
@@ -26,6 +69,7 @@ pub async fn create_runner(
             amount as "amount!: i32",
             user_id as "user_id!: i64",
             is_used,
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         "#,
@@ -36,8 +80,107 @@ pub async fn create_runner(
         user_id,
         false
     )
-    .fetch_one(pool)
-    .await
+    .fetch_one(&mut *tx)
+    .await?;
+    set_primary_runner_color(&mut tx, runner.id, runner.color_id).await?;
+
+    tx.commit().await?;
+    Ok(runner)
+}
+
+// --- BULK CREATE ---
+// สร้างหลาย runner ใน transaction เดียว (ชื่อซ้ำกับที่มีอยู่ใน kit = DuplicateNames ไม่สร้างเลยสักตัว)
+// rows: (name, color_id, amount) ที่ผ่านการตรวจจาก handler แล้ว
+pub async fn bulk_create_runners(
+    pool: &PgPool,
+    user_id: i64,
+    kit_id: i64,
+    rows: Vec<(String, i64, i32)>,
+) -> Result<Vec<Runner>, BulkCreateRunnersError> {
+    let mut tx = pool.begin().await?;
+
+    // lock kit ไว้กันสอง request สร้างชื่อเดียวกันพร้อมกัน
+    let writable = sqlx::query_scalar!(
+        r#"
+        SELECT k.id as "id!: i64"
+        FROM kits k
+        JOIN kit_members m ON m.kit_id = k.id
+        WHERE k.id = $1
+          AND k.deleted_at IS NULL
+          AND m.user_id = $2
+          AND m.role IN ('owner', 'editor')
+        FOR UPDATE OF k
+        "#,
+        kit_id,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    if writable.is_none() {
+        return Err(BulkCreateRunnersError::KitNotFound);
+    }
+
+    let mut names = Vec::with_capacity(rows.len());
+    let mut color_ids = Vec::with_capacity(rows.len());
+    let mut amounts = Vec::with_capacity(rows.len());
+    for (name, color_id, amount) in rows {
+        names.push(name);
+        color_ids.push(color_id);
+        amounts.push(amount);
+    }
+    ensure_colors_for_kit(&mut tx, kit_id, &color_ids).await?;
+
+    let existing = sqlx::query_scalar!(
+        r#"
+        SELECT name
+        FROM runners
+        WHERE kit_id = $1 AND deleted_at IS NULL AND name = ANY($2)
+        ORDER BY name
+        "#,
+        kit_id,
+        &names
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    if !existing.is_empty() {
+        return Err(BulkCreateRunnersError::DuplicateNames(existing));
+    }
+
+    let mut runners = sqlx::query_as!(
+        Runner,
+        r#"
+        INSERT INTO runners (name, kit_id, color_id, amount, user_id, is_used, updated_by)
+        SELECT t.name, $1, t.color_id, t.amount, $2, false, $2
+        FROM UNNEST($3::TEXT[], $4::BIGINT[], $5::INT[]) AS t(name, color_id, amount)
+        RETURNING
+            id as "id!: i64",
+            name,
+            kit_id as "kit_id!: i64",
+            color_id as "color_id!: i64",
+            amount as "amount!: i32",
+            user_id as "user_id!: i64",
+            is_used,
+            updated_by,
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        "#,
+        kit_id,
+        user_id,
+        &names,
+        &color_ids,
+        &amounts
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    for runner in &runners {
+        set_primary_runner_color(&mut tx, runner.id, runner.color_id).await?;
+    }
+
+    tx.commit().await?;
+
+    // คืนตามลำดับที่ส่งมา
+    runners.sort_by_key(|r| r.id);
+    Ok(runners)
 }
 
 // --- READ ---
@@ -53,10 +196,15 @@ pub async fn get_all_runners(pool: &PgPool, user_id: i64) -> Result<Vec<Runner>,
             amount as "amount!: i32",
             user_id as "user_id!: i64",
             is_used,
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         FROM runners
-        WHERE user_id = $1
+        WHERE deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id AND m.user_id = $1
+          )
         ORDER BY name ASC
         "#,
         user_id
@@ -88,10 +236,23 @@ pub async fn get_all_runners_with_color_for_kit(
             c.name as color_name,
             c.hex as color_hex,
             c.is_multi as color_is_multi,
-            c.is_clear as color_is_clear
+            c.is_clear as color_is_clear,
+            COALESCE((
+                SELECT ARRAY_AGG(DISTINCT g.gate)
+                FROM kit_part_requirements kpr2
+                CROSS JOIN LATERAL jsonb_array_elements_text(kpr2.gate) AS g(gate)
+                WHERE kpr2.runner_id = r.id
+                  AND kpr2.is_cut = false
+                  AND kpr2.deleted_at IS NULL
+            ), '{}') as "remaining_gates!: Vec<String>"
         FROM runners r
         INNER JOIN colors c ON r.color_id = c.id
-        WHERE r.user_id = $1 AND r.kit_id = $2
+        WHERE r.kit_id = $2
+          AND r.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id AND m.user_id = $1
+          )
         ORDER BY r.is_used DESC, r.name
         "#,
         user_id,
@@ -101,7 +262,7 @@ pub async fn get_all_runners_with_color_for_kit(
     .await?;
 
     // แปลง flat rows เป็น nested structure
-    let runners = rows
+    let runners: Vec<RunnerWithColor> = rows
         .into_iter()
         .map(|row| RunnerWithColor {
             id: row.runner_id,
@@ -120,9 +281,32 @@ pub async fn get_all_runners_with_color_for_kit(
                 is_clear: row.color_is_clear,
                 is_multi: row.color_is_multi,
             },
+            colors: Vec::new(),
+            gate_colors: Vec::new(),
+            remaining_gates: {
+                let mut gates = row.remaining_gates;
+                sort_gates(&mut gates);
+                gates
+            },
         })
         .collect();
 
+    attach_runner_color_sets(pool, runners).await
+}
+
+// เติมสีทั้งหมด + สีเฉพาะ gate ให้ RunnerWithColor (query เดียวสำหรับทุก runner)
+pub async fn attach_runner_color_sets(
+    pool: &PgPool,
+    mut runners: Vec<RunnerWithColor>,
+) -> Result<Vec<RunnerWithColor>, Error> {
+    let ids: Vec<i64> = runners.iter().map(|r| r.id).collect();
+    let sets = get_runner_color_sets(pool, &ids).await?;
+    for runner in &mut runners {
+        if let Some((colors, gate_colors)) = sets.get(&runner.id) {
+            runner.colors = colors.clone();
+            runner.gate_colors = gate_colors.clone();
+        }
+    }
     Ok(runners)
 }
 
@@ -142,10 +326,16 @@ pub async fn get_runner_by_id(
             amount as "amount!: i32",
             user_id as "user_id!: i64",
             is_used,
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         FROM runners
-        WHERE id = $1 AND user_id = $2
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id AND m.user_id = $2
+          )
         "#,
         runner_id,
         user_id
@@ -160,11 +350,12 @@ pub async fn update_runner(
     runner_id: i64,
     user_id: i64,
     payload: UpdateRunnerPayload,
-) -> Result<Runner, Error> {
+) -> Result<Runner, ReferenceError> {
     // amount ใน payload เป็น Option<i64> แต่ในตารางเป็น INTEGER (i32) — แปลงเพื่อความชัดเจน
     let amount_i32: Option<i32> = payload.amount.map(|v| v as i32);
+    let mut tx = pool.begin().await?;
 
-    sqlx::query_as!(
+    let runner = sqlx::query_as!(
         Runner,
         r#"
         UPDATE runners
@@ -173,8 +364,24 @@ pub async fn update_runner(
             kit_id = COALESCE($2, kit_id),
             color_id = COALESCE($3, color_id),
             amount = COALESCE($4, amount),
+            updated_by = $6,
             updated_at = NOW()
-        WHERE id = $5 AND user_id = $6
+        WHERE id = $5
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id
+                AND m.user_id = $6
+                AND m.role IN ('owner', 'editor')
+          )
+          -- ย้ายไป kit อื่นได้เฉพาะ kit ที่เราแก้ไขได้เช่นกัน
+          AND (
+              $2::BIGINT IS NULL
+              OR EXISTS (
+                  SELECT 1 FROM kit_members m
+                  WHERE m.kit_id = $2 AND m.user_id = $6 AND m.role IN ('owner', 'editor')
+              )
+          )
         RETURNING
             id as "id!: i64",
             name,
@@ -183,6 +390,7 @@ pub async fn update_runner(
             amount as "amount!: i32",
             user_id as "user_id!: i64",
             is_used,
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         "#,
@@ -193,8 +401,23 @@ pub async fn update_runner(
         runner_id,
         user_id
     )
-    .fetch_one(pool)
-    .await
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // เปลี่ยนสีหรือย้าย kit: ทุกสีของ runner ต้องเป็นของสมาชิก kit ปลายทาง (ไม่ผ่าน = rollback)
+    if payload.color_id.is_some() || payload.kit_id.is_some() {
+        set_primary_runner_color(&mut tx, runner.id, runner.color_id).await?;
+        let color_ids = sqlx::query_scalar!(
+            "SELECT color_id FROM runner_colors WHERE runner_id = $1",
+            runner.id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        ensure_colors_for_kit(&mut tx, runner.kit_id, &color_ids).await?;
+    }
+
+    tx.commit().await?;
+    Ok(runner)
 }
 
 pub async fn update_runner_is_used(
@@ -207,8 +430,15 @@ pub async fn update_runner_is_used(
         Runner,
         r#"
         UPDATE runners
-        SET is_used = $1, updated_at = NOW()
-        WHERE id = $2 AND user_id = $3
+        SET is_used = $1, updated_by = $3, updated_at = NOW()
+        WHERE id = $2
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id
+                AND m.user_id = $3
+                AND m.role IN ('owner', 'editor')
+          )
         RETURNING
             id as "id!: i64",
             name,
@@ -217,6 +447,7 @@ pub async fn update_runner_is_used(
             amount as "amount!: i32",
             user_id as "user_id!: i64",
             is_used,
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         "#,
@@ -228,21 +459,150 @@ pub async fn update_runner_is_used(
     .await
 }
 
+// --- AUTO is_used ---
+// runner ที่มี requirement และตัดครบทุกอันแล้ว = used, ยังเหลือ = ไม่ used
+// runner ที่ไม่มี requirement เลยคงค่าเดิม (ตั้งเองได้ผ่าน PATCH /runners/:id/status)
+pub async fn refresh_runners_is_used(
+    conn: &mut PgConnection,
+    runner_ids: &[i64],
+) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+        UPDATE runners r
+        SET is_used = s.all_cut, updated_at = NOW()
+        FROM (
+            SELECT runner_id, BOOL_AND(is_cut) AS all_cut
+            FROM kit_part_requirements
+            WHERE runner_id = ANY($1) AND deleted_at IS NULL
+            GROUP BY runner_id
+        ) s
+        WHERE r.id = s.runner_id
+          AND r.deleted_at IS NULL
+          AND r.is_used IS DISTINCT FROM s.all_cut
+        "#,
+        runner_ids
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// --- GATE INVENTORY ---
+pub async fn get_runner_gates(
+    pool: &PgPool,
+    runner_id: i64,
+    user_id: i64,
+) -> Result<RunnerGates, Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: i64",
+            name,
+            gate_count,
+            gates as "gates: sqlx::types::Json<Vec<String>>"
+        FROM runners
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id AND m.user_id = $2
+          )
+        "#,
+        runner_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(RunnerGates::new(
+        row.id,
+        row.name,
+        row.gate_count,
+        row.gates.map(|g| g.0),
+    ))
+}
+
+// แทนที่ inventory ทั้งชุด (gates ต้อง normalize มาแล้ว)
+pub async fn set_runner_gates(
+    pool: &PgPool,
+    runner_id: i64,
+    user_id: i64,
+    gate_count: Option<i32>,
+    gates: Option<Vec<String>>,
+) -> Result<RunnerGates, Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE runners
+        SET gate_count = $1, gates = $2::JSONB, updated_by = $4, updated_at = NOW()
+        WHERE id = $3
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id
+                AND m.user_id = $4
+                AND m.role IN ('owner', 'editor')
+          )
+        RETURNING
+            id as "id!: i64",
+            name,
+            gate_count,
+            gates as "gates: sqlx::types::Json<Vec<String>>"
+        "#,
+        gate_count,
+        gates.map(|g| serde_json::json!(g)),
+        runner_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(RunnerGates::new(
+        row.id,
+        row.name,
+        row.gate_count,
+        row.gates.map(|g| g.0),
+    ))
+}
+
 // --- DELETE ---
+// Soft delete: the runner and its requirements go to the trash with the same timestamp
 pub async fn delete_runner(pool: &PgPool, runner_id: i64, user_id: i64) -> Result<(), Error> {
-    let result = sqlx::query!(
+    let mut tx = pool.begin().await?;
+
+    let rec = sqlx::query!(
         r#"
-        DELETE FROM runners WHERE id = $1 AND user_id = $2
+        UPDATE runners
+        SET deleted_at = NOW(), updated_by = $2
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id
+                AND m.user_id = $2
+                AND m.role IN ('owner', 'editor')
+          )
+        RETURNING deleted_at as "deleted_at!"
         "#,
         runner_id,
         user_id
     )
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(Error::RowNotFound)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE kit_part_requirements
+        SET deleted_at = $2
+        WHERE runner_id = $1 AND deleted_at IS NULL
+        "#,
+        runner_id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
     .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(Error::RowNotFound);
-    }
+    tx.commit().await?;
     Ok(())
 }
 
@@ -262,10 +622,16 @@ pub async fn get_all_runners_for_kit(
             amount as "amount!: i32",
             user_id as "user_id!: i64",
             is_used,
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         FROM runners
-        WHERE kit_id = $1 AND user_id = $2
+        WHERE kit_id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id AND m.user_id = $2
+          )
         ORDER BY is_used DESC, name
         "#,
         kit_id,
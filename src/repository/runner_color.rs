@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use sqlx::{Error, PgConnection, PgPool};
+
+use crate::model::{
+    color::RunnerColor,
+    gate::{full_gate_name, sort_gates},
+    runner_color::RunnerGateColor,
+};
+use crate::repository::reference::{ensure_colors_for_kit, ReferenceError};
+
+// สีทั้งหมด + สีเฉพาะ gate ของหลาย runner พร้อมกัน (ใช้เติม RunnerWithColor)
+pub async fn get_runner_color_sets(
+    pool: &PgPool,
+    runner_ids: &[i64],
+) -> Result<HashMap<i64, (Vec<RunnerColor>, Vec<RunnerGateColor>)>, Error> {
+    let colors = sqlx::query!(
+        r#"
+        SELECT
+            rc.runner_id as "runner_id!: i64",
+            c.id as "id!: i64",
+            c.name,
+            c.code,
+            c.hex,
+            c.is_clear,
+            c.is_multi
+        FROM runner_colors rc
+        JOIN colors c ON c.id = rc.color_id
+        WHERE rc.runner_id = ANY($1)
+        ORDER BY rc.runner_id, rc.position, c.id
+        "#,
+        runner_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let gate_colors = sqlx::query!(
+        r#"
+        SELECT
+            gc.runner_id as "runner_id!: i64",
+            r.name as runner_name,
+            gc.gate,
+            gc.color_id as "color_id!: i64"
+        FROM runner_gate_colors gc
+        JOIN runners r ON r.id = gc.runner_id
+        WHERE gc.runner_id = ANY($1)
+        "#,
+        runner_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut sets: HashMap<i64, (Vec<RunnerColor>, Vec<RunnerGateColor>)> = HashMap::new();
+    for row in colors {
+        sets.entry(row.runner_id).or_default().0.push(RunnerColor {
+            id: row.id,
+            name: row.name,
+            code: row.code,
+            hex: row.hex,
+            is_clear: row.is_clear,
+            is_multi: row.is_multi,
+        });
+    }
+    for row in gate_colors {
+        sets.entry(row.runner_id)
+            .or_default()
+            .1
+            .push(RunnerGateColor {
+                // ชื่อ gate เต็มแบบเดียวกับ requirement (" a" + "12" → "A12")
+                gate: full_gate_name(&row.runner_name, &row.gate),
+                color_id: row.color_id,
+            });
+    }
+
+    // เรียง gate ตามลำดับจริง (A2 ก่อน A10)
+    for (_, gate_colors) in sets.values_mut() {
+        let mut order: Vec<String> = gate_colors.iter().map(|g| g.gate.clone()).collect();
+        sort_gates(&mut order);
+        gate_colors.sort_by_key(|g| order.iter().position(|o| *o == g.gate));
+    }
+    Ok(sets)
+}
+
+// ตั้งสีหลัก (position 0) ให้ตรงกับ runners.color_id
+// - runner ใหม่: เพิ่มแถว position 0
+// - เปลี่ยนสีหลัก: แทนที่สีเดิม (สีเฉพาะ gate ที่ชี้สีเดิมตามไปด้วยจาก ON UPDATE CASCADE)
+//   ถ้าสีใหม่เป็นสีรองอยู่แล้ว สีรองนั้นถูกรวมเป็นสีหลัก (สีเฉพาะ gate ของสีรองยังอยู่)
+pub async fn set_primary_runner_color(
+    conn: &mut PgConnection,
+    runner_id: i64,
+    color_id: i64,
+) -> Result<(), Error> {
+    // ย้ายสีเฉพาะ gate ของสีรองไปที่แถวสีหลักก่อน ไม่งั้น DELETE ด้านล่างจะ cascade ลบทิ้ง
+    // (แถวสีหลักเปลี่ยนเป็น color_id ใหม่ทีหลัง แล้ว ON UPDATE CASCADE พาไปด้วย)
+    sqlx::query!(
+        r#"
+        UPDATE runner_gate_colors g
+        SET color_id = p.color_id
+        FROM runner_colors p
+        WHERE p.runner_id = $1
+          AND p.position = 0
+          AND p.color_id <> $2
+          AND g.runner_id = $1
+          AND g.color_id = $2
+        "#,
+        runner_id,
+        color_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM runner_colors WHERE runner_id = $1 AND color_id = $2 AND position > 0",
+        runner_id,
+        color_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE runner_colors SET color_id = $2 WHERE runner_id = $1 AND position = 0",
+        runner_id,
+        color_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO runner_colors (runner_id, color_id, position)
+        VALUES ($1, $2, 0)
+        ON CONFLICT (runner_id, color_id) DO NOTHING
+        "#,
+        runner_id,
+        color_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// แทนที่ชุดสีทั้งหมดของ runner (color_ids[0] = สีหลัก)
+// gate_colors: (gate ที่ normalize แล้วไม่มี prefix, color_id) ที่ handler ตรวจแล้วว่าอยู่ใน color_ids
+pub async fn replace_runner_colors(
+    pool: &PgPool,
+    runner_id: i64,
+    user_id: i64,
+    color_ids: &[i64],
+    gate_colors: &[(String, i64)],
+) -> Result<(), ReferenceError> {
+    let mut tx = pool.begin().await?;
+
+    let kit_id = sqlx::query_scalar!(
+        r#"
+        SELECT r.kit_id as "kit_id!: i64"
+        FROM runners r
+        WHERE r.id = $1
+          AND r.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id
+                AND m.user_id = $2
+                AND m.role IN ('owner', 'editor')
+          )
+        FOR UPDATE
+        "#,
+        runner_id,
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    ensure_colors_for_kit(&mut tx, kit_id, color_ids).await?;
+
+    // ลบ runner_colors = ลบสีเฉพาะ gate ไปด้วย (FK cascade)
+    sqlx::query!("DELETE FROM runner_colors WHERE runner_id = $1", runner_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO runner_colors (runner_id, color_id, position)
+        SELECT $1, c.color_id, (c.ord - 1)::INT
+        FROM UNNEST($2::BIGINT[]) WITH ORDINALITY AS c(color_id, ord)
+        "#,
+        runner_id,
+        color_ids
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let gates: Vec<String> = gate_colors.iter().map(|(gate, _)| gate.clone()).collect();
+    let gate_color_ids: Vec<i64> = gate_colors.iter().map(|(_, color_id)| *color_id).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO runner_gate_colors (runner_id, gate, color_id, user_id, updated_by)
+        SELECT $1, g.gate, g.color_id, $2, $2
+        FROM UNNEST($3::TEXT[], $4::BIGINT[]) AS g(gate, color_id)
+        "#,
+        runner_id,
+        user_id,
+        &gates,
+        &gate_color_ids
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE runners
+        SET color_id = $2, updated_by = $3, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        runner_id,
+        color_ids[0],
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
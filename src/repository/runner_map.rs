@@ -0,0 +1,188 @@
+use sqlx::{Error, PgPool};
+
+use crate::model::runner_map::{HotspotItem, RunnerGateHotspot};
+
+// --- IMAGE ---
+// เปลี่ยนรูปของ runner (None = ลบรูป) แล้วคืนชื่อไฟล์เดิมเพื่อให้ handler ลบไฟล์ทิ้ง
+pub async fn set_runner_image(
+    pool: &PgPool,
+    runner_id: i64,
+    user_id: i64,
+    image_path: Option<&str>,
+) -> Result<Option<String>, Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE runners r
+        SET image_path = $1, updated_by = $3, updated_at = NOW()
+        FROM runners old
+        WHERE r.id = $2
+          AND old.id = r.id
+          AND r.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id
+                AND m.user_id = $3
+                AND m.role IN ('owner', 'editor')
+          )
+        RETURNING old.image_path as previous_path
+        "#,
+        image_path,
+        runner_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.previous_path)
+}
+
+pub async fn get_runner_image_path(
+    pool: &PgPool,
+    runner_id: i64,
+    user_id: i64,
+) -> Result<Option<String>, Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT image_path
+        FROM runners
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = runners.kit_id AND m.user_id = $2
+          )
+        "#,
+        runner_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.image_path)
+}
+
+// --- HOTSPOTS ---
+pub async fn get_hotspots(pool: &PgPool, runner_id: i64) -> Result<Vec<RunnerGateHotspot>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            gate,
+            x,
+            y,
+            polygon as "polygon: sqlx::types::Json<Vec<[f64; 2]>>"
+        FROM runner_gate_hotspots
+        WHERE runner_id = $1
+        ORDER BY id
+        "#,
+        runner_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RunnerGateHotspot {
+            gate: row.gate,
+            x: row.x,
+            y: row.y,
+            polygon: row.polygon.map(|p| p.0),
+        })
+        .collect())
+}
+
+// แทนที่ hotspot ทั้งชุดของ runner (gate ต้อง normalize มาแล้ว)
+pub async fn replace_hotspots(
+    pool: &PgPool,
+    runner_id: i64,
+    user_id: i64,
+    hotspots: &[HotspotItem],
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        SELECT r.id
+        FROM runners r
+        WHERE r.id = $1
+          AND r.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id
+                AND m.user_id = $2
+                AND m.role IN ('owner', 'editor')
+          )
+        FOR UPDATE
+        "#,
+        runner_id,
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM runner_gate_hotspots WHERE runner_id = $1",
+        runner_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let gates: Vec<String> = hotspots.iter().map(|h| h.gate.clone()).collect();
+    let xs: Vec<f64> = hotspots.iter().map(|h| h.x).collect();
+    let ys: Vec<f64> = hotspots.iter().map(|h| h.y).collect();
+    let polygons: Vec<serde_json::Value> = hotspots
+        .iter()
+        .map(|h| serde_json::json!(h.polygon))
+        .collect();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO runner_gate_hotspots (runner_id, gate, x, y, polygon, user_id, updated_by)
+        SELECT $1, h.gate, h.x, h.y, NULLIF(h.polygon, 'null'::JSONB), $2, $2
+        FROM UNNEST($3::TEXT[], $4::FLOAT8[], $5::FLOAT8[], $6::JSONB[])
+            AS h(gate, x, y, polygon)
+        "#,
+        runner_id,
+        user_id,
+        &gates,
+        &xs,
+        &ys,
+        &polygons
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE runners SET updated_by = $2, updated_at = NOW() WHERE id = $1",
+        runner_id,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// gate + สถานะการตัดของทุก requirement บน runner นี้ (ใช้คำนวณสถานะของ hotspot)
+pub async fn get_runner_requirement_gates(
+    pool: &PgPool,
+    runner_id: i64,
+) -> Result<Vec<(Vec<String>, bool)>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            gate as "gate: sqlx::types::Json<Vec<String>>",
+            is_cut
+        FROM kit_part_requirements
+        WHERE runner_id = $1 AND deleted_at IS NULL
+        "#,
+        runner_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.gate.0, row.is_cut))
+        .collect())
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use sqlx::{Error, PgPool};
+
+use crate::model::{
+    kit::{KitGrade, KitStatus},
+    share::{
+        CreateShareLinkPayload, KitShareLink, PublicColor, PublicKit, PublicKitPart, PublicRunner,
+        PublicSubAssembly,
+    },
+};
+
+// --- CREATE ---
+// INSERT ... SELECT จาก kits เพื่อเช็ค ownership ในคำสั่งเดียว (ไม่เจอ kit = RowNotFound)
+pub async fn create_share_link(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+    payload: CreateShareLinkPayload,
+) -> Result<KitShareLink, Error> {
+    sqlx::query_as!(
+        KitShareLink,
+        r#"
+        INSERT INTO kit_share_links (kit_id, user_id, expires_at)
+        SELECT
+            k.id,
+            $2,
+            CASE
+                WHEN $3::BIGINT IS NULL THEN NULL
+                ELSE NOW() + $3::BIGINT * INTERVAL '1 hour'
+            END
+        FROM kits k
+        WHERE k.id = $1 AND k.user_id = $2 AND k.deleted_at IS NULL
+        RETURNING
+            id as "id!: i64",
+            token,
+            kit_id as "kit_id!: i64",
+            (expires_at AT TIME ZONE 'UTC') as "expires_at?: chrono::NaiveDateTime",
+            (revoked_at AT TIME ZONE 'UTC') as "revoked_at?: chrono::NaiveDateTime",
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime"
+        "#,
+        kit_id,
+        user_id,
+        payload.expires_in_hours
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// --- READ ---
+pub async fn get_share_links_for_kit(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+) -> Result<Vec<KitShareLink>, Error> {
+    sqlx::query_as!(
+        KitShareLink,
+        r#"
+        SELECT
+            l.id as "id!: i64",
+            l.token,
+            l.kit_id as "kit_id!: i64",
+            (l.expires_at AT TIME ZONE 'UTC') as "expires_at?: chrono::NaiveDateTime",
+            (l.revoked_at AT TIME ZONE 'UTC') as "revoked_at?: chrono::NaiveDateTime",
+            (l.created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime"
+        FROM kit_share_links l
+        JOIN kits k ON k.id = l.kit_id
+        WHERE l.kit_id = $1 AND k.user_id = $2 AND k.deleted_at IS NULL
+        ORDER BY l.created_at DESC
+        "#,
+        kit_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// --- REVOKE ---
+// ไม่ลบแถว เก็บ revoked_at ไว้เพื่อให้เจ้าของเห็นประวัติลิงก์
+pub async fn revoke_share_link(
+    pool: &PgPool,
+    kit_id: i64,
+    link_id: i64,
+    user_id: i64,
+) -> Result<KitShareLink, Error> {
+    sqlx::query_as!(
+        KitShareLink,
+        r#"
+        UPDATE kit_share_links
+        SET revoked_at = COALESCE(revoked_at, NOW())
+        WHERE id = $1 AND kit_id = $2 AND user_id = $3
+        RETURNING
+            id as "id!: i64",
+            token,
+            kit_id as "kit_id!: i64",
+            (expires_at AT TIME ZONE 'UTC') as "expires_at?: chrono::NaiveDateTime",
+            (revoked_at AT TIME ZONE 'UTC') as "revoked_at?: chrono::NaiveDateTime",
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime"
+        "#,
+        link_id,
+        kit_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// --- PUBLIC READ (ไม่ต้อง login) ---
+// ลิงก์ที่ถูก revoke, หมดอายุ หรือ kit อยู่ในถังขยะ จะได้ RowNotFound เหมือนกันหมด
+pub async fn get_public_kit_by_token(pool: &PgPool, token: &str) -> Result<PublicKit, Error> {
+    // 1. หา kit จาก token
+    let kit = sqlx::query!(
+        r#"
+        SELECT
+            k.id as "id!: i64",
+            k.name,
+            k.grade as "grade: KitGrade",
+            k.status as "status: KitStatus"
+        FROM kit_share_links l
+        JOIN kits k ON k.id = l.kit_id
+        WHERE l.token = $1
+          AND l.revoked_at IS NULL
+          AND (l.expires_at IS NULL OR l.expires_at > NOW())
+          AND k.deleted_at IS NULL
+        "#,
+        token
+    )
+    .fetch_one(pool)
+    .await?;
+
+    // 2. Runners พร้อมสี
+    let runners = sqlx::query!(
+        r#"
+        SELECT
+            r.name,
+            r.amount as "amount!: i32",
+            r.is_used,
+            c.name as color_name,
+            c.code as color_code,
+            c.hex as color_hex,
+            c.is_clear as color_is_clear,
+            c.is_multi as color_is_multi
+        FROM runners r
+        JOIN colors c ON c.id = r.color_id
+        WHERE r.kit_id = $1 AND r.deleted_at IS NULL
+        ORDER BY r.name
+        "#,
+        kit.id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| PublicRunner {
+        name: row.name,
+        amount: row.amount,
+        is_used: row.is_used,
+        color: PublicColor {
+            name: row.color_name,
+            code: row.color_code,
+            hex: row.color_hex,
+            is_clear: row.color_is_clear,
+            is_multi: row.color_is_multi,
+        },
+    })
+    .collect();
+
+    // 3. Sub-assemblies (id ใช้จัดกลุ่มภายในเท่านั้น ไม่ส่งออกไป)
+    let sub_assemblies = sqlx::query!(
+        r#"
+        SELECT id as "id!: i64", name
+        FROM sub_assemblies
+        WHERE kit_id = $1 AND deleted_at IS NULL
+        ORDER BY id
+        "#,
+        kit.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // 4. Kit parts พร้อม requirements (อ้างอิง runner ด้วยชื่อ)
+    let parts = sqlx::query!(
+        r#"
+        SELECT
+            kp.sub_assembly_id as "sub_assembly_id!: i64",
+            kp.code,
+            kp.is_cut,
+            COALESCE(
+                json_agg(
+                    json_build_object(
+                        'runner_name', r.name,
+                        'gate', kpr.gate,
+                        'qty', kpr.qty,
+                        'is_cut', kpr.is_cut
+                    )
+                    ORDER BY kpr.id
+                ) FILTER (WHERE kpr.id IS NOT NULL),
+                '[]'::json
+            ) as "reqs!: serde_json::Value"
+        FROM kit_parts kp
+        -- requirement ของ runner ที่อยู่ในถังขยะไม่แสดง (runner นั้นก็ไม่อยู่ในรายการ runners)
+        LEFT JOIN (
+            kit_part_requirements kpr
+            JOIN runners r ON r.id = kpr.runner_id AND r.deleted_at IS NULL
+        ) ON kpr.kit_part_id = kp.id AND kpr.deleted_at IS NULL
+        WHERE kp.kit_id = $1 AND kp.deleted_at IS NULL
+        GROUP BY kp.id
+        ORDER BY kp.id
+        "#,
+        kit.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut parts_by_sub_assembly: HashMap<i64, Vec<PublicKitPart>> = HashMap::new();
+    for row in parts {
+        parts_by_sub_assembly
+            .entry(row.sub_assembly_id)
+            .or_default()
+            .push(PublicKitPart {
+                code: row.code,
+                is_cut: row.is_cut,
+                requirements: serde_json::from_value(row.reqs)
+                    .map_err(|e| Error::Decode(Box::new(e)))?,
+            });
+    }
+
+    let sub_assemblies = sub_assemblies
+        .into_iter()
+        .map(|sa| PublicSubAssembly {
+            name: sa.name,
+            parts: parts_by_sub_assembly.remove(&sa.id).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(PublicKit {
+        name: kit.name,
+        grade: kit.grade,
+        status: kit.status,
+        runners,
+        sub_assemblies,
+    })
+}
@@ -0,0 +1,223 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{Error, PgPool};
+
+use crate::model::{
+    color::RunnerColor,
+    gate::{full_gate_name, parse_gate, sort_gates},
+    kit::KitGrade,
+    runner::RunnerGates,
+    spare::{SpareNote, SparePart},
+};
+
+// --- READ ---
+// spare = gate ใน inventory ของ runner ที่ไม่มี requirement (ที่ยังไม่ถูกลบ) อ้างถึง
+// runner ที่ยังไม่กำหนด gate inventory จะไม่มี spare
+pub async fn get_spares(
+    pool: &PgPool,
+    user_id: i64,
+    kit_id: Option<i64>,
+    grade: Option<&str>,
+    color_id: Option<i64>,
+    color: Option<&str>,
+) -> Result<Vec<SparePart>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            r.id as "runner_id!: i64",
+            r.name as runner_name,
+            r.amount as "runner_amount!: i32",
+            r.gate_count,
+            r.gates as "gates: sqlx::types::Json<Vec<String>>",
+            k.id as "kit_id!: i64",
+            k.name as kit_name,
+            k.grade as "kit_grade: KitGrade",
+            c.id as "color_id!: i64",
+            c.name as color_name,
+            c.code as color_code,
+            c.hex as color_hex,
+            c.is_clear as color_is_clear,
+            c.is_multi as color_is_multi,
+            COALESCE((
+                SELECT ARRAY_AGG(g.gate)
+                FROM kit_part_requirements kpr
+                CROSS JOIN LATERAL jsonb_array_elements_text(kpr.gate) AS g(gate)
+                WHERE kpr.runner_id = r.id AND kpr.deleted_at IS NULL
+            ), '{}') as "used_gates!: Vec<String>"
+        FROM runners r
+        JOIN kits k ON k.id = r.kit_id
+        JOIN colors c ON c.id = r.color_id
+        WHERE r.deleted_at IS NULL
+          AND k.deleted_at IS NULL
+          AND (r.gate_count IS NOT NULL OR r.gates IS NOT NULL)
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = k.id AND m.user_id = $1
+          )
+          AND ($2::BIGINT IS NULL OR k.id = $2)
+          AND ($3::TEXT IS NULL OR k.grade = $3)
+          AND ($4::BIGINT IS NULL OR c.id = $4)
+          AND ($5::TEXT IS NULL OR c.name ILIKE '%' || $5 || '%' OR c.code ILIKE '%' || $5 || '%')
+        ORDER BY k.name, k.id, r.name
+        "#,
+        user_id,
+        kit_id,
+        grade,
+        color_id,
+        color
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let runner_ids: Vec<i64> = rows.iter().map(|row| row.runner_id).collect();
+    let mut notes: HashMap<(i64, String), SpareNote> = get_spare_notes(pool, &runner_ids)
+        .await?
+        .into_iter()
+        .map(|note| ((note.runner_id, note.gate.clone()), note))
+        .collect();
+
+    let mut spares = Vec::new();
+    for row in rows {
+        let inventory = RunnerGates::new(
+            row.runner_id,
+            row.runner_name.clone(),
+            row.gate_count,
+            row.gates.map(|g| g.0),
+        );
+        // gate ที่ requirement ใช้ (ค่าเก่าที่อ่านไม่ได้ข้ามไป)
+        let used: HashSet<String> = row
+            .used_gates
+            .iter()
+            .filter_map(|g| parse_gate(g, Some(&row.runner_name)).ok())
+            .map(|g| g.gate.to_string())
+            .collect();
+
+        let mut free: Vec<String> = inventory
+            .gate_list()
+            .into_iter()
+            .map(|g| g.to_string())
+            .filter(|key| !used.contains(key))
+            .collect();
+        sort_gates(&mut free);
+
+        for key in free {
+            let note = notes.remove(&(row.runner_id, key.clone()));
+            spares.push(SparePart {
+                runner_id: row.runner_id,
+                runner_name: row.runner_name.clone(),
+                runner_amount: row.runner_amount,
+                gate: full_gate_name(&row.runner_name, &key),
+                kit_id: row.kit_id,
+                kit_name: row.kit_name.clone(),
+                kit_grade: row.kit_grade.clone(),
+                color: RunnerColor {
+                    id: row.color_id,
+                    name: row.color_name.clone(),
+                    code: row.color_code.clone(),
+                    hex: row.color_hex.clone(),
+                    is_clear: row.color_is_clear,
+                    is_multi: row.color_is_multi,
+                },
+                tags: note.as_ref().map(|n| n.tags.clone()).unwrap_or_default(),
+                note: note.as_ref().and_then(|n| n.note.clone()),
+                updated_at: note.map(|n| n.updated_at),
+            });
+        }
+    }
+
+    Ok(spares)
+}
+
+async fn get_spare_notes(pool: &PgPool, runner_ids: &[i64]) -> Result<Vec<SpareNote>, Error> {
+    sqlx::query_as!(
+        SpareNote,
+        r#"
+        SELECT
+            runner_id as "runner_id!: i64",
+            gate,
+            tags,
+            note,
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM spare_parts
+        WHERE runner_id = ANY($1)
+        "#,
+        runner_ids
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// --- UPSERT tag/note ---
+// gate ต้องเป็น key ไม่มี prefix (เช่น "12") และ handler ตรวจแล้วว่าเป็น spare จริง
+pub async fn upsert_spare_note(
+    pool: &PgPool,
+    user_id: i64,
+    runner_id: i64,
+    gate: &str,
+    tags: &[String],
+    note: Option<&str>,
+) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO spare_parts (runner_id, gate, tags, note, user_id, updated_by)
+        SELECT r.id, $2, $3, $4, $5, $5
+        FROM runners r
+        WHERE r.id = $1
+          AND r.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id
+                AND m.user_id = $5
+                AND m.role IN ('owner', 'editor')
+          )
+        ON CONFLICT (runner_id, gate) DO UPDATE
+        SET tags = EXCLUDED.tags,
+            note = EXCLUDED.note,
+            updated_by = EXCLUDED.updated_by,
+            updated_at = NOW()
+        RETURNING id
+        "#,
+        runner_id,
+        gate,
+        tags,
+        note,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(())
+}
+
+// --- DELETE tag/note ---
+pub async fn delete_spare_note(
+    pool: &PgPool,
+    user_id: i64,
+    runner_id: i64,
+    gate: &str,
+) -> Result<(), Error> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM spare_parts s
+        USING runners r
+        WHERE s.runner_id = $1
+          AND s.gate = $2
+          AND r.id = s.runner_id
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id
+                AND m.user_id = $3
+                AND m.role IN ('owner', 'editor')
+          )
+        "#,
+        runner_id,
+        gate,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+    Ok(())
+}
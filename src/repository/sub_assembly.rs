@@ -1,4 +1,5 @@
 use crate::model::sub_assembly::{CreateSubAssemblyPayload, SubAssembly, UpdateSubAssemblyPayload};
+use crate::repository::runner::refresh_runners_is_used;
 use sqlx::{Error, PgPool};
 
 pub async fn create_sub_assembly(
@@ -9,13 +10,22 @@ pub async fn create_sub_assembly(
     sqlx::query_as!(
         SubAssembly,
         r#"
-        INSERT INTO sub_assemblies (name, kit_id, user_id, created_at, updated_at)
-        VALUES ($1, $2, $3, NOW(), NOW())
+        INSERT INTO sub_assemblies (name, kit_id, user_id, updated_by, created_at, updated_at)
+        SELECT $1, $2, $3, $3, NOW(), NOW()
+        WHERE EXISTS (
+            SELECT 1 FROM kit_members m
+            JOIN kits k ON k.id = m.kit_id
+            WHERE m.kit_id = $2
+              AND m.user_id = $3
+              AND m.role IN ('owner', 'editor')
+              AND k.deleted_at IS NULL
+        )
         RETURNING
             id as "id!: i64",
             name,
             kit_id as "kit_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         "#,
@@ -40,10 +50,16 @@ pub async fn get_all_sub_assemblies_for_kit(
             name,
             kit_id as "kit_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         FROM sub_assemblies
-        WHERE kit_id = $1 AND user_id = $2
+        WHERE kit_id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = sub_assemblies.kit_id AND m.user_id = $2
+          )
         "#,
         kit_id,
         user_id
@@ -65,10 +81,16 @@ pub async fn get_sub_assembly_by_id(
             name,
             kit_id as "kit_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         FROM sub_assemblies
-        WHERE id = $1 AND user_id = $2
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = sub_assemblies.kit_id AND m.user_id = $2
+          )
         "#,
         id,
         user_id
@@ -90,13 +112,29 @@ pub async fn update_sub_assembly(
         SET
             name = COALESCE($1, name),
             kit_id = COALESCE($2, kit_id),
+            updated_by = $4,
             updated_at = NOW()
-        WHERE id = $3 AND user_id = $4
+        WHERE id = $3
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = sub_assemblies.kit_id
+                AND m.user_id = $4
+                AND m.role IN ('owner', 'editor')
+          )
+          AND (
+              $2::BIGINT IS NULL
+              OR EXISTS (
+                  SELECT 1 FROM kit_members m
+                  WHERE m.kit_id = $2 AND m.user_id = $4 AND m.role IN ('owner', 'editor')
+              )
+          )
         RETURNING
             id as "id!: i64",
             name,
             kit_id as "kit_id!: i64",
             user_id as "user_id!: i64",
+            updated_by,
             (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
             (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
         "#,
@@ -109,20 +147,70 @@ pub async fn update_sub_assembly(
     .await
 }
 
+// Soft delete: the sub-assembly, its kit parts and their requirements share one timestamp
 pub async fn delete_sub_assembly(pool: &PgPool, id: i64, user_id: i64) -> Result<(), Error> {
-    let result = sqlx::query!(
+    let mut tx = pool.begin().await?;
+
+    let rec = sqlx::query!(
         r#"
-        DELETE FROM sub_assemblies
-        WHERE id = $1 AND user_id = $2
+        UPDATE sub_assemblies
+        SET deleted_at = NOW(), updated_by = $2
+        WHERE id = $1
+          AND deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = sub_assemblies.kit_id
+                AND m.user_id = $2
+                AND m.role IN ('owner', 'editor')
+          )
+        RETURNING deleted_at as "deleted_at!"
         "#,
         id,
         user_id
     )
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(Error::RowNotFound)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE kit_part_requirements
+        SET deleted_at = $2
+        WHERE deleted_at IS NULL
+          AND kit_part_id IN (SELECT id FROM kit_parts WHERE sub_assembly_id = $1)
+        "#,
+        id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE kit_parts
+        SET deleted_at = $2
+        WHERE sub_assembly_id = $1 AND deleted_at IS NULL
+        "#,
+        id,
+        rec.deleted_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // requirement ที่ยังไม่ตัดของ sub-assembly นี้ไม่นับแล้ว runner อาจกลายเป็น used
+    let runner_ids: Vec<i64> = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT req.runner_id as "runner_id!: i64"
+        FROM kit_part_requirements req
+        JOIN kit_parts kp ON kp.id = req.kit_part_id
+        WHERE kp.sub_assembly_id = $1
+        "#,
+        id
+    )
+    .fetch_all(&mut *tx)
     .await?;
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
 
-    if result.rows_affected() == 0 {
-        return Err(Error::RowNotFound);
-    }
+    tx.commit().await?;
     Ok(())
 }
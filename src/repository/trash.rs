@@ -0,0 +1,698 @@
+use std::str::FromStr;
+
+use sqlx::{Error, PgConnection, PgPool};
+
+use crate::model::trash::{TrashItem, TrashItemType};
+use crate::repository::runner::refresh_runners_is_used;
+
+// Error ของการ restore: แยก "parent ยังอยู่ในถังขยะ" ออกจาก RowNotFound
+// เพื่อให้ handler ตอบ 409 แทน 404 ได้
+#[derive(Debug)]
+pub enum RestoreError {
+    NotFound,
+    ParentInTrash(TrashItemType),
+    Db(Error),
+}
+
+impl From<Error> for RestoreError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::RowNotFound => RestoreError::NotFound,
+            other => RestoreError::Db(other),
+        }
+    }
+}
+
+// --- LIST ---
+// kit ที่ลบทั้งตัวเห็นเฉพาะ owner, ลูก (runner/sub_assembly/kit_part) เห็นทั้ง owner และ editor
+// Children trashed together with their parent share its deleted_at, so they are
+// hidden here: restoring the parent brings them back.
+pub async fn list_trash(
+    pool: &PgPool,
+    user_id: i64,
+    retention_days: i64,
+) -> Result<Vec<TrashItem>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            t.item_type as "item_type!",
+            t.id as "id!",
+            t.name as "name!",
+            t.kit_id as "kit_id!",
+            t.kit_name as "kit_name!",
+            (t.deleted_at AT TIME ZONE 'UTC') as "deleted_at!: chrono::NaiveDateTime",
+            ((t.deleted_at + $2::BIGINT * INTERVAL '1 day') AT TIME ZONE 'UTC')
+                as "purge_at!: chrono::NaiveDateTime"
+        FROM (
+            SELECT 'kit' AS item_type, k.id, k.name::TEXT AS name, k.id AS kit_id,
+                   k.name::TEXT AS kit_name, k.deleted_at
+            FROM kits k
+            WHERE k.user_id = $1 AND k.deleted_at IS NOT NULL
+
+            UNION ALL
+
+            SELECT 'runner', r.id, r.name, r.kit_id, k.name::TEXT, r.deleted_at
+            FROM runners r
+            JOIN kits k ON k.id = r.kit_id
+            WHERE EXISTS (
+                SELECT 1 FROM kit_members m
+                WHERE m.kit_id = r.kit_id
+                  AND m.user_id = $1
+                  AND m.role IN ('owner', 'editor')
+            )
+              AND r.deleted_at IS NOT NULL
+              AND k.deleted_at IS DISTINCT FROM r.deleted_at
+
+            UNION ALL
+
+            SELECT 'sub_assembly', sa.id, sa.name, sa.kit_id, k.name::TEXT, sa.deleted_at
+            FROM sub_assemblies sa
+            JOIN kits k ON k.id = sa.kit_id
+            WHERE EXISTS (
+                SELECT 1 FROM kit_members m
+                WHERE m.kit_id = sa.kit_id
+                  AND m.user_id = $1
+                  AND m.role IN ('owner', 'editor')
+            )
+              AND sa.deleted_at IS NOT NULL
+              AND k.deleted_at IS DISTINCT FROM sa.deleted_at
+
+            UNION ALL
+
+            SELECT 'kit_part', kp.id, COALESCE(kp.code, ''), kp.kit_id, k.name::TEXT, kp.deleted_at
+            FROM kit_parts kp
+            JOIN kits k ON k.id = kp.kit_id
+            JOIN sub_assemblies sa ON sa.id = kp.sub_assembly_id
+            WHERE EXISTS (
+                SELECT 1 FROM kit_members m
+                WHERE m.kit_id = kp.kit_id
+                  AND m.user_id = $1
+                  AND m.role IN ('owner', 'editor')
+            )
+              AND kp.deleted_at IS NOT NULL
+              AND k.deleted_at IS DISTINCT FROM kp.deleted_at
+              AND sa.deleted_at IS DISTINCT FROM kp.deleted_at
+        ) t
+        ORDER BY t.deleted_at DESC, t.id DESC
+        "#,
+        user_id,
+        retention_days
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut items = Vec::with_capacity(rows.len());
+    for row in rows {
+        let item_type = TrashItemType::from_str(&row.item_type).map_err(Error::Protocol)?;
+        items.push(TrashItem {
+            item_type,
+            id: row.id,
+            name: row.name,
+            kit_id: row.kit_id,
+            kit_name: row.kit_name,
+            deleted_at: row.deleted_at,
+            purge_at: row.purge_at,
+        });
+    }
+
+    Ok(items)
+}
+
+// --- RESTORE ---
+// คืนค่ารายการ พร้อมลูกที่ถูกลบไปด้วย timestamp เดียวกัน
+pub async fn restore_item(
+    pool: &PgPool,
+    user_id: i64,
+    item_type: TrashItemType,
+    id: i64,
+) -> Result<(), RestoreError> {
+    let mut tx = pool.begin().await?;
+
+    match item_type {
+        TrashItemType::Kit => {
+            let rec = sqlx::query!(
+                r#"
+                SELECT deleted_at as "deleted_at!"
+                FROM kits
+                WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+                "#,
+                id,
+                user_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE kits SET deleted_at = NULL, updated_at = NOW() WHERE id = $1",
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE kit_part_requirements
+                SET deleted_at = NULL
+                WHERE deleted_at = $2
+                  AND kit_part_id IN (SELECT id FROM kit_parts WHERE kit_id = $1)
+                "#,
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE kit_parts SET deleted_at = NULL WHERE kit_id = $1 AND deleted_at = $2",
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE sub_assemblies SET deleted_at = NULL WHERE kit_id = $1 AND deleted_at = $2",
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE runners SET deleted_at = NULL WHERE kit_id = $1 AND deleted_at = $2",
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        TrashItemType::Runner => {
+            let rec = sqlx::query!(
+                r#"
+                SELECT r.deleted_at as "deleted_at!", k.deleted_at as kit_deleted_at
+                FROM runners r
+                JOIN kits k ON k.id = r.kit_id
+                WHERE r.id = $1
+                  AND r.deleted_at IS NOT NULL
+                  AND EXISTS (
+                      SELECT 1 FROM kit_members m
+                      WHERE m.kit_id = r.kit_id
+                        AND m.user_id = $2
+                        AND m.role IN ('owner', 'editor')
+                  )
+                "#,
+                id,
+                user_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if rec.kit_deleted_at.is_some() {
+                return Err(RestoreError::ParentInTrash(TrashItemType::Kit));
+            }
+
+            sqlx::query!(
+                "UPDATE runners SET deleted_at = NULL, updated_at = NOW() WHERE id = $1",
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE kit_part_requirements SET deleted_at = NULL WHERE runner_id = $1 AND deleted_at = $2",
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        TrashItemType::SubAssembly => {
+            let rec = sqlx::query!(
+                r#"
+                SELECT sa.deleted_at as "deleted_at!", k.deleted_at as kit_deleted_at
+                FROM sub_assemblies sa
+                JOIN kits k ON k.id = sa.kit_id
+                WHERE sa.id = $1
+                  AND sa.deleted_at IS NOT NULL
+                  AND EXISTS (
+                      SELECT 1 FROM kit_members m
+                      WHERE m.kit_id = sa.kit_id
+                        AND m.user_id = $2
+                        AND m.role IN ('owner', 'editor')
+                  )
+                "#,
+                id,
+                user_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if rec.kit_deleted_at.is_some() {
+                return Err(RestoreError::ParentInTrash(TrashItemType::Kit));
+            }
+
+            sqlx::query!(
+                "UPDATE sub_assemblies SET deleted_at = NULL, updated_at = NOW() WHERE id = $1",
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE kit_part_requirements
+                SET deleted_at = NULL
+                WHERE deleted_at = $2
+                  AND kit_part_id IN (SELECT id FROM kit_parts WHERE sub_assembly_id = $1)
+                "#,
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE kit_parts SET deleted_at = NULL WHERE sub_assembly_id = $1 AND deleted_at = $2",
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        TrashItemType::KitPart => {
+            let rec = sqlx::query!(
+                r#"
+                SELECT
+                    kp.deleted_at as "deleted_at!",
+                    k.deleted_at as kit_deleted_at,
+                    sa.deleted_at as sub_assembly_deleted_at
+                FROM kit_parts kp
+                JOIN kits k ON k.id = kp.kit_id
+                JOIN sub_assemblies sa ON sa.id = kp.sub_assembly_id
+                WHERE kp.id = $1
+                  AND kp.deleted_at IS NOT NULL
+                  AND EXISTS (
+                      SELECT 1 FROM kit_members m
+                      WHERE m.kit_id = kp.kit_id
+                        AND m.user_id = $2
+                        AND m.role IN ('owner', 'editor')
+                  )
+                "#,
+                id,
+                user_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if rec.kit_deleted_at.is_some() {
+                return Err(RestoreError::ParentInTrash(TrashItemType::Kit));
+            }
+            if rec.sub_assembly_deleted_at.is_some() {
+                return Err(RestoreError::ParentInTrash(TrashItemType::SubAssembly));
+            }
+
+            sqlx::query!(
+                "UPDATE kit_parts SET deleted_at = NULL, updated_at = NOW() WHERE id = $1",
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE kit_part_requirements SET deleted_at = NULL WHERE kit_part_id = $1 AND deleted_at = $2",
+                id,
+                rec.deleted_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    // requirement ที่ยังไม่ตัดกลับมาแล้ว runner อาจไม่ used อีกต่อไป
+    let runner_ids = get_affected_runner_ids(&mut tx, item_type, id).await?;
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// runner ที่มี requirement อยู่ใต้รายการนี้ (ใช้ refresh is_used หลัง restore / purge)
+async fn get_affected_runner_ids(
+    conn: &mut PgConnection,
+    item_type: TrashItemType,
+    id: i64,
+) -> Result<Vec<i64>, Error> {
+    match item_type {
+        TrashItemType::Kit => {
+            sqlx::query_scalar!(
+                r#"SELECT DISTINCT runner_id as "runner_id!: i64" FROM kit_part_requirements WHERE kit_id = $1"#,
+                id
+            )
+            .fetch_all(conn)
+            .await
+        }
+        TrashItemType::Runner => Ok(vec![id]),
+        TrashItemType::SubAssembly => {
+            sqlx::query_scalar!(
+                r#"
+                SELECT DISTINCT req.runner_id as "runner_id!: i64"
+                FROM kit_part_requirements req
+                JOIN kit_parts kp ON kp.id = req.kit_part_id
+                WHERE kp.sub_assembly_id = $1
+                "#,
+                id
+            )
+            .fetch_all(conn)
+            .await
+        }
+        TrashItemType::KitPart => {
+            sqlx::query_scalar!(
+                r#"SELECT DISTINCT runner_id as "runner_id!: i64" FROM kit_part_requirements WHERE kit_part_id = $1"#,
+                id
+            )
+            .fetch_all(conn)
+            .await
+        }
+    }
+}
+
+// --- PURGE (ลบถาวร) ---
+// FK ON DELETE CASCADE จะลบลูกทั้งหมดให้เอง
+// ลูกที่ถูกลบพร้อม parent ลบแยกไม่ได้ (ไม่อยู่ใน list_trash เหมือนกัน) → RowNotFound
+// คืน path รูป runner ที่หายไปพร้อมการลบ ให้ caller ลบไฟล์ใน upload_dir ทิ้ง
+pub async fn purge_item(
+    pool: &PgPool,
+    user_id: i64,
+    item_type: TrashItemType,
+    id: i64,
+) -> Result<Vec<String>, Error> {
+    let mut tx = pool.begin().await?;
+
+    let image_paths = match item_type {
+        TrashItemType::Kit => {
+            sqlx::query_scalar!(
+                r#"SELECT image_path as "image_path!" FROM runners WHERE kit_id = $1 AND image_path IS NOT NULL"#,
+                id
+            )
+            .fetch_all(&mut *tx)
+            .await?
+        }
+        TrashItemType::Runner => {
+            sqlx::query_scalar!(
+                r#"SELECT image_path as "image_path!" FROM runners WHERE id = $1 AND image_path IS NOT NULL"#,
+                id
+            )
+            .fetch_all(&mut *tx)
+            .await?
+        }
+        TrashItemType::SubAssembly | TrashItemType::KitPart => Vec::new(),
+    };
+    let runner_ids = get_affected_runner_ids(&mut tx, item_type, id).await?;
+
+    let result = match item_type {
+        TrashItemType::Kit => {
+            sqlx::query!(
+                "DELETE FROM kits WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL",
+                id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await?
+        }
+        TrashItemType::Runner => {
+            sqlx::query!(
+                r#"
+                DELETE FROM runners r
+                USING kits k
+                WHERE r.id = $1
+                  AND k.id = r.kit_id
+                  AND r.deleted_at IS NOT NULL
+                  AND k.deleted_at IS DISTINCT FROM r.deleted_at
+                  AND EXISTS (
+                      SELECT 1 FROM kit_members m
+                      WHERE m.kit_id = r.kit_id
+                        AND m.user_id = $2
+                        AND m.role IN ('owner', 'editor')
+                  )
+                "#,
+                id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await?
+        }
+        TrashItemType::SubAssembly => {
+            sqlx::query!(
+                r#"
+                DELETE FROM sub_assemblies sa
+                USING kits k
+                WHERE sa.id = $1
+                  AND k.id = sa.kit_id
+                  AND sa.deleted_at IS NOT NULL
+                  AND k.deleted_at IS DISTINCT FROM sa.deleted_at
+                  AND EXISTS (
+                      SELECT 1 FROM kit_members m
+                      WHERE m.kit_id = sa.kit_id
+                        AND m.user_id = $2
+                        AND m.role IN ('owner', 'editor')
+                  )
+                "#,
+                id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await?
+        }
+        TrashItemType::KitPart => {
+            sqlx::query!(
+                r#"
+                DELETE FROM kit_parts kp
+                USING kits k, sub_assemblies sa
+                WHERE kp.id = $1
+                  AND k.id = kp.kit_id
+                  AND sa.id = kp.sub_assembly_id
+                  AND kp.deleted_at IS NOT NULL
+                  AND k.deleted_at IS DISTINCT FROM kp.deleted_at
+                  AND sa.deleted_at IS DISTINCT FROM kp.deleted_at
+                  AND EXISTS (
+                      SELECT 1 FROM kit_members m
+                      WHERE m.kit_id = kp.kit_id
+                        AND m.user_id = $2
+                        AND m.role IN ('owner', 'editor')
+                  )
+                "#,
+                id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await?
+        }
+    };
+
+    if result.rows_affected() == 0 {
+        return Err(Error::RowNotFound);
+    }
+    refresh_runners_is_used(&mut tx, &runner_ids).await?;
+
+    tx.commit().await?;
+    Ok(image_paths)
+}
+
+// ล้างถังขยะทั้งหมดของผู้ใช้ คืนค่าจำนวนรายการที่ลบ + path รูป runner ที่ต้องลบไฟล์
+// ลูกที่ถูกลบพร้อม parent (deleted_at เดียวกัน) ไม่อยู่ในรายการของ list_trash → ไม่ลบตรงนี้
+// (kit ของเราเองลบลูกให้ผ่าน CASCADE, kit ของคนอื่นต้องรอ owner กู้คืน/ล้างเอง)
+pub async fn empty_trash(pool: &PgPool, user_id: i64) -> Result<(u64, Vec<String>), Error> {
+    let mut tx = pool.begin().await?;
+    let mut total: u64 = 0;
+
+    let image_paths = sqlx::query_scalar!(
+        r#"
+        SELECT r.image_path as "image_path!"
+        FROM runners r
+        JOIN kits k ON k.id = r.kit_id
+        WHERE r.image_path IS NOT NULL
+          AND (
+              (k.user_id = $1 AND k.deleted_at IS NOT NULL)
+              OR (
+                  r.deleted_at IS NOT NULL
+                  AND k.deleted_at IS DISTINCT FROM r.deleted_at
+                  AND EXISTS (
+                      SELECT 1 FROM kit_members m
+                      WHERE m.kit_id = r.kit_id
+                        AND m.user_id = $1
+                        AND m.role IN ('owner', 'editor')
+                  )
+              )
+          )
+        "#,
+        user_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    total += sqlx::query!(
+        "DELETE FROM kits WHERE user_id = $1 AND deleted_at IS NOT NULL",
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    total += sqlx::query!(
+        r#"
+        DELETE FROM sub_assemblies sa
+        USING kits k
+        WHERE k.id = sa.kit_id
+          AND sa.deleted_at IS NOT NULL
+          AND k.deleted_at IS DISTINCT FROM sa.deleted_at
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = sa.kit_id
+                AND m.user_id = $1
+                AND m.role IN ('owner', 'editor')
+          )
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    total += sqlx::query!(
+        r#"
+        DELETE FROM kit_parts kp
+        USING kits k, sub_assemblies sa
+        WHERE k.id = kp.kit_id
+          AND sa.id = kp.sub_assembly_id
+          AND kp.deleted_at IS NOT NULL
+          AND k.deleted_at IS DISTINCT FROM kp.deleted_at
+          AND sa.deleted_at IS DISTINCT FROM kp.deleted_at
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = kp.kit_id
+                AND m.user_id = $1
+                AND m.role IN ('owner', 'editor')
+          )
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    total += sqlx::query!(
+        r#"
+        DELETE FROM runners r
+        USING kits k
+        WHERE k.id = r.kit_id
+          AND r.deleted_at IS NOT NULL
+          AND k.deleted_at IS DISTINCT FROM r.deleted_at
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = r.kit_id
+                AND m.user_id = $1
+                AND m.role IN ('owner', 'editor')
+          )
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query!(
+        r#"
+        DELETE FROM kit_part_requirements req
+        USING kits k, runners r, kit_parts kp
+        WHERE k.id = req.kit_id
+          AND r.id = req.runner_id
+          AND kp.id = req.kit_part_id
+          AND req.deleted_at IS NOT NULL
+          AND k.deleted_at IS DISTINCT FROM req.deleted_at
+          AND r.deleted_at IS DISTINCT FROM req.deleted_at
+          AND kp.deleted_at IS DISTINCT FROM req.deleted_at
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = req.kit_id
+                AND m.user_id = $1
+                AND m.role IN ('owner', 'editor')
+          )
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok((total, image_paths))
+}
+
+// ใช้โดย background task ใน main.rs: ลบถาวรทุกรายการที่อยู่ในถังขยะนานเกิน retention
+// คืนจำนวนรายการที่ลบ + path รูป runner ที่ต้องลบไฟล์
+pub async fn purge_expired(
+    pool: &PgPool,
+    retention_days: i64,
+) -> Result<(u64, Vec<String>), Error> {
+    let mut tx = pool.begin().await?;
+    let mut total: u64 = 0;
+
+    let image_paths = sqlx::query_scalar!(
+        r#"
+        SELECT r.image_path as "image_path!"
+        FROM runners r
+        WHERE r.image_path IS NOT NULL
+          AND (
+              r.kit_id IN (
+                  SELECT id FROM kits
+                  WHERE deleted_at < NOW() - $1::BIGINT * INTERVAL '1 day'
+              )
+              OR r.deleted_at < NOW() - $1::BIGINT * INTERVAL '1 day'
+          )
+        "#,
+        retention_days
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    total += sqlx::query!(
+        "DELETE FROM kits WHERE deleted_at < NOW() - $1::BIGINT * INTERVAL '1 day'",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    total += sqlx::query!(
+        "DELETE FROM sub_assemblies WHERE deleted_at < NOW() - $1::BIGINT * INTERVAL '1 day'",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    total += sqlx::query!(
+        "DELETE FROM kit_parts WHERE deleted_at < NOW() - $1::BIGINT * INTERVAL '1 day'",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    total += sqlx::query!(
+        "DELETE FROM runners WHERE deleted_at < NOW() - $1::BIGINT * INTERVAL '1 day'",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query!(
+        "DELETE FROM kit_part_requirements WHERE deleted_at < NOW() - $1::BIGINT * INTERVAL '1 day'",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok((total, image_paths))
+}
@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+
+use sqlx::{Error, PgConnection, PgPool};
+
+use crate::model::workflow::{
+    KitPartStage, KitStageProgress, StageProgress, StageTransition, WorkflowStage, DEFAULT_STAGES,
+};
+use crate::repository::reference::{
+    ensure_kit_writable, ensure_stage_for_kit, get_kit_part_kit_id, ReferenceError,
+};
+
+#[derive(Debug)]
+pub enum StageError {
+    NotFound,
+    InUse(i64),      // 👈 จำนวน part (รวมในถังขยะ) ที่ยังอยู่ขั้นนี้ → 409
+    Invalid(String), // 👈 เช่น order ไม่ครบทุก stage → 400
+    Db(Error),
+}
+
+impl From<Error> for StageError {
+    fn from(e: Error) -> Self {
+        StageError::Db(e)
+    }
+}
+
+// user ที่ยังไม่มี stage เลย → ใส่ขั้นเริ่มต้นให้
+async fn ensure_default_stages(conn: &mut PgConnection, user_id: i64) -> Result<(), Error> {
+    let names: Vec<String> = DEFAULT_STAGES.iter().map(|s| s.to_string()).collect();
+    let positions: Vec<i32> = (0..names.len() as i32).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO workflow_stages (user_id, name, position)
+        SELECT $1, name, position
+        FROM UNNEST($2::TEXT[], $3::INT4[]) AS s(name, position)
+        WHERE NOT EXISTS (SELECT 1 FROM workflow_stages WHERE user_id = $1)
+        ON CONFLICT (user_id, name) DO NOTHING
+        "#,
+        user_id,
+        &names,
+        &positions
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+async fn fetch_stages(conn: &mut PgConnection, user_id: i64) -> Result<Vec<WorkflowStage>, Error> {
+    sqlx::query_as!(
+        WorkflowStage,
+        r#"
+        SELECT
+            id,
+            name,
+            position,
+            user_id,
+            (created_at AT TIME ZONE 'UTC') as "created_at!: chrono::NaiveDateTime",
+            (updated_at AT TIME ZONE 'UTC') as "updated_at!: chrono::NaiveDateTime"
+        FROM workflow_stages
+        WHERE user_id = $1
+        ORDER BY position, id
+        "#,
+        user_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+// เขียน position ใหม่เป็น 0..n ตามลำดับ ids
+async fn write_order(conn: &mut PgConnection, user_id: i64, ids: &[i64]) -> Result<(), Error> {
+    let positions: Vec<i32> = (0..ids.len() as i32).collect();
+    sqlx::query!(
+        r#"
+        UPDATE workflow_stages s
+        SET position = o.position, updated_at = NOW()
+        FROM UNNEST($2::BIGINT[], $3::INT4[]) AS o(id, position)
+        WHERE s.id = o.id AND s.user_id = $1 AND s.position <> o.position
+        "#,
+        user_id,
+        ids,
+        &positions
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// ย้าย stage_id ไปอยู่ที่ position (เกินช่วง = ท้ายสุด)
+fn move_to(ids: &mut Vec<i64>, stage_id: i64, position: i32) {
+    ids.retain(|id| *id != stage_id);
+    let index = (position.max(0) as usize).min(ids.len());
+    ids.insert(index, stage_id);
+}
+
+// GET /workflow_stages
+pub async fn get_stages(pool: &PgPool, user_id: i64) -> Result<Vec<WorkflowStage>, Error> {
+    let mut conn = pool.acquire().await?;
+    ensure_default_stages(&mut conn, user_id).await?;
+    fetch_stages(&mut conn, user_id).await
+}
+
+pub async fn create_stage(
+    pool: &PgPool,
+    user_id: i64,
+    name: &str,
+    position: Option<i32>,
+) -> Result<WorkflowStage, Error> {
+    let mut tx = pool.begin().await?;
+    ensure_default_stages(&mut tx, user_id).await?;
+
+    let stage_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO workflow_stages (user_id, name, position)
+        VALUES ($1, $2, (SELECT COALESCE(MAX(position) + 1, 0) FROM workflow_stages WHERE user_id = $1))
+        RETURNING id
+        "#,
+        user_id,
+        name
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let Some(position) = position {
+        let mut ids: Vec<i64> = fetch_stages(&mut tx, user_id)
+            .await?
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        move_to(&mut ids, stage_id, position);
+        write_order(&mut tx, user_id, &ids).await?;
+    }
+
+    let stage = fetch_stages(&mut tx, user_id)
+        .await?
+        .into_iter()
+        .find(|s| s.id == stage_id)
+        .ok_or(Error::RowNotFound)?;
+    tx.commit().await?;
+    Ok(stage)
+}
+
+pub async fn update_stage(
+    pool: &PgPool,
+    stage_id: i64,
+    user_id: i64,
+    name: Option<&str>,
+    position: Option<i32>,
+) -> Result<WorkflowStage, Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query_scalar!(
+        r#"
+        UPDATE workflow_stages
+        SET name = COALESCE($3, name), updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING id
+        "#,
+        stage_id,
+        user_id,
+        name
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let stages = fetch_stages(&mut tx, user_id).await?;
+    if let Some(position) = position {
+        let mut ids: Vec<i64> = stages.iter().map(|s| s.id).collect();
+        move_to(&mut ids, stage_id, position);
+        write_order(&mut tx, user_id, &ids).await?;
+    }
+
+    let stage = fetch_stages(&mut tx, user_id)
+        .await?
+        .into_iter()
+        .find(|s| s.id == stage_id)
+        .ok_or(Error::RowNotFound)?;
+    tx.commit().await?;
+    Ok(stage)
+}
+
+// PUT /workflow_stages/order
+pub async fn reorder_stages(
+    pool: &PgPool,
+    user_id: i64,
+    stage_ids: &[i64],
+) -> Result<Vec<WorkflowStage>, StageError> {
+    let mut tx = pool.begin().await?;
+
+    let mut current: Vec<i64> = fetch_stages(&mut tx, user_id)
+        .await?
+        .iter()
+        .map(|s| s.id)
+        .collect();
+    let mut requested = stage_ids.to_vec();
+    current.sort_unstable();
+    requested.sort_unstable();
+    if current != requested {
+        return Err(StageError::Invalid(
+            "stage_ids must list every stage exactly once".to_string(),
+        ));
+    }
+
+    write_order(&mut tx, user_id, stage_ids).await?;
+    let stages = fetch_stages(&mut tx, user_id).await?;
+    tx.commit().await?;
+    Ok(stages)
+}
+
+// ลบได้เฉพาะ stage ที่ไม่มี part อยู่ (รวม part ในถังขยะ เพราะกู้คืนได้)
+pub async fn delete_stage(pool: &PgPool, stage_id: i64, user_id: i64) -> Result<(), StageError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query_scalar!(
+        "SELECT id FROM workflow_stages WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        stage_id,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(StageError::NotFound)?;
+
+    let in_use = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM kit_parts WHERE stage_id = $1"#,
+        stage_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if in_use > 0 {
+        return Err(StageError::InUse(in_use));
+    }
+
+    sqlx::query!("DELETE FROM workflow_stages WHERE id = $1", stage_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let ids: Vec<i64> = fetch_stages(&mut tx, user_id)
+        .await?
+        .iter()
+        .map(|s| s.id)
+        .collect();
+    write_order(&mut tx, user_id, &ids).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// ขั้นปัจจุบันของหลาย kit part พร้อมกัน
+pub async fn get_kit_part_stages(
+    pool: &PgPool,
+    kit_part_ids: &[i64],
+) -> Result<HashMap<i64, KitPartStage>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            kp.id as kit_part_id,
+            s.id as stage_id,
+            s.name,
+            s.position,
+            (kp.stage_updated_at AT TIME ZONE 'UTC') as "updated_at?: chrono::NaiveDateTime"
+        FROM kit_parts kp
+        JOIN workflow_stages s ON s.id = kp.stage_id
+        WHERE kp.id = ANY($1)
+        "#,
+        kit_part_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.kit_part_id,
+                KitPartStage {
+                    stage_id: row.stage_id,
+                    name: row.name,
+                    position: row.position,
+                    updated_at: row.updated_at,
+                },
+            )
+        })
+        .collect())
+}
+
+// ย้าย part ไปขั้น stage_id พร้อมบันทึกประวัติ (part ที่อยู่ขั้นนั้นอยู่แล้วไม่นับ)
+// เข้าขั้นใดก็ตาม = ตัดออกจาก runner แล้ว จึง set is_cut ให้ด้วย
+pub(crate) async fn apply_stage(
+    conn: &mut PgConnection,
+    kit_part_ids: &[i64],
+    stage_id: Option<i64>,
+    user_id: i64,
+    advance_only: bool,
+) -> Result<u64, Error> {
+    let result = sqlx::query!(
+        r#"
+        WITH target AS (
+            SELECT id, name, position FROM workflow_stages WHERE id = $2
+        ),
+        moved AS (
+            SELECT kp.id, kp.stage_id as from_stage_id, fs.name as from_stage_name
+            FROM kit_parts kp
+            LEFT JOIN workflow_stages fs ON fs.id = kp.stage_id
+            WHERE kp.id = ANY($1)
+              AND kp.deleted_at IS NULL
+              AND kp.stage_id IS DISTINCT FROM $2
+              AND (
+                  NOT $4
+                  OR kp.stage_id IS NULL
+                  OR fs.position < (SELECT position FROM target)
+              )
+            FOR UPDATE OF kp
+        ),
+        updated AS (
+            UPDATE kit_parts kp
+            SET
+                stage_id = $2,
+                stage_updated_at = NOW(),
+                is_cut = CASE WHEN $2::BIGINT IS NULL THEN kp.is_cut ELSE TRUE END,
+                cut_at = CASE WHEN $2::BIGINT IS NULL THEN kp.cut_at ELSE COALESCE(kp.cut_at, NOW()) END,
+                updated_by = $3,
+                updated_at = NOW()
+            FROM moved
+            WHERE kp.id = moved.id
+            RETURNING kp.id
+        )
+        INSERT INTO kit_part_stage_history (
+            kit_part_id, from_stage_id, from_stage_name, to_stage_id, to_stage_name, changed_by
+        )
+        SELECT updated.id, moved.from_stage_id, moved.from_stage_name, $2, (SELECT name FROM target), $3
+        FROM updated
+        JOIN moved ON moved.id = updated.id
+        "#,
+        kit_part_ids,
+        stage_id,
+        user_id,
+        advance_only
+    )
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// PATCH /kit_parts/:id/stage
+pub async fn set_kit_part_stage(
+    pool: &PgPool,
+    kit_part_id: i64,
+    user_id: i64,
+    stage_id: Option<i64>,
+) -> Result<Option<KitPartStage>, ReferenceError> {
+    let mut tx = pool.begin().await?;
+    let kit_id = get_kit_part_kit_id(&mut tx, user_id, kit_part_id).await?;
+    ensure_kit_writable(&mut tx, user_id, kit_id).await?;
+    if let Some(stage_id) = stage_id {
+        ensure_stage_for_kit(&mut tx, kit_id, stage_id).await?;
+    }
+
+    apply_stage(&mut tx, &[kit_part_id], stage_id, user_id, false).await?;
+    tx.commit().await?;
+
+    Ok(get_kit_part_stages(pool, &[kit_part_id])
+        .await?
+        .remove(&kit_part_id))
+}
+
+// PATCH /sub_assemblies/:id/stage — ทุก part (ที่ไม่อยู่ในถังขยะ) ของ sub-assembly
+pub async fn set_sub_assembly_stage(
+    pool: &PgPool,
+    sub_assembly_id: i64,
+    user_id: i64,
+    stage_id: Option<i64>,
+    advance_only: bool,
+) -> Result<u64, ReferenceError> {
+    let mut tx = pool.begin().await?;
+    let kit_id = sqlx::query_scalar!(
+        r#"
+        SELECT sa.kit_id as "kit_id!: i64"
+        FROM sub_assemblies sa
+        WHERE sa.id = $1
+          AND sa.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = sa.kit_id AND m.user_id = $2
+          )
+        "#,
+        sub_assembly_id,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ReferenceError::NotFound("Sub-assembly not found".to_string()))?;
+    ensure_kit_writable(&mut tx, user_id, kit_id).await?;
+    if let Some(stage_id) = stage_id {
+        ensure_stage_for_kit(&mut tx, kit_id, stage_id).await?;
+    }
+
+    let kit_part_ids = sqlx::query_scalar!(
+        "SELECT id FROM kit_parts WHERE sub_assembly_id = $1 AND deleted_at IS NULL",
+        sub_assembly_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let updated = apply_stage(&mut tx, &kit_part_ids, stage_id, user_id, advance_only).await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+// GET /kit_parts/:id/stage_history (ใหม่สุดก่อน)
+pub async fn get_stage_history(
+    pool: &PgPool,
+    kit_part_id: i64,
+    user_id: i64,
+) -> Result<Vec<StageTransition>, ReferenceError> {
+    let mut conn = pool.acquire().await?;
+    get_kit_part_kit_id(&mut conn, user_id, kit_part_id).await?;
+
+    Ok(sqlx::query_as!(
+        StageTransition,
+        r#"
+        SELECT
+            id,
+            kit_part_id,
+            from_stage_id,
+            from_stage_name,
+            to_stage_id,
+            to_stage_name,
+            changed_by,
+            (changed_at AT TIME ZONE 'UTC') as "changed_at!: chrono::NaiveDateTime"
+        FROM kit_part_stage_history
+        WHERE kit_part_id = $1
+        ORDER BY changed_at DESC, id DESC
+        "#,
+        kit_part_id
+    )
+    .fetch_all(&mut *conn)
+    .await?)
+}
+
+// GET /kits/:id/stage_progress — นับ part ตาม workflow ของ owner
+pub async fn get_kit_stage_progress(
+    pool: &PgPool,
+    kit_id: i64,
+    user_id: i64,
+    sub_assembly_id: Option<i64>,
+) -> Result<KitStageProgress, Error> {
+    let mut conn = pool.acquire().await?;
+
+    // ไม่ใช่สมาชิก / kit อยู่ในถังขยะ → RowNotFound
+    let owner_id = sqlx::query_scalar!(
+        r#"
+        SELECT owner.user_id
+        FROM kits k
+        JOIN kit_members owner ON owner.kit_id = k.id AND owner.role = 'owner'
+        WHERE k.id = $1
+          AND k.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1 FROM kit_members m
+              WHERE m.kit_id = k.id AND m.user_id = $2
+          )
+        "#,
+        kit_id,
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    ensure_default_stages(&mut conn, owner_id).await?;
+    let stages = fetch_stages(&mut conn, owner_id).await?;
+
+    let counts = sqlx::query!(
+        r#"
+        SELECT kp.stage_id, COUNT(*) as "count!: i64"
+        FROM kit_parts kp
+        WHERE kp.kit_id = $1
+          AND kp.deleted_at IS NULL
+          AND ($2::BIGINT IS NULL OR kp.sub_assembly_id = $2)
+        GROUP BY kp.stage_id
+        "#,
+        kit_id,
+        sub_assembly_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let total_parts: i64 = counts.iter().map(|row| row.count).sum();
+    let unstaged_count = counts
+        .iter()
+        .filter(|row| row.stage_id.is_none())
+        .map(|row| row.count)
+        .sum();
+    let current: HashMap<i64, i64> = counts
+        .iter()
+        .filter_map(|row| row.stage_id.map(|id| (id, row.count)))
+        .collect();
+    let other_stage_count = current
+        .iter()
+        .filter(|(id, _)| !stages.iter().any(|stage| stage.id == **id))
+        .map(|(_, count)| count)
+        .sum();
+
+    // reached = ขั้นนี้ + ทุกขั้นถัดไป (ไล่จากขั้นสุดท้ายย้อนกลับ)
+    let mut reached = 0;
+    let mut progress: Vec<StageProgress> = stages
+        .into_iter()
+        .rev()
+        .map(|stage| {
+            let current_count = current.get(&stage.id).copied().unwrap_or(0);
+            reached += current_count;
+            StageProgress {
+                stage_id: stage.id,
+                name: stage.name,
+                position: stage.position,
+                current_count,
+                reached_count: reached,
+                percent: if total_parts > 0 {
+                    (reached as f64 / total_parts as f64 * 1000.0).round() / 10.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+    progress.reverse();
+
+    Ok(KitStageProgress {
+        kit_id,
+        sub_assembly_id,
+        total_parts,
+        unstaged_count,
+        other_stage_count,
+        stages: progress,
+    })
+}
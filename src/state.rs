@@ -6,4 +6,6 @@ use sqlx::PgPool;
 pub struct AppState {
     pub db_pool: PgPool,
     pub jwt_secret: String,
+    pub trash_retention_days: i64, // 👈 จำนวนวันก่อนลบรายการในถังขยะถาวร
+    pub upload_dir: String,        // 👈 โฟลเดอร์เก็บรูป runner ที่อัปโหลด
 }